@@ -7,6 +7,7 @@ use crate::control::callback;
 use crate::display::camera::Camera2d;
 use crate::display::object::traits::*;
 use crate::display::Scene;
+use crate::display::scene::WheelCapture;
 use crate::system::web::dom;
 use crate::system::web;
 use events::NavigatorEvents;
@@ -30,12 +31,13 @@ pub struct Navigator {
 impl Navigator {
     pub fn new(scene:&Scene, camera:&Camera2d) -> Self {
         let dom                    = scene.dom.root.clone_ref();
+        let wheel_capture          = &scene.mouse.wheel_capture;
         let zoom_speed             = 10.0;
         let min_zoom               = 10.0;
         let max_zoom               = 10000.0;
         let scaled_down_zoom_speed = zoom_speed / 1000.0;
         let (simulator,resize_callback,_events) = Self::start_navigator_events
-            (&dom.into(),camera,min_zoom,max_zoom,scaled_down_zoom_speed);
+            (&dom.into(),wheel_capture,camera,min_zoom,max_zoom,scaled_down_zoom_speed);
         Self {simulator,_events,resize_callback}
     }
 
@@ -49,11 +51,12 @@ impl Navigator {
     }
 
     fn start_navigator_events
-    ( dom        : &dom::WithKnownShape<web::EventTarget>
-    , camera     : &Camera2d
-    , min_zoom   : f32
-    , max_zoom   : f32
-    , zoom_speed : f32
+    ( dom           : &dom::WithKnownShape<web::EventTarget>
+    , wheel_capture : &WheelCapture
+    , camera        : &Camera2d
+    , min_zoom      : f32
+    , max_zoom      : f32
+    , zoom_speed    : f32
     ) -> (physics::inertia::DynSimulator<Vector3>,callback::Handle,NavigatorEvents) {
         let simulator        = Self::create_simulator(&camera);
         let panning_callback = enclose!((dom,camera,mut simulator) move |pan: PanEvent| {
@@ -100,7 +103,9 @@ impl Navigator {
                 position          += direction * zoom_factor;
                 simulator.set_target_value(position);
         });
-        (simulator,resize_callback, NavigatorEvents::new(&dom, panning_callback, zoom_callback, zoom_speed))
+        let events = NavigatorEvents::new
+            (&dom,wheel_capture,panning_callback,zoom_callback,zoom_speed);
+        (simulator,resize_callback,events)
     }
 }
 
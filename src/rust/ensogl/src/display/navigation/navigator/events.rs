@@ -3,6 +3,7 @@ use crate::prelude::*;
 use crate::control::io::mouse;
 use crate::control::io::mouse::MouseManager;
 use crate::control::callback;
+use crate::display::scene::WheelCapture;
 use crate::system::web::IgnoreContextMenuHandle;
 use crate::system::web;
 
@@ -167,6 +168,7 @@ impl NavigatorEventsData {
 pub struct NavigatorEvents {
     data                 : Rc<NavigatorEventsData>,
     mouse_manager        : MouseManager,
+    wheel_capture        : WheelCapture,
     #[derivative(Debug="ignore")]
     mouse_down           : Option<callback::Handle>,
     #[derivative(Debug="ignore")]
@@ -182,10 +184,16 @@ pub struct NavigatorEvents {
 }
 
 impl NavigatorEvents {
-    pub fn new
-    <P,Z>(event_target:&web::dom::WithKnownShape<web::EventTarget>, pan_callback:P, zoom_callback:Z, zoom_speed:f32) -> Self
+    pub fn new<P,Z>
+    ( event_target  : &web::dom::WithKnownShape<web::EventTarget>
+    , wheel_capture : &WheelCapture
+    , pan_callback  : P
+    , zoom_callback : Z
+    , zoom_speed    : f32
+    ) -> Self
     where P : FnPanEvent, Z : FnZoomEvent {
         let mouse_manager        = MouseManager::new(event_target);
+        let wheel_capture        = wheel_capture.clone_ref();
         let pan_callback         = Box::new(pan_callback);
         let zoom_callback        = Box::new(zoom_callback);
         let mouse_move           = default();
@@ -198,6 +206,7 @@ impl NavigatorEvents {
         let mut event_handler = Self {
             data,
             mouse_manager,
+            wheel_capture,
             mouse_down,
             mouse_move,
             mouse_up,
@@ -218,9 +227,14 @@ impl NavigatorEvents {
         self.initialize_mouse_end_event();
     }
 
+    /// The wheel events captured by some component of the scene, see `WheelCapture`, are ignored.
     fn initialize_wheel_zoom(&mut self) {
-        let data     = Rc::downgrade(&self.data);
-        let listener = self.mouse_manager.on_wheel.add(move |event:&mouse::OnWheel| {
+        let data          = Rc::downgrade(&self.data);
+        let wheel_capture = self.wheel_capture.clone_ref();
+        let listener      = self.mouse_manager.on_wheel.add(move |event:&mouse::OnWheel| {
+            if wheel_capture.is_captured() {
+                return
+            }
             event.prevent_default();
             if let Some(data) = data.upgrade() {
                 if event.ctrl_key() {
@@ -280,6 +280,64 @@ mod target_tests {
 
 
 
+// ====================
+// === WheelCapture ===
+// ====================
+
+/// Tracks the components capturing the mouse wheel events, like a scrollable visualization under
+/// the mouse cursor. While the wheel is captured, the scene-wide wheel handlers, like the camera
+/// navigation, should ignore the wheel events, so the component is the only one handling them.
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct WheelCapture {
+    count : Rc<Cell<usize>>,
+}
+
+impl WheelCapture {
+    /// Capture the wheel events until the returned guard is dropped.
+    pub fn capture(&self) -> WheelCaptureGuard {
+        self.count.set(self.count.get() + 1);
+        let count = self.count.clone_ref();
+        WheelCaptureGuard {count}
+    }
+
+    /// Check whether some component captures the wheel events.
+    pub fn is_captured(&self) -> bool {
+        self.count.get() > 0
+    }
+}
+
+/// Keeps the wheel events captured as long as it is alive. See `WheelCapture`.
+#[derive(Debug)]
+pub struct WheelCaptureGuard {
+    count : Rc<Cell<usize>>,
+}
+
+impl Drop for WheelCaptureGuard {
+    fn drop(&mut self) {
+        self.count.set(self.count.get() - 1);
+    }
+}
+
+#[cfg(test)]
+mod wheel_capture_tests {
+    use super::*;
+
+    #[test]
+    fn capturing_until_all_guards_are_dropped() {
+        let wheel_capture = WheelCapture::default();
+        assert!(!wheel_capture.is_captured());
+        let guard1 = wheel_capture.capture();
+        let guard2 = wheel_capture.clone_ref().capture();
+        assert!(wheel_capture.is_captured());
+        drop(guard1);
+        assert!(wheel_capture.is_captured());
+        drop(guard2);
+        assert!(!wheel_capture.is_captured());
+    }
+}
+
+
+
 // =============
 // === Mouse ===
 // =============
@@ -287,6 +345,7 @@ mod target_tests {
 #[derive(Clone,CloneRef,Debug)]
 pub struct Mouse {
     pub mouse_manager : MouseManager,
+    pub wheel_capture : WheelCapture,
     pub last_position : Rc<Cell<Vector2<i32>>>,
     pub position      : Uniform<Vector2<i32>>,
     pub hover_ids     : Uniform<Vector4<u32>>,
@@ -328,8 +387,10 @@ impl Mouse {
         ));
         let on_down = mouse_manager.on_down . add(f!((event) frp.down . emit(event.button())));
         let on_up   = mouse_manager.on_up   . add(f!((event) frp.up   . emit(event.button())));
-        let handles = Rc::new(vec![on_move,on_down,on_up]);
-        Self {mouse_manager,last_position,position,hover_ids,target,handles,frp,scene_frp,logger}
+        let handles       = Rc::new(vec![on_move,on_down,on_up]);
+        let wheel_capture = default();
+        Self {mouse_manager,wheel_capture,last_position,position,hover_ids,target,handles,frp
+             ,scene_frp,logger}
     }
 
     /// Reemits FRP mouse changed position event with the last mouse position value.
@@ -11,4 +11,5 @@ pub use client::Client;
 pub use client::Event;
 pub use client::Notification;
 pub use client::MockAPI as MockClient;
+pub use client::SwappableClient;
 pub use connection::Connection;
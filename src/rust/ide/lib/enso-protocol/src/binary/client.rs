@@ -1,5 +1,5 @@
-//! Module defines LS binary protocol client `API` and its two implementation: `Client` and
-//! `MockClient`.
+//! Module defines LS binary protocol client `API` and its implementations: `Client`,
+//! `SwappableClient` and `MockClient`.
 
 use crate::prelude::*;
 
@@ -206,6 +206,51 @@ impl API for Client {
 
 
 
+// =======================
+// === SwappableClient ===
+// =======================
+
+/// The binary protocol counterpart of `language_server::SwappableClient`, see its documentation.
+#[derive(Clone,Derivative)]
+#[derivative(Debug)]
+pub struct SwappableClient {
+    #[derivative(Debug="ignore")]
+    client : Rc<RefCell<Rc<dyn API>>>,
+}
+
+impl SwappableClient {
+    /// Create a client delegating the calls to the given one.
+    pub fn new(client:Box<dyn API>) -> Self {
+        let client = Rc::new(RefCell::new(client.into()));
+        SwappableClient {client}
+    }
+
+    /// Replace the client which the calls are delegated to.
+    pub fn replace(&self, client:Box<dyn API>) {
+        *self.client.borrow_mut() = client.into();
+    }
+}
+
+impl API for SwappableClient {
+    fn init(&self, client_id:Uuid) -> LocalBoxFuture<FallibleResult<()>> {
+        self.client.borrow().init(client_id)
+    }
+
+    fn write_file(&self, path:&Path, contents:&[u8]) -> LocalBoxFuture<FallibleResult<()>> {
+        self.client.borrow().write_file(path,contents)
+    }
+
+    fn read_file(&self, path:&Path) -> LocalBoxFuture<FallibleResult<Vec<u8>>> {
+        self.client.borrow().read_file(path)
+    }
+
+    fn event_stream(&self) -> LocalBoxStream<Event> {
+        self.client.borrow().event_stream()
+    }
+}
+
+
+
 // =============
 // === Tests ===
 // =============
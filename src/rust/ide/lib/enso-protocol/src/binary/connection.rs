@@ -4,6 +4,7 @@ use crate::prelude::*;
 
 use crate::binary::API;
 use crate::binary::MockClient;
+use crate::binary::SwappableClient;
 
 
 
@@ -31,6 +32,8 @@ pub struct Connection {
     /// LS client that has already initialized protocol.
     #[derivative(Debug="ignore")]
     pub client:Box<dyn API>,
+    /// The handle to the `client`, allowing replacing the underlying client on reconnection.
+    swappable:SwappableClient,
 }
 
 impl Connection {
@@ -38,18 +41,29 @@ impl Connection {
     pub async fn new(client:impl API + 'static, client_id:Uuid) -> FallibleResult<Self> {
         let init_response = client.init(client_id).await;
         init_response.map_err(FailedToInitializeProtocol)?;
-        let client = Box::new(client);
-        Ok (Connection {client_id,client})
+        let swappable = SwappableClient::new(Box::new(client));
+        let client    = Box::new(swappable.clone());
+        Ok (Connection {client_id,client,swappable})
     }
 
     /// Creates a connection which wraps a mock client.
     pub fn new_mock(client:MockClient) -> Connection {
+        let swappable = SwappableClient::new(Box::new(client));
         Connection {
-            client        : Box::new(client),
+            client        : Box::new(swappable.clone()),
+            swappable,
             client_id     : default(),
         }
     }
 
+    /// Replace the client of this connection with a new one, e.g. after the previous connection
+    /// was lost. The session is initialized for the new client using the same client id.
+    pub async fn reconnect(&self, client:Box<dyn API>) -> FallibleResult<()> {
+        client.init(self.client_id).await.map_err(FailedToInitializeProtocol)?;
+        self.swappable.replace(client);
+        Ok(())
+    }
+
     /// Creates a Rc handle to a connection which wraps a mock client.
     pub fn new_mock_rc(client:MockClient) -> Rc<Connection> {
         Rc::new(Self::new_mock(client))
@@ -80,6 +94,7 @@ mod tests {
     use super::*;
 
     use crate::binary::MockClient;
+    use crate::language_server::types::Path;
     use mockall::predicate::*;
     use json_rpc::error::RpcError;
     use futures::task::LocalSpawnExt;
@@ -111,5 +126,35 @@ mod tests {
         pool.spawner().spawn_local(case).unwrap();
         pool.run();
     }
+
+    #[test]
+    fn test_reconnect() {
+        let client_id = Uuid::from_u128(159);
+        let path      = Path::new(default(),&["Main.enso"]);
+        let mock_with = |init:FallibleResult<()>, contents:&str| {
+            let contents = Vec::from(contents.as_bytes());
+            let mut mock = MockClient::new();
+            mock.expect_init().with(eq(client_id)).times(1).return_once(move |_| ready(init));
+            mock.expect_read_file().return_once(move |_| ready(Ok(contents)));
+            mock
+        };
+        let case = async {
+            let connection = Connection::new(mock_with(Ok(()),"old"),client_id).await.unwrap();
+
+            let err    = Err(RpcError::new_remote_error(0,"ErrorMessage").into());
+            let failed = Box::new(mock_with(err,"unused"));
+            assert!(connection.reconnect(failed).await.is_err());
+            let contents = connection.read_file(&path).await.unwrap();
+            assert_eq!(contents,Vec::from("old".as_bytes()));
+
+            let succeeded = Box::new(mock_with(Ok(()),"new"));
+            connection.reconnect(succeeded).await.unwrap();
+            let contents = connection.read_file(&path).await.unwrap();
+            assert_eq!(contents,Vec::from("new".as_bytes()));
+        };
+        let mut pool = futures::executor::LocalPool::new();
+        pool.spawner().spawn_local(case).unwrap();
+        pool.run();
+    }
 }
 
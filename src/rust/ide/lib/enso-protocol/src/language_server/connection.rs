@@ -3,6 +3,7 @@
 use crate::prelude::*;
 
 use crate::language_server::MockClient;
+use crate::language_server::SwappableClient;
 use crate::language_server::API;
 
 use uuid::Uuid;
@@ -39,6 +40,9 @@ pub struct Connection {
     /// LS client that has already initialized protocol.
     #[derivative(Debug="ignore")]
     pub client:Box<dyn API>,
+    /// The handle to the `client`, allowing replacing the underlying client on reconnection.
+    #[derivative(Debug="ignore")]
+    swappable:SwappableClient,
     /// Content roots obtained during initialization. Guaranteed to be non-empty.
     content_roots:Vec<Uuid>,
 }
@@ -53,19 +57,33 @@ impl Connection {
         if content_roots.is_empty() {
             Err(MissingContentRoots.into())
         } else {
-            Ok(Connection {client_id,client,content_roots})
+            let swappable = SwappableClient::new(client);
+            let client    = Box::new(swappable.clone());
+            Ok(Connection {client_id,client,swappable,content_roots})
         }
     }
 
     /// Creates a connection which wraps a mock client.
     pub fn new_mock(client:MockClient) -> Connection {
+        let swappable = SwappableClient::new(Box::new(client));
         Connection {
-            client        : Box::new(client),
+            client        : Box::new(swappable.clone()),
+            swappable,
             client_id     : default(),
             content_roots : vec![default()],
         }
     }
 
+    /// Replace the client of this connection with a new one, e.g. after the previous connection
+    /// was lost. The protocol is initialized for the new client using the same client id, so the
+    /// server may correlate it with the binary connection.
+    pub async fn reconnect(&self, client:Box<dyn API>) -> FallibleResult<()> {
+        let init_response = client.init_protocol_connection(&self.client_id).await;
+        init_response.map_err(|e| FailedToInitializeProtocol(e.into()))?;
+        self.swappable.replace(client);
+        Ok(())
+    }
+
     /// Creates a Rc handle to a connection which wraps a mock client.
     pub fn new_mock_rc(client:MockClient) -> Rc<Connection> {
         Rc::new(Self::new_mock(client))
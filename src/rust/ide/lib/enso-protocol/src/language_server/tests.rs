@@ -343,11 +343,71 @@ fn test_computed_value_update() {
             assert_eq!(update.typename.as_ref().map(|ty| ty.as_str()), Some(typename));
             assert_eq!(update.short_value.as_ref().map(|ty| ty.as_str()), Some(short_value));
             assert!(update.method_call.is_none());
+            assert_eq!(update.payload, ExpressionValueUpdatePayload::Value);
         }
         _ => panic!("Expected Notification::ExpressionValuesComputed"),
     }
 }
 
+#[test]
+fn test_computed_value_update_with_panic() {
+    use json_rpc::Event;
+    use crate::language_server::Notification;
+
+    let context_id   = Uuid::parse_str("b36dea0b-b75a-40cf-aaad-5fcdf29a0573").unwrap();
+    let id           = Uuid::parse_str("d4b540c0-3ef5-487c-9453-df9d3efd351c").unwrap();
+    let message      = "Division by zero.";
+    let notification = json!({
+        "jsonrpc" : "2.0",
+        "method"  : "executionContext/expressionValuesComputed",
+        "params"  :  {
+            "contextId" : context_id,
+            "updates"   : [{
+                "id"         : id,
+                "type"       : null,
+                "shortValue" : null,
+                "methodCall" : null,
+                "payload"    : {"type":"Panic", "message":message, "trace":[id]}
+            }]
+        }
+    });
+
+    let mut fixture = setup_language_server();
+    let mut stream = fixture.client.events();
+    fixture.transport.mock_peer_json_message(notification);
+    fixture.executor.run_until_stalled();
+
+    match stream.expect_next() {
+        Event::Notification(Notification::ExpressionValuesComputed(expression_value_update)) => {
+            let update   = &expression_value_update.updates.first().unwrap();
+            let expected = ExpressionValueUpdatePayload::Panic {
+                message : message.to_string(),
+                trace   : vec![id],
+            };
+            assert_eq!(update.payload, expected);
+        }
+        _ => panic!("Expected Notification::ExpressionValuesComputed"),
+    }
+}
+
+#[test]
+fn test_suggestions_database_modify_update() {
+    let entry = SuggestionEntry::SuggestionEntryAtom {
+        name          : "Atom".to_string(),
+        module        : "Project.Main".to_string(),
+        arguments     : vec![],
+        return_type   : "Atom".to_string(),
+        documentation : Some("An atom.".to_string()),
+    };
+    let update = SuggestionsDatabaseUpdate::Modify {id:12, entry};
+
+    let json = serde_json::to_value(&update).unwrap();
+    assert_eq!(json["modify"]["id"], json!(12));
+    assert_eq!(json["modify"]["entry"]["suggestionEntryAtom"]["name"], json!("Atom"));
+    let deserialized = serde_json::from_value::<SuggestionsDatabaseUpdate>(json).unwrap();
+    assert_eq!(deserialized, update);
+}
+
 #[test]
 fn test_execution_context() {
     let root_id   = uuid::Uuid::parse_str("00000000-0000-0000-0000-000000000000");
@@ -565,3 +625,33 @@ fn test_execution_context() {
         ()
     );
 }
+
+#[test]
+fn test_nested_batch() {
+    let mut fixture = setup_language_server();
+    let root_id     = uuid::Uuid::new_v4();
+    let main        = Path {root_id, segments:vec!["Main.txt".into()]};
+    let client      = &fixture.client as &dyn API;
+    let transport   = &mut fixture.transport;
+    let (mut outer,mut inner) = client.batch(|client| {
+        let outer = client.file_exists(&main);
+        let inner = client.batch(|client| client.delete_file(&main));
+        // The nested batch joins the outer one, so nothing is sent yet.
+        transport.with_mut_data(|data| assert!(data.sent_text_msgs.is_empty()));
+        (outer,inner)
+    });
+
+    let requests = fixture.transport.expect_json_message::<Vec<RequestMessage<Value>>>();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].method, "file/exists");
+    assert_eq!(requests[1].method, "file/delete");
+    let (exists_id,delete_id) = (requests[0].id,requests[1].id);
+    let replies = vec!
+        [ Message::new_success(delete_id,json!(null))
+        , Message::new_success(exists_id,json!({"exists":true}))
+        ];
+    fixture.transport.mock_peer_json_message(replies);
+    fixture.executor.run_until_stalled();
+    assert_eq!(outer.expect_ok(), response::FileExists {exists:true});
+    assert_eq!(inner.expect_ok(), ());
+}
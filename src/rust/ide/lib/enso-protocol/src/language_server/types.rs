@@ -160,6 +160,32 @@ pub struct ExpressionValueUpdate {
     pub typename    : Option<String>,
     pub short_value : Option<String>,
     pub method_call : Option<MethodPointer>,
+    /// The result of the expression's computation. Missing for the servers not reporting errors,
+    /// in which case the expression is assumed to have been computed successfully.
+    #[serde(default)]
+    pub payload     : ExpressionValueUpdatePayload,
+}
+
+/// Describes the result of the expression's computation: a value, a dataflow error or a panic.
+#[derive(Clone,Debug,PartialEq)]
+#[derive(Serialize,Deserialize)]
+#[allow(missing_docs)]
+#[serde(tag="type")]
+pub enum ExpressionValueUpdatePayload {
+    /// The expression was computed to a value.
+    Value,
+    /// The expression evaluated to a dataflow error. The `trace` contains the expressions through
+    /// which the error has been propagated, starting from the one which raised it.
+    DataflowError {trace:Vec<ExpressionId>},
+    /// The computation of the expression panicked. The `trace` contains the expressions through
+    /// which the panic has been propagated, starting from the one which raised it.
+    Panic {message:String, trace:Vec<ExpressionId>},
+}
+
+impl Default for ExpressionValueUpdatePayload {
+    fn default() -> Self {
+        Self::Value
+    }
 }
 
 
@@ -608,6 +634,10 @@ pub enum SuggestionsDatabaseUpdate {
     Remove {
         id : SuggestionEntryId
     },
+    Modify {
+        id    : SuggestionEntryId,
+        entry : SuggestionEntry,
+    },
 }
 
 /// Notification about change in the suggestions database.
@@ -33,6 +33,10 @@ pub enum RpcError {
     #[fail(display = "Received a reply of a wrong type.")]
     MismatchedResponseType,
 
+    /// The request was cancelled before receiving a response.
+    #[fail(display = "Request was cancelled.")]
+    Cancelled,
+
     /// Response timeout.
     #[allow(missing_docs)]
     #[fail(display = "Response timed out after {} ms.", millis)]
@@ -21,6 +21,8 @@ use futures::channel::mpsc::unbounded;
 use futures::channel::mpsc::UnboundedSender;
 use futures::channel::oneshot;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::future::Future;
 use utils::channel;
 
@@ -104,6 +106,76 @@ pub enum Event<N> {
 
 
 
+// ======================
+// === RequestOptions ===
+// ======================
+
+/// Options of a single request, overriding the `Handler`'s defaults.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct RequestOptions {
+    /// The timeout of this request. If not set, the `Handler`'s timeout is used.
+    pub timeout : Option<Duration>,
+}
+
+impl RequestOptions {
+    /// Options with the given timeout.
+    pub fn with_timeout(timeout:Duration) -> Self {
+        RequestOptions {timeout:Some(timeout)}
+    }
+}
+
+
+
+// ==========================
+// === CancellationHandle ===
+// ==========================
+
+/// Handle allowing to cancel the ongoing request.
+///
+/// The cancelled request's future yields `RpcError::Cancelled` and the peer's response to it, if
+/// it ever comes, is ignored.
+#[derive(Debug)]
+pub struct CancellationHandle<Notification> {
+    /// The id of the request.
+    pub id  : Id,
+    handler : WeakHandler<Notification>,
+}
+
+impl<Notification> Clone for CancellationHandle<Notification> {
+    fn clone(&self) -> Self {
+        let id      = self.id;
+        let handler = self.handler.clone();
+        CancellationHandle {id,handler}
+    }
+}
+
+impl<Notification> CancellationHandle<Notification> {
+    /// Cancel the request. Does nothing if the request was already completed.
+    pub fn cancel(&self) {
+        if let Some(handler) = self.handler.upgrade() {
+            handler.cancel_request(self.id);
+        }
+    }
+}
+
+/// Parameters of the notification informing the peer about a cancelled request.
+#[derive(Clone,Copy,Debug,Serialize)]
+struct CancelRequestParams {
+    id : Id,
+}
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The maximum number of abandoned calls remembered by the `Handler`. When exceeded, the oldest
+/// ones are forgotten, and the replies to them, if they ever come, are reported as unexpected.
+pub const MAX_ABANDONED_CALLS:usize = 256;
+
+
+
 // ===================
 // === HandlerData ===
 // ===================
@@ -111,7 +183,7 @@ pub enum Event<N> {
 /// Container that stores Sender's for ongoing calls. Each call identified by
 /// id has its own sender. After reply is received, the call is removed
 /// from this container.
-pub type OngoingCalls = HashMap<Id,oneshot::Sender<ReplyMessage>>;
+pub type OngoingCalls = HashMap<Id,oneshot::Sender<Result<ReplyMessage>>>;
 
 
 
@@ -143,6 +215,13 @@ pub struct HandlerData<Notification> {
     timeout         : Duration,
     /// Ongoing calls.
     ongoing_calls   : OngoingCalls,
+    /// Calls which were cancelled or timed out before receiving the reply. The replies to them
+    /// are ignored. At most `MAX_ABANDONED_CALLS` are remembered, the oldest are forgotten first.
+    abandoned_calls : VecDeque<Id>,
+    /// The method of the notification sent to the peer when a request is cancelled.
+    cancel_method   : Option<&'static str>,
+    /// Requests collected to be sent together as a batch, if the batch was started.
+    batch           : Option<Vec<(Id,String)>>,
     /// Handle to send outgoing events.
     outgoing_events : Option<UnboundedSender<Event<Notification>>>,
     /// Provides identifiers for requests.
@@ -154,7 +233,7 @@ pub struct HandlerData<Notification> {
 
 impl<Notification> {
     /// Inserts a new entry for an ongoing request awaiting reply.
-    pub fn insert_ongoing_request(&mut self, id:Id, sender:oneshot::Sender<ReplyMessage>) {
+    pub fn insert_ongoing_request(&mut self, id:Id, sender:oneshot::Sender<Result<ReplyMessage>>) {
         self.ongoing_calls.insert(id,sender);
     }
 
@@ -164,14 +243,44 @@ impl<Notification> {
     ///
     /// Returns the channel handle for the request, it should be immediately
     /// after notified or dropped.
-    pub fn remove_ongoing_request(&mut self, id:Id) -> Option<oneshot::Sender<ReplyMessage>> {
+    pub fn remove_ongoing_request
+    (&mut self, id:Id) -> Option<oneshot::Sender<Result<ReplyMessage>>> {
         self.ongoing_calls.remove(&id)
     }
 
+    /// Removes the request from the map of ongoing requests and remembers it as abandoned, so the
+    /// peer's reply to it will be ignored.
+    ///
+    /// Returns the channel handle for the request, if it was still ongoing.
+    pub fn abandon_ongoing_request
+    (&mut self, id:Id) -> Option<oneshot::Sender<Result<ReplyMessage>>> {
+        let sender = self.ongoing_calls.remove(&id);
+        if sender.is_some() {
+            if self.abandoned_calls.len() >= MAX_ABANDONED_CALLS {
+                self.abandoned_calls.pop_front();
+            }
+            self.abandoned_calls.push_back(id);
+        }
+        sender
+    }
+
+    /// Checks if the request was abandoned and forgets about it, as only one reply to it is
+    /// expected.
+    pub fn take_abandoned_request(&mut self, id:Id) -> bool {
+        let index = self.abandoned_calls.iter().position(|abandoned| *abandoned == id);
+        index.and_then(|index| self.abandoned_calls.remove(index)).is_some()
+    }
+
+    /// The number of remembered abandoned requests.
+    pub fn abandoned_requests_count(&self) -> usize {
+        self.abandoned_calls.len()
+    }
+
     /// Removes all the ongoing requests. This will be recognized by the `Future`s
     /// as losing connection error.
     pub fn clear_ongoing_requests(&mut self) {
-        self.ongoing_calls.clear()
+        self.ongoing_calls.clear();
+        self.abandoned_calls.clear();
     }
 
     /// Obtains an id for a new request to be made.
@@ -184,6 +293,38 @@ impl<Notification> {
         self.transport.send_text(text)
     }
 
+    /// Starts collecting the requests into a batch. Until the batch is sent with `send_batch`,
+    /// the opened requests are not sent to the peer. Does nothing if the batch is already started.
+    ///
+    /// Returns `true` if this call started the batch. The nested batches are joined with the outer
+    /// one, so only the caller which started the batch should send it.
+    pub fn start_batch(&mut self) -> bool {
+        let is_new = self.batch.is_none();
+        if is_new {
+            self.batch = Some(default());
+        }
+        is_new
+    }
+
+    /// Checks if the requests are currently collected into a batch.
+    pub fn is_batch_started(&self) -> bool {
+        self.batch.is_some()
+    }
+
+    /// Adds the request to the started batch. Returns `false` if the batch was not started, so
+    /// the request should be sent on its own.
+    pub fn add_to_batch(&mut self, id:Id, message_json:&str) -> bool {
+        if let Some(batch) = &mut self.batch {
+            batch.push((id,message_json.to_string()));
+        }
+        self.batch.is_some()
+    }
+
+    /// Finishes the batch and returns the collected requests.
+    pub fn take_batch(&mut self) -> Vec<(Id,String)> {
+        self.batch.take().unwrap_or_default()
+    }
+
     /// Creates a new stream with events from this handler.
     ///
     /// If such stream was already existing, it will be finished (and
@@ -210,6 +351,18 @@ impl<Notification> {
     pub fn set_timeout(&mut self, timeout:Duration) {
         self.timeout = timeout;
     }
+
+    /// The method of the notification sent to the peer when a request is cancelled or timed out.
+    pub fn cancel_method(&self) -> Option<&'static str> {
+        self.cancel_method
+    }
+
+    /// Set the method of the notification sent to the peer when a request is cancelled or timed
+    /// out, e.g. `$/cancelRequest`. The notification's parameters contain the request's `id`. If
+    /// not set, the peer is not notified.
+    pub fn set_cancel_method(&mut self, method:Option<&'static str>) {
+        self.cancel_method = method;
+    }
 }
 } // shared!
 
@@ -239,6 +392,9 @@ impl<Notification> Handler<Notification> {
         let data = HandlerData {
             timeout         : crate::constants::TIMEOUT,
             ongoing_calls   : default(),
+            abandoned_calls : default(),
+            cancel_method   : None,
+            batch           : None,
             id_generator    : IdGenerator::new(),
             transport       : Box::new(transport),
             outgoing_events : None,
@@ -281,27 +437,107 @@ impl<Notification> Handler<Notification> {
     /// `open_request_with_json` docstring for more information.
     pub fn open_request_with_message<Returned:DeserializeOwned>
     (&self, id:Id, message_json:&str) -> impl Future<Output = Result<Returned>> {
-        let (sender, receiver) = oneshot::channel::<ReplyMessage>();
+        self.open_request_with_message_and_options(id,message_json,default())
+    }
+
+    /// Sends a request to the peer and returns a `Future` that shall yield a reply message,
+    /// together with a handle allowing to cancel the request. The given options override the
+    /// handler's defaults for this request only.
+    pub fn open_cancellable_request<In:api::RemoteMethodCall>
+    (&self, input:In, options:RequestOptions)
+    -> (CancellationHandle<Notification>, impl Future<Output = Result<In::Returned>>) {
+        let id      = self.generate_new_id();
+        let message = api::into_request_message(input,id);
+        let serialized_message = serde_json::to_string(&message).unwrap();
+        let handle  = CancellationHandle {id, handler:self.downgrade()};
+        (handle,self.open_request_with_message_and_options(id,&serialized_message,options))
+    }
+
+    /// Version of `open_cancellable_request` taking the method name and JSON input separately.
+    /// See `open_request_with_json` docstring for the reasons.
+    pub fn open_cancellable_request_with_json<Returned:DeserializeOwned>
+    (&self, method_name:&str, input:&serde_json::Value, options:RequestOptions)
+    -> (CancellationHandle<Notification>, impl Future<Output = Result<Returned>>) {
+        let id      = self.generate_new_id();
+        let message = crate::messages::Message::new_request(id,method_name,input);
+        let serialized_message = serde_json::to_string(&message).unwrap();
+        let handle  = CancellationHandle {id, handler:self.downgrade()};
+        (handle,self.open_request_with_message_and_options(id,&serialized_message,options))
+    }
+
+    /// Sends a request to the peer and returns a `Future` that shall yield a reply message.
+    ///
+    /// Helper common code for all the request opening methods. When the request times out, it is
+    /// cancelled just like with the `CancellationHandle`.
+    pub fn open_request_with_message_and_options<Returned:DeserializeOwned>
+    (&self, id:Id, message_json:&str, options:RequestOptions)
+    -> impl Future<Output = Result<Returned>> {
+        let (sender, receiver) = oneshot::channel::<Result<ReplyMessage>>();
         let ret                = receiver.map(|result_or_cancel| {
-            let result = result_or_cancel?;
+            let result = result_or_cancel??;
             decode_result(result)
         });
 
         self.insert_ongoing_request(id,sender);
-        if self.send_text_message(message_json).is_err() {
+        let is_batched = self.add_to_batch(id,message_json);
+        if !is_batched && self.send_text_message(message_json).is_err() {
             // If message cannot be send, future ret must be cancelled.
             self.remove_ongoing_request(id);
         }
 
-        let millis = self.timeout().as_millis();
-        future::select(ret, sleep(self.timeout()).boxed_local()).map(move |either|
+        let timeout = options.timeout.unwrap_or_else(|| self.timeout());
+        let millis  = timeout.as_millis();
+        let handler = self.downgrade();
+        future::select(ret, sleep(timeout).boxed_local()).map(move |either|
             match either {
                 future::Either::Left ((x, _)) => x,
-                future::Either::Right((_, _)) => Err(RpcError::TimeoutError{millis}),
+                future::Either::Right((_, _)) => {
+                    if let Some(handler) = handler.upgrade() {
+                        handler.cancel_request(id);
+                    }
+                    Err(RpcError::TimeoutError{millis})
+                }
             }
         )
     }
 
+    /// Sends all the requests opened since `start_batch` call as a single JSON-RPC batch. The
+    /// replies are delivered to the requests' futures as usual.
+    ///
+    /// Does nothing if the batch was not started or no request was opened.
+    pub fn send_batch(&self) {
+        let batch = self.take_batch();
+        if !batch.is_empty() {
+            let messages = batch.iter().map(|(_,message)| message.as_str());
+            let message  = format!("[{}]", messages.join(","));
+            if self.send_text_message(&message).is_err() {
+                // If message cannot be send, futures of all the requests must be cancelled.
+                for (id,_) in batch {
+                    self.remove_ongoing_request(id);
+                }
+            }
+        }
+    }
+
+    /// Cancels the ongoing request. Its future yields `RpcError::Cancelled` and the peer's reply,
+    /// if it ever comes, is ignored. If the cancel method is set (see `set_cancel_method`), the
+    /// peer is notified about the cancellation.
+    ///
+    /// Does nothing if the request is not ongoing, e.g. it was already completed.
+    pub fn cancel_request(&self, id:Id) {
+        if let Some(sender) = self.abandon_ongoing_request(id) {
+            // Disregard any error. We do not care if RPC caller already dropped the future.
+            sender.send(Err(RpcError::Cancelled)).ok();
+            if let Some(method) = self.cancel_method() {
+                let message = messages::Message::new_notification(method,CancelRequestParams{id});
+                let message = serde_json::to_string(&message).unwrap();
+                // If the notification cannot be sent, the connection is probably lost, so the
+                // peer will not reply anyway.
+                self.send_text_message(&message).ok();
+            }
+        }
+    }
+
     /// Deal with `Response` message from the peer.
     ///
     /// It shall be either matched with an open request or yield an error.
@@ -310,7 +546,9 @@ impl<Notification> Handler<Notification> {
         if let Some(sender) = self.remove_ongoing_request(message.id) {
             // Disregard any error. We do not care if RPC caller already
             // dropped the future.
-            sender.send(message.result).ok();
+            sender.send(Ok(message.result)).ok();
+        } else if self.take_abandoned_request(message.id) {
+            // The request was cancelled or timed out, the reply is no longer awaited.
         } else {
             self.error_occurred(HandlingError::UnexpectedResponse(message));
         }
@@ -338,14 +576,19 @@ impl<Notification> Handler<Notification> {
     /// Deal with incoming text message from the peer.
     ///
     /// The message must conform either to the `Response` or to the
-    /// `Notification` JSON-serialized format. Otherwise, an error is raised.
+    /// `Notification` JSON-serialized format, or be a batch (array) of such
+    /// messages. Otherwise, an error is raised.
     pub fn process_incoming_message(&self, message:String)
     where Notification: DeserializeOwned {
-        match messages::decode_incoming_message(&message) {
-            Ok(messages::IncomingMessage::Response(response)) =>
-                self.process_response(response),
-            Ok(messages::IncomingMessage::Notification(notification)) =>
-                self.process_notification(notification),
+        match messages::decode_incoming_messages(&message) {
+            Ok(messages) => for message in messages {
+                match message {
+                    messages::IncomingMessage::Response(response) =>
+                        self.process_response(response),
+                    messages::IncomingMessage::Notification(notification) =>
+                        self.process_notification(notification),
+                }
+            },
             Err(err) =>
                 self.error_occurred(HandlingError::InvalidMessage(err)),
         }
@@ -13,13 +13,20 @@
 /// }
 /// ```
 ///
-/// This macro generates an `API` trait and creates two structs implementing `API`
-/// called `Client`, with the actual RPC methods, and `MockClient`, with mocked methods with
-/// return types setup by:
+/// This macro generates an `API` trait and creates three structs implementing `API`
+/// called `Client`, with the actual RPC methods, `SwappableClient`, delegating calls to a
+/// replaceable client, and `MockClient`, with mocked methods with return types setup by:
 /// ```rust,compile_fail
 ///     fn expect_call_me_please
 ///     (&mut self, my_number_is:String,result:json_rpc::api::Result<()>) { /* impl */ }
 /// ```
+///
+/// Additionally, the `WithOptions` struct is generated, having the same methods as `API`, which
+/// make the calls with the given `RequestOptions` and return the `CancellationHandle`s of them:
+/// ```rust,compile_fail
+///     let options        = RequestOptions::with_timeout(timeout);
+///     let (handle,reply) = client.with_options(options).call_me_please(&my_number);
+/// ```
 #[macro_export]
 macro_rules! make_rpc_methods {
     (
@@ -48,6 +55,131 @@ macro_rules! make_rpc_methods {
             ///
             /// On a repeated call, previous stream is closed.
             fn events(&self) -> futures::stream::LocalBoxStream<'static,Event>;
+
+            /// Start collecting the calls into a JSON-RPC batch, which is sent as a single message
+            /// by `send_batch`. Clients not supporting batches make the calls immediately.
+            ///
+            /// Returns `true` if this call started the batch. When the batch was already started,
+            /// the calls join it, and it is sent by the caller which started it.
+            fn start_batch(&self) -> bool { false }
+
+            /// Send all the calls made since `start_batch` as a single batch.
+            fn send_batch(&self) {}
+
+            /// Call the remote method with the given name and JSON input, using the given options.
+            /// Returns the handle allowing to cancel the call together with the future of its JSON
+            /// result, or `None` if the client does not support the options, so the call should be
+            /// made as usual.
+            fn call_with_options
+            (&self, _method:&str, _input:&serde_json::Value, _options:RequestOptions)
+            -> Option<(CancellationHandle,JsonResultFuture)> { None }
+        }
+
+        /// Handle allowing to cancel the call made with `WithOptions`.
+        pub type CancellationHandle = json_rpc::handler::CancellationHandle<Notification>;
+
+        /// The options of a single call made with `WithOptions`.
+        pub type RequestOptions = json_rpc::handler::RequestOptions;
+
+        /// The future of the call's result, not yet deserialized from JSON.
+        pub type JsonResultFuture =
+            std::pin::Pin<Box<dyn Future<Output=Result<serde_json::Value>>>>;
+
+        impl dyn API {
+            /// Make all the calls done by `f` in a single JSON-RPC batch. Returns whatever `f`
+            /// returns, usually the futures of the calls' results.
+            ///
+            /// ```rust,compile_fail
+            /// let (first,second) = client.batch(|client| (client.first(), client.second()));
+            /// let (first,second) = futures::join!(first,second);
+            /// ```
+            ///
+            /// If called within another batch, the calls join the outer batch and are sent with
+            /// it.
+            pub fn batch<R>(&self, f:impl FnOnce(&dyn API) -> R) -> R {
+                let is_outermost = self.start_batch();
+                let result       = f(self);
+                if is_outermost {
+                    self.send_batch();
+                }
+                result
+            }
+
+            /// Make all the calls returned by `f` in a single JSON-RPC batch, and wait for all of
+            /// them. Each call succeeds or fails on its own, so the result of every call is
+            /// returned, in the order of the calls.
+            pub fn batch_all<T:'static>
+            ( &self
+            , f : impl FnOnce(&dyn API) -> Vec<std::pin::Pin<Box<dyn Future<Output=Result<T>>>>>
+            ) -> futures::future::JoinAll<std::pin::Pin<Box<dyn Future<Output=Result<T>>>>> {
+                futures::future::join_all(self.batch(f))
+            }
+
+            /// Wrap the `future`, so all the calls it makes during a single poll are sent in one
+            /// JSON-RPC batch. This way the calls of several independent operations, e.g. joined
+            /// with `futures::join!`, are batched, even if each operation makes its next calls
+            /// only after receiving the replies to its previous ones.
+            pub fn batch_each_poll<'a,F:Future+'a>
+            (&'a self, future:F) -> impl Future<Output=F::Output> + 'a {
+                let mut future = Box::pin(future);
+                futures::future::poll_fn(move |cx| {
+                    self.batch(|_| future.as_mut().poll(cx))
+                })
+            }
+
+            /// Get the view of this client making the calls with the given options. See
+            /// `WithOptions`.
+            pub fn with_options(&self, options:RequestOptions) -> WithOptions {
+                WithOptions {client:self, options}
+            }
+        }
+
+
+
+        // ===================
+        // === WithOptions ===
+        // ===================
+
+        /// The view of an `API` client making the calls with the given options, e.g. a timeout
+        /// different from the client's default. Each call returns, along with the result's future,
+        /// the handle allowing to cancel it, if the client supports cancelling.
+        #[derive(Clone,Copy)]
+        pub struct WithOptions<'a> {
+            client  : &'a dyn API,
+            options : RequestOptions,
+        }
+
+        impl WithOptions<'_> {
+            $(
+                $(#[doc = $doc])+
+                #[allow(clippy::ptr_arg)]
+                pub fn $method<'a>(&'a self $(,$param_name:&'a $param_ty)*)
+                -> ( Option<CancellationHandle>
+                   , std::pin::Pin<Box<dyn Future<Output=Result<$result>>>>
+                   ) {
+                    use json_rpc::api::RemoteMethodCall;
+                    let phantom    = std::marker::PhantomData;
+                    let input      = $method_input { phantom, $($param_name:&$param_name),* };
+                    let input_json = serde_json::to_value(input).unwrap();
+                    let name       = $method_input::NAME;
+                    match self.client.call_with_options(name,&input_json,self.options) {
+                        Some((handle,result)) => {
+                            let result = futures::FutureExt::map(result, |result| {
+                                Ok(serde_json::from_value::<$result>(result?)?)
+                            });
+                            (Some(handle),Box::pin(result))
+                        }
+                        // The client ignores the options.
+                        None => (None,self.client.$method($($param_name),*)),
+                    }
+                }
+            )*
+        }
+
+        impl Debug for WithOptions<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "Client with options {:?}", self.options)
+            }
         }
 
 
@@ -98,6 +230,23 @@ macro_rules! make_rpc_methods {
             fn events(&self) -> futures::stream::LocalBoxStream<'static,Event> {
                 self.handler.borrow_mut().handler_event_stream().boxed_local()
             }
+
+            fn start_batch(&self) -> bool {
+                self.handler.borrow().start_batch()
+            }
+
+            fn send_batch(&self) {
+                self.handler.borrow().send_batch()
+            }
+
+            fn call_with_options
+            (&self, method:&str, input:&serde_json::Value, options:RequestOptions)
+            -> Option<(CancellationHandle,JsonResultFuture)> {
+                let handler       = self.handler.borrow();
+                let (handle,call) = handler
+                    .open_cancellable_request_with_json::<serde_json::Value>(method,input,options);
+                Some((handle,Box::pin(call)))
+            }
         }
 
         $(
@@ -118,6 +267,64 @@ macro_rules! make_rpc_methods {
 
 
 
+        // =======================
+        // === SwappableClient ===
+        // =======================
+
+        /// A client delegating all the calls to another client, which may be replaced at any time,
+        /// e.g. with a client of a new connection when the previous one was lost.
+        #[derive(Clone)]
+        pub struct SwappableClient {
+            client : std::rc::Rc<std::cell::RefCell<std::rc::Rc<dyn API>>>,
+        }
+
+        impl SwappableClient {
+            /// Create a client delegating the calls to the given one.
+            pub fn new(client:Box<dyn API>) -> Self {
+                let client = std::rc::Rc::new(std::cell::RefCell::new(client.into()));
+                Self {client}
+            }
+
+            /// Replace the client which the calls are delegated to. The pending calls are not
+            /// affected.
+            pub fn replace(&self, client:Box<dyn API>) {
+                *self.client.borrow_mut() = client.into();
+            }
+        }
+
+        impl API for SwappableClient {
+            $(fn $method<'a>(&'a self $(,$param_name:&'a $param_ty)*)
+            -> std::pin::Pin<Box<dyn Future<Output=Result<$result>>>> {
+                self.client.borrow().$method($($param_name),*)
+            })*
+
+            fn events(&self) -> futures::stream::LocalBoxStream<'static,Event> {
+                self.client.borrow().events()
+            }
+
+            fn start_batch(&self) -> bool {
+                self.client.borrow().start_batch()
+            }
+
+            fn send_batch(&self) {
+                self.client.borrow().send_batch()
+            }
+
+            fn call_with_options
+            (&self, method:&str, input:&serde_json::Value, options:RequestOptions)
+            -> Option<(CancellationHandle,JsonResultFuture)> {
+                self.client.borrow().call_with_options(method,input,options)
+            }
+        }
+
+        impl Debug for SwappableClient {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "Swappable Client")
+            }
+        }
+
+
+
         // ==================
         // === MockClient ===
         // ==================
@@ -220,6 +220,27 @@ pub fn decode_incoming_message
     from_value::<IncomingMessage>(message.payload)
 }
 
+/// Partially decodes incoming message, which may be either a single message or a batch (an array)
+/// of messages, e.g. replies to the batch of requests.
+///
+/// Each message is decoded like in `decode_incoming_message`.
+pub fn decode_incoming_messages
+(message:&str) -> serde_json::Result<Vec<IncomingMessage>> {
+    use serde_json::Value;
+    use serde_json::from_str;
+    use serde_json::from_value;
+    match from_str::<Value>(&message)? {
+        Value::Array(messages) => messages.into_iter().map(|message| {
+            let message = from_value::<Message<Value>>(message)?;
+            from_value::<IncomingMessage>(message.payload)
+        }).collect(),
+        message => {
+            let message = from_value::<Message<Value>>(message)?;
+            Ok(vec![from_value::<IncomingMessage>(message.payload)?])
+        }
+    }
+}
+
 /// Message from server to client.
 ///
 /// `In` is any serializable (or already serialized) representation of the
@@ -377,4 +398,24 @@ mod tests {
             _ => panic!("Invalid decoding result of {}: {:?}", text, decoding_result),
         }
     }
+
+    #[test]
+    fn decode_incoming_batch_text() {
+        let text = r#"[
+            {"jsonrpc":"2.0","id":1,"result":null},
+            {"jsonrpc":"2.0","method":"mockNotification","params":{"number":1}}
+        ]"#;
+        let decoding_result = decode_incoming_messages(text).unwrap();
+        match decoding_result.as_slice() {
+            [IncomingMessage::Response(response),IncomingMessage::Notification(notification)] => {
+                assert_eq!(response.id, Id(1));
+                assert_eq!(notification.0["method"], "mockNotification");
+            },
+            _ => panic!("Invalid decoding result of {}: {:?}", text, decoding_result),
+        }
+
+        let text            = r#"{"jsonrpc":"2.0","id":1,"result":null}"#;
+        let decoding_result = decode_incoming_messages(text).unwrap();
+        assert_eq!(decoding_result.len(), 1);
+    }
 }
@@ -7,6 +7,8 @@ use futures::channel::mpsc::UnboundedReceiver;
 use futures::channel::mpsc::UnboundedSender;
 use futures::channel::mpsc::unbounded;
 
+pub mod recording;
+
 /// A transport that facilitate JSON-RPC protocol.
 ///
 /// Must allow sending and receiving text messages. Additionally, connection at
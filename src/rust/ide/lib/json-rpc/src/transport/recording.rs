@@ -0,0 +1,637 @@
+//! Transports recording the traffic of another transport and replaying the recorded traffic.
+//!
+//! The recordings allow reproducing the exact conversation with the peer, e.g. when investigating
+//! bug reports or writing regression tests from the real traces.
+
+use crate::prelude::*;
+
+use crate::transport::Transport;
+use crate::transport::TransportEvent;
+
+use failure::Error;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use utils::channel;
+use utils::option::OptionExt;
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+/// Errors emitted by the `ReplayTransport`.
+#[derive(Clone,Debug,Fail)]
+pub enum ReplayError {
+    /// The message sent by the client differs from the one in the recording.
+    #[fail(display = "Sent message {:?} while the recording expected {:?}.", actual, expected)]
+    #[allow(missing_docs)]
+    UnexpectedMessage {actual:Payload, expected:Payload},
+    /// The client sent a message after the end of the recording.
+    #[fail(display = "Sent message {:?} after the end of the recording.", _0)]
+    RecordingFinished(Payload),
+}
+
+/// Error raised when the recording could not be read.
+#[derive(Debug,Fail)]
+#[fail(display = "Invalid recording at line {}: {}.", line, error)]
+#[allow(missing_docs)]
+pub struct InvalidRecording {
+    pub line  : usize,
+    #[cause]
+    pub error : serde_json::Error,
+}
+
+
+
+// =================
+// === Recording ===
+// =================
+
+/// The direction in which the recorded message was sent.
+#[derive(Clone,Copy,Debug,Deserialize,Eq,PartialEq,Serialize)]
+pub enum Direction {
+    /// The message was sent by the client to the peer.
+    Outgoing,
+    /// The event was received by the client.
+    Incoming,
+}
+
+/// The contents of the recorded message or event.
+#[derive(Clone,Debug,Deserialize,PartialEq,Serialize)]
+#[serde(tag="type", content="data")]
+pub enum Payload {
+    /// A text message.
+    Text(String),
+    /// A binary message.
+    Binary(Vec<u8>),
+    /// The connection has been opened.
+    Opened,
+    /// The connection has been closed.
+    Closed,
+}
+
+impl Payload {
+    /// The payload of the incoming transport event.
+    pub fn from_event(event:&TransportEvent) -> Self {
+        match event {
+            TransportEvent::TextMessage(text)   => Payload::Text(text.clone()),
+            TransportEvent::BinaryMessage(data) => Payload::Binary(data.clone()),
+            TransportEvent::Opened              => Payload::Opened,
+            TransportEvent::Closed              => Payload::Closed,
+        }
+    }
+
+    /// The transport event which delivers this payload to the client.
+    pub fn to_event(&self) -> TransportEvent {
+        match self {
+            Payload::Text(text)   => TransportEvent::TextMessage(text.clone()),
+            Payload::Binary(data) => TransportEvent::BinaryMessage(data.clone()),
+            Payload::Opened       => TransportEvent::Opened,
+            Payload::Closed       => TransportEvent::Closed,
+        }
+    }
+
+    /// Checks if the payloads are equivalent. The text messages containing JSON are compared
+    /// structurally, so the formatting and order of the fields does not matter.
+    pub fn matches(&self, other:&Payload) -> bool {
+        match (self,other) {
+            (Payload::Text(text),Payload::Text(other_text)) => {
+                let json       = serde_json::from_str::<serde_json::Value>(text);
+                let other_json = serde_json::from_str::<serde_json::Value>(other_text);
+                match (json,other_json) {
+                    (Ok(json),Ok(other_json)) => json == other_json,
+                    _                         => text == other_text,
+                }
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// A single recorded message or event.
+#[derive(Clone,Debug,Deserialize,PartialEq,Serialize)]
+pub struct RecordedMessage {
+    /// The time in milliseconds since the recording has started.
+    pub timestamp : f64,
+    #[allow(missing_docs)]
+    pub direction : Direction,
+    #[allow(missing_docs)]
+    pub payload   : Payload,
+}
+
+/// A shared list of recorded messages, in the order they were sent or received.
+///
+/// The recording is serialized as JSON lines, one line for each message.
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct Recording(Rc<RefCell<Vec<RecordedMessage>>>);
+
+impl Recording {
+    /// Create an empty recording.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Append a message to the recording.
+    pub fn push(&self, message:RecordedMessage) {
+        self.0.borrow_mut().push(message)
+    }
+
+    /// All the recorded messages.
+    pub fn messages(&self) -> Vec<RecordedMessage> {
+        self.0.borrow().clone()
+    }
+
+    /// Serialize the recording to JSON lines.
+    pub fn to_json_lines(&self) -> String {
+        let messages = self.0.borrow();
+        // The recorded messages consist only of serializable data.
+        let lines = messages.iter().map(|message| serde_json::to_string(message).unwrap());
+        lines.map(|line| line + "\n").collect()
+    }
+
+    /// Deserialize the recording from JSON lines. The empty lines are ignored.
+    pub fn from_json_lines(text:&str) -> std::result::Result<Self,InvalidRecording> {
+        let lines    = text.lines().enumerate().filter(|(_,line)| !line.trim().is_empty());
+        let messages = lines.map(|(index,line)| {
+            serde_json::from_str(line).map_err(|error| InvalidRecording {line:index+1,error})
+        }).collect::<std::result::Result<Vec<_>,_>>()?;
+        Ok(Recording(Rc::new(RefCell::new(messages))))
+    }
+
+    /// Write the recording to the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path:impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path,self.to_json_lines())
+    }
+
+    /// Read the recording from the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path:impl AsRef<std::path::Path>) -> std::result::Result<Self,Error> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self::from_json_lines(&text)?)
+    }
+}
+
+
+
+// =============
+// === Clock ===
+// =============
+
+/// Measures the time since the recording has started.
+#[derive(Clone,Copy,Debug)]
+struct Clock {
+    #[cfg(target_arch = "wasm32")]
+    start : f64,
+    #[cfg(not(target_arch = "wasm32"))]
+    start : std::time::Instant,
+}
+
+impl Clock {
+    #[cfg(target_arch = "wasm32")]
+    fn new() -> Self {
+        let start = crate::ensogl::performance().now();
+        Clock {start}
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn new() -> Self {
+        let start = std::time::Instant::now();
+        Clock {start}
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn elapsed_ms(&self) -> f64 {
+        crate::ensogl::performance().now() - self.start
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn elapsed_ms(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+
+
+// ==========================
+// === RecordingTransport ===
+// ==========================
+
+/// A transport wrapping another one and recording all its traffic: the messages sent through it
+/// and the events received from it.
+///
+/// The received events are passed through the `runner` future, which must be run for the
+/// transport to work, similarly to the `Handler`'s runner.
+#[derive(Debug)]
+pub struct RecordingTransport<T> {
+    inner             : T,
+    recording         : Recording,
+    clock             : Clock,
+    inner_events      : Option<UnboundedReceiver<TransportEvent>>,
+    event_transmitter : Rc<RefCell<Option<UnboundedSender<TransportEvent>>>>,
+}
+
+impl<T:Transport> RecordingTransport<T> {
+    /// Wrap the transport, appending its traffic to the given recording.
+    pub fn new(mut inner:T, recording:Recording) -> Self {
+        let inner_events      = Some(inner.establish_event_stream());
+        let clock             = Clock::new();
+        let event_transmitter = default();
+        RecordingTransport {inner,recording,clock,inner_events,event_transmitter}
+    }
+
+    /// The recording of this transport's traffic.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Returns a future which records the events received from the wrapped transport and passes
+    /// them further. Should be continually run while the transport is used.
+    ///
+    /// The future can be obtained only once, the subsequent calls return a finished future.
+    pub fn runner(&mut self) -> impl Future<Output=()> {
+        let recording         = self.recording.clone_ref();
+        let clock             = self.clock;
+        let event_transmitter = self.event_transmitter.clone();
+        let inner_events      = self.inner_events.take();
+        let inner_events      = futures::stream::iter(inner_events).flatten();
+        inner_events.for_each(move |event| {
+            let timestamp = clock.elapsed_ms();
+            let direction = Direction::Incoming;
+            let payload   = Payload::from_event(&event);
+            recording.push(RecordedMessage {timestamp,direction,payload});
+            if let Some(transmitter) = event_transmitter.borrow().as_ref() {
+                channel::emit(transmitter,event);
+            }
+            futures::future::ready(())
+        })
+    }
+
+    fn record_outgoing(&self, payload:Payload) {
+        let timestamp = self.clock.elapsed_ms();
+        let direction = Direction::Outgoing;
+        self.recording.push(RecordedMessage {timestamp,direction,payload});
+    }
+}
+
+impl<T:Transport> Transport for RecordingTransport<T> {
+    fn send_text(&mut self, message:&str) -> Result<(), Error> {
+        self.record_outgoing(Payload::Text(message.to_string()));
+        self.inner.send_text(message)
+    }
+
+    fn send_binary(&mut self, message:&[u8]) -> Result<(), Error> {
+        self.record_outgoing(Payload::Binary(message.to_vec()));
+        self.inner.send_binary(message)
+    }
+
+    fn set_event_transmitter(&mut self, transmitter:UnboundedSender<TransportEvent>) {
+        *self.event_transmitter.borrow_mut() = Some(transmitter);
+    }
+}
+
+
+
+// ===============
+// === Matcher ===
+// ===============
+
+/// Decides whether the message sent by the client matches the one in the recording, and adjusts
+/// the replayed incoming messages to the client's session.
+pub trait Matcher : Debug {
+    /// Checks if the `actual` message sent by the client matches the `recorded` one.
+    fn matches(&mut self, recorded:&Payload, actual:&Payload) -> bool;
+
+    /// Adjusts the recorded incoming message before it is emitted to the client.
+    fn adjust_incoming(&self, payload:Payload) -> Payload {
+        payload
+    }
+}
+
+/// The default `Matcher`, comparing the messages with `Payload::matches`.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct StructuralMatcher;
+
+impl Matcher for StructuralMatcher {
+    fn matches(&mut self, recorded:&Payload, actual:&Payload) -> bool {
+        recorded.matches(actual)
+    }
+}
+
+/// A `Matcher` tolerating the UUIDs differing between the recorded and the replayed sessions, as
+/// the client generates random ones, e.g. for its id or for the attached visualizations.
+///
+/// The JSON messages are compared structurally, with every UUID string matching any other UUID,
+/// as long as the recorded one is always replaced with the same actual one. The learned
+/// replacements are applied to the UUIDs in the replayed incoming messages, so the client
+/// receives the ids it used. The binary messages are compared exactly.
+#[derive(Clone,Debug,Default)]
+pub struct UuidNormalizingMatcher {
+    /// Maps the UUIDs from the recording to the ones used by the client.
+    replacements : HashMap<String,String>,
+}
+
+impl UuidNormalizingMatcher {
+    /// Create a matcher which has not learned any replacements yet.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Checks if the text has the form of a UUID, e.g. `9c0a9b7e-6d8c-4b6e-8f5e-3a2d1c0b9a87`.
+    pub fn is_uuid(text:&str) -> bool {
+        let groups = text.split('-').collect_vec();
+        let sizes  = groups.iter().map(|group| group.len()).collect_vec();
+        let is_hex = |group:&&str| group.chars().all(|c| c.is_ascii_hexdigit());
+        sizes == [8,4,4,4,12] && groups.iter().all(is_hex)
+    }
+
+    /// Matches the JSON values, learning the new replacements into `replacements`.
+    fn match_json
+    ( replacements : &mut HashMap<String,String>
+    , recorded     : &serde_json::Value
+    , actual       : &serde_json::Value
+    ) -> bool {
+        use serde_json::Value;
+        match (recorded,actual) {
+            (Value::String(recorded),Value::String(actual))
+            if Self::is_uuid(recorded) && Self::is_uuid(actual) => {
+                let replacement = replacements.entry(recorded.clone());
+                replacement.or_insert_with(|| actual.clone()) == actual
+            }
+            (Value::Array(recorded),Value::Array(actual)) => {
+                recorded.len() == actual.len() && recorded.iter().zip(actual).all(|(r,a)| {
+                    Self::match_json(replacements,r,a)
+                })
+            }
+            (Value::Object(recorded),Value::Object(actual)) => {
+                recorded.len() == actual.len() && recorded.iter().all(|(key,r)| {
+                    actual.get(key).map_or(false, |a| Self::match_json(replacements,r,a))
+                })
+            }
+            _ => recorded == actual,
+        }
+    }
+
+    /// Replaces the recorded UUIDs with the learned ones.
+    fn replace_uuids(&self, value:&mut serde_json::Value) {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => if let Some(replacement) = self.replacements.get(text.as_str()) {
+                *text = replacement.clone();
+            }
+            Value::Array(values)  => values.iter_mut().for_each(|v| self.replace_uuids(v)),
+            Value::Object(values) => values.values_mut().for_each(|v| self.replace_uuids(v)),
+            _                     => {}
+        }
+    }
+}
+
+impl Matcher for UuidNormalizingMatcher {
+    fn matches(&mut self, recorded:&Payload, actual:&Payload) -> bool {
+        if let (Payload::Text(recorded),Payload::Text(actual)) = (recorded,actual) {
+            let recorded_json = serde_json::from_str::<serde_json::Value>(recorded);
+            let actual_json   = serde_json::from_str::<serde_json::Value>(actual);
+            if let (Ok(recorded_json),Ok(actual_json)) = (recorded_json,actual_json) {
+                // The replacements are learned only if the whole message matches.
+                let mut replacements = self.replacements.clone();
+                let matches = Self::match_json(&mut replacements,&recorded_json,&actual_json);
+                if matches {
+                    self.replacements = replacements;
+                }
+                return matches
+            }
+        }
+        recorded.matches(actual)
+    }
+
+    fn adjust_incoming(&self, payload:Payload) -> Payload {
+        match payload {
+            Payload::Text(text) => match serde_json::from_str::<serde_json::Value>(&text) {
+                Ok(mut json) => {
+                    self.replace_uuids(&mut json);
+                    Payload::Text(json.to_string())
+                }
+                Err(_) => Payload::Text(text),
+            }
+            payload => payload,
+        }
+    }
+}
+
+
+
+// =======================
+// === ReplayTransport ===
+// =======================
+
+/// A transport playing the role of the peer from the recording.
+///
+/// Each message sent through the transport is checked against the next outgoing message in the
+/// recording, and then the incoming messages recorded after it are emitted; the unexpected
+/// messages are rejected with an error. The incoming messages
+/// recorded before any outgoing message are emitted once the event transmitter is set. The
+/// timestamps are ignored, the messages are replayed as soon as possible.
+///
+/// The messages are compared by the `Matcher`, by default the `StructuralMatcher`. Use the
+/// `UuidNormalizingMatcher` for replaying the sessions in which the client generates random ids.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    remaining         : VecDeque<RecordedMessage>,
+    matcher           : Box<dyn Matcher>,
+    event_transmitter : Option<UnboundedSender<TransportEvent>>,
+}
+
+impl ReplayTransport {
+    /// Create a transport replaying the given recording.
+    pub fn new(recording:&Recording) -> Self {
+        Self::new_with_matcher(recording,StructuralMatcher)
+    }
+
+    /// Create a transport replaying the given recording, comparing the messages with the given
+    /// matcher.
+    pub fn new_with_matcher(recording:&Recording, matcher:impl Matcher + 'static) -> Self {
+        let remaining         = recording.messages().into();
+        let matcher           = Box::new(matcher);
+        let event_transmitter = None;
+        ReplayTransport {remaining,matcher,event_transmitter}
+    }
+
+    /// Checks if all the recorded messages were replayed.
+    pub fn is_finished(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    fn replay_incoming(&mut self) {
+        if let Some(transmitter) = &self.event_transmitter {
+            while self.remaining.front().contains_if(|m| m.direction == Direction::Incoming) {
+                // We just checked that the front message exists.
+                let message = self.remaining.pop_front().unwrap();
+                let payload = self.matcher.adjust_incoming(message.payload);
+                channel::emit(transmitter,payload.to_event());
+            }
+        }
+    }
+
+    fn expect_outgoing(&mut self, actual:Payload) -> Result<(), Error> {
+        match self.remaining.pop_front() {
+            Some(message) if self.matcher.matches(&message.payload,&actual) => {
+                self.replay_incoming();
+                Ok(())
+            }
+            Some(message) => {
+                let expected = message.payload.clone();
+                self.remaining.push_front(message);
+                Err(ReplayError::UnexpectedMessage {actual,expected}.into())
+            }
+            None => Err(ReplayError::RecordingFinished(actual).into()),
+        }
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send_text(&mut self, message:&str) -> Result<(), Error> {
+        self.expect_outgoing(Payload::Text(message.to_string()))
+    }
+
+    fn send_binary(&mut self, message:&[u8]) -> Result<(), Error> {
+        self.expect_outgoing(Payload::Binary(message.to_vec()))
+    }
+
+    fn set_event_transmitter(&mut self, transmitter:UnboundedSender<TransportEvent>) {
+        self.event_transmitter = Some(transmitter);
+        self.replay_incoming();
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_util::transport::mock::MockTransport;
+
+    use futures::channel::mpsc::TryRecvError;
+    use futures::executor::LocalPool;
+    use futures::task::LocalSpawnExt;
+
+    fn expect_text(events:&mut UnboundedReceiver<TransportEvent>, expected:&str) {
+        match events.try_next() {
+            Ok(Some(TransportEvent::TextMessage(text))) => assert_eq!(text, expected),
+            _ => panic!("Expected a text message {}.", expected),
+        }
+    }
+
+    fn matches_closed(event:std::result::Result<Option<TransportEvent>,TryRecvError>) -> bool {
+        if let Ok(Some(TransportEvent::Closed)) = event { true } else { false }
+    }
+
+    #[test]
+    fn recording_and_replaying() {
+        let mut pool      = LocalPool::new();
+        let mut mock      = MockTransport::new();
+        let recording     = Recording::new();
+        let mut recorder  = RecordingTransport::new(mock.clone(),recording.clone_ref());
+        pool.spawner().spawn_local(recorder.runner()).unwrap();
+        let mut events    = recorder.establish_event_stream();
+
+        // The incoming events are recorded by the runner, so it must run after each of them.
+        mock.mock_peer_text_message("hello");
+        pool.run_until_stalled();
+        recorder.send_text(r#"{"a":1,"b":2}"#).unwrap();
+        mock.mock_peer_text_message("reply");
+        pool.run_until_stalled();
+        recorder.send_binary(&[1,2,3]).unwrap();
+        mock.mock_connection_closed();
+        pool.run_until_stalled();
+        expect_text(&mut events,"hello");
+        expect_text(&mut events,"reply");
+        assert_eq!(mock.expect_text_message(), r#"{"a":1,"b":2}"#);
+
+        let text     = recording.to_json_lines();
+        let restored = Recording::from_json_lines(&text).unwrap();
+        let messages = restored.messages();
+        let payloads = messages.iter().map(|message| (message.direction,&message.payload));
+        let expected = vec![
+            (Direction::Incoming,&Payload::Text("hello".into())),
+            (Direction::Outgoing,&Payload::Text(r#"{"a":1,"b":2}"#.into())),
+            (Direction::Incoming,&Payload::Text("reply".into())),
+            (Direction::Outgoing,&Payload::Binary(vec![1,2,3])),
+            (Direction::Incoming,&Payload::Closed),
+        ];
+        assert_eq!(payloads.collect_vec(), expected);
+
+        let mut replay = ReplayTransport::new(&restored);
+        let mut events = replay.establish_event_stream();
+        expect_text(&mut events,"hello");
+        assert!(replay.send_text(r#"{ "b":2, "a":1 }"#).is_ok());
+        expect_text(&mut events,"reply");
+        assert!(replay.send_binary(&[3,2,1]).is_err());
+        assert!(replay.send_binary(&[1,2,3]).is_ok());
+        assert!(matches_closed(events.try_next()));
+        assert!(replay.is_finished());
+        assert!(replay.send_text("more").is_err());
+    }
+
+    #[test]
+    fn replaying_with_different_uuids() {
+        let recorded_id = "0a1b2c3d-0000-4000-8000-000000000001";
+        let actual_id   = "9f8e7d6c-1111-4111-9111-111111111111";
+        let other_id    = "9f8e7d6c-2222-4222-a222-222222222222";
+        let message     = |method:&str, id:&str| {
+            let json = serde_json::json!({"method":method, "params":{"contextId":id}});
+            Payload::Text(json.to_string())
+        };
+        let recording = Recording::new();
+        let push      = |direction,payload| {
+            recording.push(RecordedMessage {timestamp:0.0,direction,payload})
+        };
+        push(Direction::Outgoing,message("create",recorded_id));
+        push(Direction::Incoming,message("created",recorded_id));
+        push(Direction::Outgoing,message("destroy",recorded_id));
+
+        let mut exact = ReplayTransport::new(&recording);
+        assert!(exact.send_text(&text_of(message("create",actual_id))).is_err());
+
+        let matcher    = UuidNormalizingMatcher::new();
+        let mut replay = ReplayTransport::new_with_matcher(&recording,matcher);
+        let mut events = replay.establish_event_stream();
+        assert!(replay.send_text(&text_of(message("create",actual_id))).is_ok());
+        let expected = text_of(message("created",actual_id));
+        match events.try_next() {
+            Ok(Some(TransportEvent::TextMessage(text))) => {
+                assert!(Payload::Text(text).matches(&Payload::Text(expected)));
+            }
+            _ => panic!("Expected a text message."),
+        }
+        // The recorded id was already replaced with the other one.
+        assert!(replay.send_text(&text_of(message("destroy",other_id))).is_err());
+        assert!(replay.send_text(&text_of(message("destroy",actual_id))).is_ok());
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn recognizing_uuids() {
+        assert!(UuidNormalizingMatcher::is_uuid("0a1b2c3d-0000-4000-8000-00000000000f"));
+        assert!(!UuidNormalizingMatcher::is_uuid("0a1b2c3d-0000-4000-8000-00000000000g"));
+        assert!(!UuidNormalizingMatcher::is_uuid("0a1b2c3d-0000-4000-8000"));
+        assert!(!UuidNormalizingMatcher::is_uuid("main"));
+    }
+
+    fn text_of(payload:Payload) -> String {
+        match payload {
+            Payload::Text(text) => text,
+            _                   => panic!("Expected a text payload."),
+        }
+    }
+}
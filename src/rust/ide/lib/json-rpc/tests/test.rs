@@ -9,6 +9,8 @@ use json_rpc::api::RemoteMethodCall;
 use json_rpc::api::Result;
 use json_rpc::error::RpcError;
 use json_rpc::error::HandlingError;
+use json_rpc::handler::CancellationHandle;
+use json_rpc::handler::RequestOptions;
 use json_rpc::messages::Id;
 use json_rpc::messages::Message;
 use json_rpc::messages::Version;
@@ -90,6 +92,14 @@ impl Client {
         self.handler.open_request(input).map(|result| result.map(|r| r.result))
     }
 
+    pub fn pow_cancellable
+    (&mut self, i:i64, options:RequestOptions)
+    -> (CancellationHandle<MockNotification>, impl Future<Output = Result<i64>>) {
+        let input        = MockRequest { i };
+        let (handle,fut) = self.handler.open_cancellable_request(input,options);
+        (handle,fut.map(|result| result.map(|r| r.result)))
+    }
+
     pub fn events_processor(&mut self) -> impl Future<Output = ()> {
         self.handler.runner()
     }
@@ -234,6 +244,135 @@ fn test_timeout_error() {
     }
 }
 
+#[test]
+fn test_request_timeout_override() {
+    let mut fixture  = Fixture::new();
+    let timeout      = Duration::from_millis(10);
+    let options      = RequestOptions::with_timeout(timeout);
+    let (_,fut)      = fixture.client.pow_cancellable(8,options);
+    let mut fut      = Box::pin(fut);
+    let req_msg      = fixture.transport.expect_json_message::<MockRequestMessage>();
+
+    fut.expect_pending(); // no reply
+    fixture.pool.run_until_stalled();
+    sleep(timeout * 10);
+
+    if let RpcError::TimeoutError{..} = fut.expect_err() {} else {
+        panic!("Expected an error to be TimeoutError");
+    }
+    // The late reply should be silently ignored.
+    fixture.transport.mock_peer_json_message(pow_impl(req_msg));
+    fixture.pool.run_until_stalled();
+    fixture.client.events_stream.expect_pending();
+}
+
+#[test]
+fn test_cancelling_request() {
+    let mut fixture  = Fixture::new();
+    fixture.client.handler.set_cancel_method(Some("$/cancelRequest"));
+    let (handle,fut) = fixture.client.pow_cancellable(8,default());
+    let mut fut      = Box::pin(fut);
+    let req_msg      = fixture.transport.expect_json_message::<MockRequestMessage>();
+    fut.expect_pending(); // no reply
+
+    handle.cancel();
+    if let RpcError::Cancelled = fut.expect_err() {} else {
+        panic!("Expected an error to be Cancelled");
+    }
+    let notification = fixture.transport.expect_json_message::<serde_json::Value>();
+    let expected     = serde_json::json!({
+        "jsonrpc" : "2.0",
+        "method"  : "$/cancelRequest",
+        "params"  : {"id" : req_msg.id},
+    });
+    assert_eq!(notification, expected);
+
+    // The stale reply should be ignored, while the next request works as usual.
+    let mut next_fut = Box::pin(fixture.client.pow(4));
+    let next_req_msg = fixture.transport.expect_json_message::<MockRequestMessage>();
+    fixture.transport.mock_peer_json_message(pow_impl(req_msg));
+    fixture.transport.mock_peer_json_message(pow_impl(next_req_msg));
+    fixture.pool.run_until_stalled();
+    fixture.client.events_stream.expect_pending();
+    assert_eq!(next_fut.expect_ok(), 16);
+
+    // Cancelling the completed request does nothing.
+    handle.cancel();
+    fixture.transport.with_mut_data(|data| assert!(data.sent_text_msgs.is_empty()));
+}
+
+#[test]
+fn test_batch_call() {
+    let mut fixture = Fixture::new();
+    fixture.client.handler.start_batch();
+    let mut fut1    = Box::pin(fixture.client.pow(2));
+    let mut fut2    = Box::pin(fixture.client.pow(3));
+    fixture.transport.with_mut_data(|data| assert!(data.sent_text_msgs.is_empty()));
+    fixture.client.handler.send_batch();
+
+    let requests = fixture.transport.expect_json_message::<Vec<MockRequestMessage>>();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].i, 2);
+    assert_eq!(requests[1].i, 3);
+    fixture.transport.with_mut_data(|data| assert!(data.sent_text_msgs.is_empty()));
+
+    // The replies in the batch may come in any order.
+    let replies = requests.into_iter().rev().map(pow_impl).collect_vec();
+    fixture.transport.mock_peer_json_message(replies);
+    fixture.pool.run_until_stalled();
+    assert_eq!(fut1.expect_ok(), 4);
+    assert_eq!(fut2.expect_ok(), 9);
+
+    // After the batch was sent, requests are sent immediately again.
+    let mut fut3 = Box::pin(fixture.client.pow(4));
+    let req_msg  = fixture.transport.expect_json_message::<MockRequestMessage>();
+    fixture.transport.mock_peer_json_message(pow_impl(req_msg));
+    fixture.pool.run_until_stalled();
+    assert_eq!(fut3.expect_ok(), 16);
+}
+
+#[test]
+fn test_interleaved_options() {
+    let mut fixture        = Fixture::new();
+    let timeout            = Duration::from_millis(10);
+    let options            = RequestOptions::with_timeout(timeout);
+    let (first_handle,fut) = fixture.client.pow_cancellable(2,options);
+    let mut first          = Box::pin(fut);
+    let mut second         = Box::pin(fixture.client.pow(3));
+    let (_,fut)            = fixture.client.pow_cancellable(4,options);
+    let mut third          = Box::pin(fut);
+    let (_,fut)            = fixture.client.pow_cancellable(5,default());
+    let mut fourth         = Box::pin(fut);
+
+    first_handle.cancel();
+    if let RpcError::Cancelled = first.expect_err() {} else {
+        panic!("Expected an error to be Cancelled");
+    }
+    third.expect_pending();
+    fixture.pool.run_until_stalled();
+    sleep(timeout * 10);
+    if let RpcError::TimeoutError{..} = third.expect_err() {} else {
+        panic!("Expected an error to be TimeoutError");
+    }
+    // The requests opened without the options use the handler's default timeout.
+    second.expect_pending();
+    fourth.expect_pending();
+}
+
+#[test]
+fn test_abandoned_calls_limit() {
+    let fixture = Fixture::new();
+    let calls   = (0..json_rpc::handler::MAX_ABANDONED_CALLS + 10).map(|i| {
+        fixture.client.handler.open_cancellable_request(MockRequest {i:i as i64},default())
+    }).collect_vec();
+    for (handle,_) in &calls {
+        handle.cancel();
+    }
+    let count = fixture.client.handler.abandoned_requests_count();
+    assert_eq!(count, json_rpc::handler::MAX_ABANDONED_CALLS);
+    drop(calls);
+}
+
 #[test]
 fn test_disconnect_error() {
     let mut fixture = Fixture::new();
@@ -80,7 +80,8 @@ impl<M:Metadata> TryFrom<&ParsedSourceFile<M>> for String {
 
 // === Parsed Source File Serialization ===
 
-const METADATA_TAG:&str = "\n\n\n#### METADATA ####\n";
+/// The tag separating the module's code from the id map and metadata sections.
+pub const METADATA_TAG:&str = "\n\n\n#### METADATA ####\n";
 
 fn to_json_single_line(val:&impl Serialize) -> std::result::Result<String,serde_json::Error> {
     let json = serde_json::to_string(val)?;
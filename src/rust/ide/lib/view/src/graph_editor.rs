@@ -44,8 +44,11 @@ use frp::io::keyboard;
 // === Constants ===
 // =================
 
-const SNAP_DISTANCE_THRESHOLD         : f32 = 10.0;
-const VIZ_PREVIEW_MODE_TOGGLE_TIME_MS : f32 = 300.0;
+const SNAP_DISTANCE_THRESHOLD         : f32         = 10.0;
+const VIZ_PREVIEW_MODE_TOGGLE_TIME_MS : f32         = 300.0;
+const WARNING_COLOR                   : color::Rgba = color::Rgba::new(1.0, 0.8, 0.3, 0.9);
+const ERROR_COLOR                     : color::Rgba = color::Rgba::new(1.0, 0.4, 0.3, 0.9);
+const MESSAGE_LINE_HEIGHT             : f32         = 20.0;
 
 
 
@@ -305,6 +308,10 @@ ensogl::def_command_api! { Commands
     enter_selected_node,
     /// Steps out of the current node, popping the topmost stack frame from the crumb list.
     exit_node,
+    /// Undo the last change of the graph.
+    undo,
+    /// Redo the last undone change of the graph.
+    redo,
 
     /// Enable nodes multi selection mode. It works like inverse mode for single node selection and like merge mode for multi node selection mode.
     enable_node_multi_select,
@@ -340,6 +347,13 @@ ensogl::def_command_api! { Commands
     /// Switches the selected visualisation to/from fullscreen mode.
     toggle_fullscreen_for_selected_visualization,
 
+    /// Show the short value previews on all nodes permanently, not only on output ports hover.
+    enable_value_previews,
+    /// Show the short value previews only when the node's output ports are hovered.
+    disable_value_previews,
+    /// Toggle between showing the short value previews permanently and on output ports hover.
+    toggle_value_previews,
+
 
     /// Cancel the operation being currently performed. Often mapped to the escape key.
     cancel,
@@ -373,8 +387,16 @@ pub struct FrpInputs {
     pub remove_node                  : frp::Source<NodeId>,
     pub set_node_expression          : frp::Source<(NodeId,node::Expression)>,
     pub set_node_position            : frp::Source<(NodeId,Vector2)>,
+    pub set_node_error               : frp::Source<(NodeId,Option<node::Error>)>,
+    pub set_node_short_value         : frp::Source<(NodeId,Option<node::ShortValue>)>,
+    pub set_node_type                : frp::Source<(NodeId,Option<Type>)>,
     pub set_expression_type          : frp::Source<(ast::Id,Option<Type>)>,
     pub set_method_pointer           : frp::Source<(ast::Id,Option<MethodPointer>)>,
+    pub set_breadcrumbs              : frp::Source<Vec<MethodPointer>>,
+    pub set_warning                  : frp::Source<Option<String>>,
+    pub set_execution_error          : frp::Source<Option<String>>,
+    pub set_clipping_area            : frp::Source<Option<ClippingArea>>,
+    pub set_active                   : frp::Source<bool>,
     pub cycle_visualization          : frp::Source<NodeId>,
     pub set_visualization            : frp::Source<(NodeId,Option<visualization::Path>)>,
     pub register_visualization       : frp::Source<Option<visualization::Definition>>,
@@ -411,8 +433,16 @@ impl FrpInputs {
             remove_node                  <- source();
             set_node_expression          <- source();
             set_node_position            <- source();
+            set_node_error               <- source();
+            set_node_short_value         <- source();
+            set_node_type                <- source();
             set_expression_type          <- source();
             set_method_pointer           <- source();
+            set_breadcrumbs              <- source();
+            set_warning                  <- source();
+            set_execution_error          <- source();
+            set_clipping_area            <- source();
+            set_active                   <- source();
             set_visualization_data       <- source();
             cycle_visualization          <- source();
             set_visualization            <- source();
@@ -431,7 +461,11 @@ impl FrpInputs {
              ,remove_all_node_input_edges,remove_all_node_output_edges,set_visualization_data
              ,set_detached_edge_targets,set_edge_source,set_edge_target
              ,unset_edge_source,unset_edge_target
-             ,set_node_position,set_expression_type,set_method_pointer,select_node,remove_node
+             ,set_node_position,set_node_error,set_node_short_value,set_node_type
+             ,set_expression_type
+             ,set_method_pointer,set_breadcrumbs,set_warning,set_execution_error
+             ,set_clipping_area,set_active,select_node
+             ,remove_node
              ,set_node_expression,connect_nodes,deselect_all_nodes,cycle_visualization
              ,set_visualization,register_visualization,some_edge_targets_detached
              ,some_edge_sources_detached,all_edge_targets_attached,hover_node_input
@@ -518,6 +552,9 @@ generate_frp_outputs! {
     node_expression_set       : (NodeId,node::Expression),
     node_entered              : NodeId,
     node_exited               : (),
+    breadcrumb_selected       : usize,
+    undo_requested            : (),
+    redo_requested            : (),
 
     edge_added        : EdgeId,
     edge_removed      : EdgeId,
@@ -539,6 +576,7 @@ generate_frp_outputs! {
     visualization_disabled : NodeId,
     visualization_enable_fullscreen : NodeId,
     visualization_set_preprocessor  : (NodeId,data::EnsoCode),
+    visualization_chosen            : (NodeId,visualization::Path),
 
 }
 
@@ -741,6 +779,37 @@ impl Grid {
 
 
 
+// ====================
+// === ClippingArea ===
+// ====================
+
+/// A rectangular area of the graph editor, in its local coordinates. When set, the nodes whose
+/// positions lie outside the area are hidden, together with the edges attached to them.
+///
+/// The elements are hidden as a whole, there is no clipping of the sprites: a node positioned
+/// inside the area is displayed entirely, even if it reaches beyond the area's border.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct ClippingArea {
+    pub min : Vector2,
+    pub max : Vector2,
+}
+
+impl ClippingArea {
+    /// Constructor.
+    pub fn new(min:Vector2, max:Vector2) -> Self {
+        Self {min,max}
+    }
+
+    /// Check whether the point lies inside the area, including its borders.
+    pub fn contains(&self, point:Vector2) -> bool {
+        let inside_x = self.min.x <= point.x && point.x <= self.max.x;
+        let inside_y = self.min.y <= point.y && point.y <= self.max.y;
+        inside_x && inside_y
+    }
+}
+
+
+
 // =============
 // === Nodes ===
 // =============
@@ -978,9 +1047,16 @@ pub struct GraphEditorModel {
     pub display_object : display::object::Instance,
     pub scene          : Scene,
     pub project_name   : component::ProjectName,
+    pub breadcrumbs    : component::Breadcrumbs,
+    pub warning        : component::Message,
+    pub error          : component::Message,
     pub cursor         : cursor::Cursor,
     pub nodes          : Nodes,
     pub edges          : Edges,
+    pub visualizations : visualization::Registry,
+    vis_paths          : SharedHashMap<NodeId,visualization::Path>,
+    node_types         : SharedHashMap<NodeId,data::EnsoType>,
+    clipping_area      : Rc<Cell<Option<ClippingArea>>>,
     touch_state        : TouchState,
     frp                : FrpInputs,
 }
@@ -1001,6 +1077,10 @@ impl GraphEditorModel {
         let nodes          = Nodes::new(&logger);
 //        let visualizations = Stage::new(scene.clone_ref(), Logger::new("VisualisationCollection"));
         let edges          = default();
+        let visualizations = visualization::Registry::with_default_visualizations();
+        let vis_paths      = default();
+        let node_types     = default();
+        let clipping_area  = default();
         let frp            = FrpInputs::new(network);
         let touch_state    = TouchState::new(network,&scene.mouse.frp);
         let project_name   = component::ProjectName::new(scene,focus_manager);
@@ -1008,8 +1088,20 @@ impl GraphEditorModel {
         let screen = scene.camera().screen();
         let margin = 10.0;
         project_name.set_position(Vector3::new(0.0,screen.height / 2.0 - margin,0.0));
+        let breadcrumbs    = component::Breadcrumbs::new(scene);
+        display_object.add_child(&breadcrumbs);
+        let breadcrumbs_x  = -screen.width / 2.0 + margin;
+        breadcrumbs.set_position(Vector3::new(breadcrumbs_x,screen.height / 2.0 - margin,0.0));
+        let warning        = component::Message::new(scene,WARNING_COLOR);
+        display_object.add_child(&warning);
+        let warning_y      = -screen.height / 2.0 + margin;
+        warning.set_position(Vector3::new(breadcrumbs_x,warning_y,0.0));
+        let error          = component::Message::new(scene,ERROR_COLOR);
+        display_object.add_child(&error);
+        error.set_position(Vector3::new(breadcrumbs_x,warning_y + MESSAGE_LINE_HEIGHT,0.0));
         let scene = scene.clone_ref();
-        Self {logger,display_object,scene,cursor,nodes,edges,touch_state,frp,project_name}//visualizations }
+        Self {logger,display_object,scene,cursor,nodes,edges,visualizations,vis_paths,node_types
+             ,clipping_area,touch_state,frp,project_name,breadcrumbs,warning,error}
     }
 
     fn create_edge(&self) -> EdgeId {
@@ -1122,12 +1214,111 @@ impl GraphEditorModel {
         }
     }
 
+    /// The type of the node's value, or `Any` if it is not known.
+    fn node_type(&self, node_id:NodeId) -> data::EnsoType {
+        self.node_types.get_cloned(&node_id).unwrap_or_else(data::EnsoType::any)
+    }
+
+    /// Instantiate the given visualization on the node. Returns whether it succeeded.
+    fn set_node_visualization
+    (&self, node_id:NodeId, definition:&visualization::Definition) -> bool {
+        let node = match self.nodes.get_cloned_ref(&node_id) {
+            Some(node) => node,
+            None       => return false,
+        };
+        match definition.new_instance(&self.scene) {
+            Ok(vis) => {
+                self.vis_paths.insert(node_id,definition.signature.path.clone_ref());
+                node.visualization.frp.set_visualization.emit(Some(vis));
+                true
+            }
+            Err(err) => {
+                self.logger.warning(|| format!("Failed to instantiate visualisation: {:?}",err));
+                false
+            }
+        }
+    }
+
+    /// Set the node's visualization to the one registered under the given path. If it is not
+    /// registered yet, e.g. because the project's visualizations are still being loaded, the path
+    /// is remembered and the visualization is set once it is registered.
+    fn set_node_visualization_path(&self, node_id:NodeId, path:&Option<visualization::Path>) {
+        match path {
+            Some(path) => match self.visualizations.definition_from_path(path) {
+                Some(definition) => { self.set_node_visualization(node_id,&definition); }
+                None => if self.nodes.get_cloned_ref(&node_id).is_some() {
+                    self.logger.info(|| format!("Visualisation {:?} is pending.",path));
+                    self.vis_paths.insert(node_id,path.clone_ref());
+                },
+            },
+            None => if let Some(node) = self.nodes.get_cloned_ref(&node_id) {
+                self.vis_paths.remove(&node_id);
+                node.visualization.frp.set_visualization.emit(None)
+            },
+        }
+    }
+
+    /// Set the type of the node's value. If the node's visualization is not applicable to the new
+    /// type, it is replaced with the one preferred for this type. The pending visualization, which
+    /// is not registered yet, is kept.
+    fn set_node_type(&self, node_id:NodeId, tp:&Option<Type>) {
+        match tp {
+            None     => { self.node_types.remove(&node_id); }
+            Some(tp) => {
+                let tp         = data::EnsoType::new(tp.0.clone_ref());
+                let applicable = self.visualizations.valid_sources(&tp);
+                let current    = self.vis_paths.get_cloned(&node_id);
+                let is_current = |def:&visualization::Definition| {
+                    current.as_ref() == Some(&def.signature.path)
+                };
+                let is_pending = current.as_ref().map_or(false, |path| {
+                    self.visualizations.definition_from_path(path).is_none()
+                });
+                let keep       = is_pending || applicable.iter().any(is_current);
+                self.node_types.insert(node_id,tp.clone_ref());
+                if !keep {
+                    let definition = self.visualizations.default_definition(&tp);
+                    self.set_node_visualization(node_id,&definition);
+                }
+            }
+        }
+    }
+
+    /// Switch the node's visualization to the next one applicable to the node's type. Returns the
+    /// path of the newly chosen visualization.
+    fn cycle_visualization(&self, node_id:NodeId) -> Option<visualization::Path> {
+        let applicable = self.visualizations.valid_sources(&self.node_type(node_id));
+        let current    = self.vis_paths.get_cloned(&node_id);
+        let is_current = |def:&visualization::Definition| {
+            current.as_ref() == Some(&def.signature.path)
+        };
+        let position   = applicable.iter().position(is_current);
+        let next       = position.map_or(0, |position| (position + 1) % applicable.len());
+        let definition = applicable.get(next)?;
+        let chosen     = self.set_node_visualization(node_id,definition);
+        if chosen { Some(definition.signature.path.clone_ref()) } else { None }
+    }
+
+    /// Register the visualization definition, replacing the instances of the previously
+    /// registered definition with the same path.
+    fn register_visualization(&self, definition:&visualization::Definition) {
+        self.visualizations.add(definition.clone_ref());
+        let path     = &definition.signature.path;
+        let node_ids = self.vis_paths.raw.borrow().iter().filter(|(_,p)| *p == path)
+            .map(|(node_id,_)| *node_id).collect_vec();
+        for node_id in node_ids {
+            self.set_node_visualization(node_id,definition);
+        }
+    }
+
     /// Warning! This function does not remove connected edges. It needs to be handled by the
     /// implementation.
     fn remove_node(&self, node_id:impl Into<NodeId>) {
         let node_id = node_id.into();
         self.nodes.remove(&node_id);
         self.nodes.selected.remove_item(&node_id);
+        self.vis_paths.remove(&node_id);
+        self.node_types.remove(&node_id);
     }
 
     fn node_in_edges(&self, node_id:impl Into<NodeId>) -> Vec<EdgeId> {
@@ -1162,6 +1353,28 @@ impl GraphEditorModel {
         }
     }
 
+    fn set_node_error(&self, node_id:impl Into<NodeId>, error:&Option<node::Error>) {
+        let node_id = node_id.into();
+        if let Some(node) = self.nodes.get_cloned_ref(&node_id) {
+            node.frp.set_error.emit(error);
+        }
+    }
+
+    fn set_node_short_value
+    (&self, node_id:impl Into<NodeId>, value:&Option<node::ShortValue>) {
+        let node_id = node_id.into();
+        if let Some(node) = self.nodes.get_cloned_ref(&node_id) {
+            node.frp.set_short_value.emit(value);
+        }
+    }
+
+    fn set_node_value_pinned(&self, node_id:impl Into<NodeId>, pinned:bool) {
+        let node_id = node_id.into();
+        if let Some(node) = self.nodes.get_cloned_ref(&node_id) {
+            node.frp.set_value_pinned.emit(pinned);
+        }
+    }
+
     fn is_connection(&self, edge_id:impl Into<EdgeId>) -> bool {
         let edge_id = edge_id.into();
         match self.edges.get_cloned_ref(&edge_id) {
@@ -1172,6 +1385,58 @@ impl GraphEditorModel {
 }
 
 
+// === Clipping ===
+
+impl GraphEditorModel {
+    /// Hide the elements positioned outside the area, or display all of them if the area is
+    /// `None`. See `ClippingArea`.
+    fn set_clipping_area(&self, area:Option<ClippingArea>) {
+        self.clipping_area.set(area);
+        self.refresh_clipping(&self.project_name);
+        self.refresh_clipping(&self.breadcrumbs);
+        self.refresh_clipping(&self.warning);
+        self.refresh_clipping(&self.error);
+        for node_id in self.nodes.keys() {
+            if let Some(node) = self.nodes.get_cloned_ref(&node_id) {
+                self.refresh_clipping(&node);
+            }
+        }
+        for edge_id in self.edges.keys() {
+            self.refresh_edge_clipping(edge_id);
+        }
+    }
+
+    fn is_clipped(&self, position:Vector2) -> bool {
+        self.clipping_area.get().map_or(false,|area| !area.contains(position))
+    }
+
+    fn refresh_clipping(&self, object:&impl display::Object) {
+        let clipped = self.is_clipped(object.position().xy());
+        self.set_clipped(object,clipped);
+    }
+
+    /// The edge is clipped together with any of the nodes it is attached to. The detached edges
+    /// follow the mouse cursor, so they are always displayed.
+    fn refresh_edge_clipping(&self, edge_id:EdgeId) {
+        if let Some(edge) = self.edges.get_cloned_ref(&edge_id) {
+            let endpoints = edge.source().into_iter().chain(edge.target());
+            let clipped   = endpoints.filter_map(|endpoint| {
+                self.nodes.get_cloned_ref(&endpoint.node_id)
+            }).any(|node| !node.has_parent());
+            self.set_clipped(&edge,clipped);
+        }
+    }
+
+    fn set_clipped(&self, object:&impl display::Object, clipped:bool) {
+        if clipped {
+            object.unset_parent();
+        } else if !object.has_parent() {
+            self.add_child(object);
+        }
+    }
+}
+
+
 // === Connect ===
 
 impl GraphEditorModel {
@@ -1270,6 +1535,7 @@ impl GraphEditorModel {
                 t.x = position.x;
                 t.y = position.y;
             });
+            self.refresh_clipping(&node);
             for edge_id in self.node_in_and_out_edges(node_id) {
                 self.refresh_edge_position(edge_id);
             }
@@ -1299,6 +1565,7 @@ impl GraphEditorModel {
     pub fn refresh_edge_position(&self, edge_id:EdgeId) {
         self.refresh_edge_source_position(edge_id);
         self.refresh_edge_target_position(edge_id);
+        self.refresh_edge_clipping(edge_id);
     }
 
     pub fn refresh_edge_source_size(&self, edge_id:EdgeId) {
@@ -1371,6 +1638,17 @@ impl GraphEditor {
         self.frp.add_node.emit(());
         self.frp.outputs.node_added.value()
     }
+
+    /// Shortcut triggering the command only on the active graph editors, so many graph editors
+    /// may be displayed at once. The modifier keys are tracked by all the graph editors, so their
+    /// selection modes stay in sync when another graph editor gets activated.
+    fn active_shortcut
+    (action:impl Into<shortcut::Action>, command:impl Into<shortcut::Command>)
+    -> shortcut::Shortcut {
+        use application::shortcut::DefaultShortcutProvider;
+        let condition = shortcut::Condition::Simple("is_active".into());
+        Self::self_shortcut_when(action,command,condition)
+    }
 }
 
 impl application::command::Provider for GraphEditor {
@@ -1382,26 +1660,29 @@ impl application::command::Provider for GraphEditor {
 impl application::shortcut::DefaultShortcutProvider for GraphEditor {
     fn default_shortcuts() -> Vec<application::shortcut::Shortcut> {
         use keyboard::Key;
-        vec! [ Self::self_shortcut(shortcut::Action::press        (&[Key::Escape],&[])                              , "cancel_project_name_editing")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("n".into())],&[])  , "add_node_at_cursor")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Control,Key::Backspace],&[])              , "remove_selected_nodes")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character(" ".into())],&[])  , "press_visualization_visibility")
-             , Self::self_shortcut(shortcut::Action::double_press (&[Key::Control,Key::Character(" ".into())],&[])  , "double_press_visualization_visibility")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Control,Key::Character(" ".into())],&[])  , "release_visualization_visibility")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Meta],&[])                                , "toggle_node_multi_select")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Meta],&[])                                , "toggle_node_multi_select")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Control],&[])                             , "toggle_node_multi_select")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Control],&[])                             , "toggle_node_multi_select")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Shift],&[])                               , "toggle_node_merge_select")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Shift],&[])                               , "toggle_node_merge_select")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Alt],&[])                                 , "toggle_node_subtract_select")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Alt],&[])                                 , "toggle_node_subtract_select")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Shift,Key::Alt],&[])                      , "toggle_node_inverse_select")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Shift,Key::Alt],&[])                      , "toggle_node_inverse_select")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("d".into())],&[])  , "set_test_visualization_data_for_selected_node")
-             , Self::self_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("f".into())],&[])  , "cycle_visualization_for_selected_node")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Control,Key::Enter],&[])                  , "enter_selected_node")
-             , Self::self_shortcut(shortcut::Action::release      (&[Key::Control,Key::ArrowUp],&[])                , "exit_node")
+        vec! [ Self::active_shortcut(shortcut::Action::press        (&[Key::Escape],&[])                              , "cancel_project_name_editing")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("n".into())],&[])  , "add_node_at_cursor")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Backspace],&[])              , "remove_selected_nodes")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character(" ".into())],&[])  , "press_visualization_visibility")
+             , Self::active_shortcut(shortcut::Action::double_press (&[Key::Control,Key::Character(" ".into())],&[])  , "double_press_visualization_visibility")
+             , Self::active_shortcut(shortcut::Action::release      (&[Key::Control,Key::Character(" ".into())],&[])  , "release_visualization_visibility")
+             , Self::self_shortcut  (shortcut::Action::press        (&[Key::Meta],&[])                                , "toggle_node_multi_select")
+             , Self::self_shortcut  (shortcut::Action::release      (&[Key::Meta],&[])                                , "toggle_node_multi_select")
+             , Self::self_shortcut  (shortcut::Action::press        (&[Key::Control],&[])                             , "toggle_node_multi_select")
+             , Self::self_shortcut  (shortcut::Action::release      (&[Key::Control],&[])                             , "toggle_node_multi_select")
+             , Self::self_shortcut  (shortcut::Action::press        (&[Key::Shift],&[])                               , "toggle_node_merge_select")
+             , Self::self_shortcut  (shortcut::Action::release      (&[Key::Shift],&[])                               , "toggle_node_merge_select")
+             , Self::self_shortcut  (shortcut::Action::press        (&[Key::Alt],&[])                                 , "toggle_node_subtract_select")
+             , Self::self_shortcut  (shortcut::Action::release      (&[Key::Alt],&[])                                 , "toggle_node_subtract_select")
+             , Self::self_shortcut  (shortcut::Action::press        (&[Key::Shift,Key::Alt],&[])                      , "toggle_node_inverse_select")
+             , Self::self_shortcut  (shortcut::Action::release      (&[Key::Shift,Key::Alt],&[])                      , "toggle_node_inverse_select")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("d".into())],&[])  , "set_test_visualization_data_for_selected_node")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("f".into())],&[])  , "cycle_visualization_for_selected_node")
+             , Self::active_shortcut(shortcut::Action::release      (&[Key::Control,Key::Enter],&[])                  , "enter_selected_node")
+             , Self::active_shortcut(shortcut::Action::release      (&[Key::Control,Key::ArrowUp],&[])                , "exit_node")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("z".into())],&[])  , "undo")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Control,Key::Character("y".into())],&[])  , "redo")
+             , Self::active_shortcut(shortcut::Action::press        (&[Key::Alt,Key::Character("v".into())],&[])      , "toggle_value_previews")
              ]
     }
 }
@@ -1476,8 +1757,6 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     let inputs         = &model.frp;
     let mouse          = &scene.mouse.frp;
     let touch          = &model.touch_state;
-    let visualizations = visualization::Registry::with_default_visualizations();
-    let logger         = &model.logger;
     let outputs        = UnsealedFrpOutputs::new();
     let sealed_outputs = outputs.seal(); // Done here to keep right eval order.
 
@@ -1497,9 +1776,21 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     }
 
 
+    // === Status ===
+    frp::extend! { network
+    def is_empty_src  = source::<bool>();
+    def is_active = inputs.set_active.sampler();
+    def is_empty  = is_empty_src.sampler();
+    }
+    // The graph editor is active until its owner activates another one, e.g. when many graph
+    // editors are displayed side by side.
+    inputs.set_active.emit(true);
+
+
     // === Selection Target Redirection ===
     frp::extend! { network
-    mouse_down_target <- mouse.down.map(f_!(model.scene.mouse.target.get()));
+    active_mouse_down <- mouse.down.gate(&is_active);
+    mouse_down_target <- active_mouse_down.map(f_!(model.scene.mouse.target.get()));
     mouse_up_target   <- mouse.up.map(f_!(model.scene.mouse.target.get()));
     background_up     <- mouse_up_target.map(|t| if t==&display::scene::Target::Background {Some(())} else {None}).unwrap();
 
@@ -1679,6 +1970,26 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     }
 
 
+    // === Value Previews ===
+    frp::extend! { network
+
+    let value_previews_pinned = enable_disable_toggle
+        ( network
+        , &inputs.enable_value_previews
+        , &inputs.disable_value_previews
+        , &inputs.toggle_value_previews
+        );
+
+    new_node_value_pinned <- new_node.map2(&value_previews_pinned,|id,pinned| (*id,*pinned));
+    eval new_node_value_pinned (((id,pinned)) model.set_node_value_pinned(id,*pinned));
+    eval value_previews_pinned ((pinned) {
+        for node_id in model.all_nodes() {
+            model.set_node_value_pinned(node_id,*pinned);
+        }
+    });
+    }
+
+
     // === Node Connect ===
 
     frp::extend! { network
@@ -1739,6 +2050,16 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     outputs.node_expression_set <+ inputs.set_node_expression;
 
 
+    // === Set Node Error ===
+
+    eval inputs.set_node_error (((id,error)) model.set_node_error(id,error));
+
+
+    // === Set Node Short Value ===
+
+    eval inputs.set_node_short_value (((id,value)) model.set_node_short_value(id,value));
+
+
 
     // ==================
     // === Move Nodes ===
@@ -1956,30 +2277,10 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
 //    }));
 
 
-   // === Vis Set ===
-
-   def _update_vis_data = inputs.set_visualization.map(f!([logger,nodes,scene,visualizations]((node_id,vis_path)) {
-       match (&nodes.get_cloned_ref(node_id), vis_path) {
-            (Some(node), Some(vis_path)) => {
-                let vis_definition = visualizations.definition_from_path(vis_path);
-                if let Some(definition) = vis_definition {
-                    match definition.new_instance(&scene) {
-                        Ok(vis)  => node.visualization.frp.set_visualization.emit(Some(vis)),
-                        Err(err) => {
-                            logger.warning(
-                                || format!("Failed to instantiate visualisation: {:?}",err));
-                        },
-                    };
-                } else {
-                    logger.warning(|| format!("Failed to get visualisation: {:?}",vis_path));
-                }
-            },
-            (Some(node), None) => node.visualization.frp.set_visualization.emit(None),
-             _                 => logger.warning(|| format!("Failed to get node: {:?}",node_id)),
-
-       }
+    // === Vis Set ===
 
-   }));
+    eval inputs.set_visualization (((id,path)) model.set_node_visualization_path(*id,path));
+    eval inputs.set_node_type     (((id,tp))   model.set_node_type(*id,tp));
 
 
     // === Vis Update Data ===
@@ -2004,22 +2305,8 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
      nodes_to_cycle <= inputs.cycle_visualization_for_selected_node.map(f_!(model.selected_nodes()));
      node_to_cycle  <- any(nodes_to_cycle,inputs.cycle_visualization);
 
-     let cycle_count = Rc::new(Cell::new(0));
-     def _cycle_visualization = node_to_cycle.map(f!([scene,nodes,visualizations,logger](node_id) {
-        let visualizations = visualizations.valid_sources(&"Any".into());
-        cycle_count.set(cycle_count.get() % visualizations.len());
-        let vis  = &visualizations[cycle_count.get()];
-        let vis  = vis.new_instance(&scene);
-        let node = nodes.get_cloned_ref(node_id);
-        match (vis, node) {
-            (Ok(vis), Some(node))  => {
-                node.visualization.frp.set_visualization.emit(Some(vis));
-            },
-            (Err(e), _) => logger.warning(|| format!("Failed to cycle visualization: {:?}", e)),
-            _           => {}
-        };
-        cycle_count.set(cycle_count.get() + 1);
-    }));
+     vis_cycled <- node_to_cycle.map(f!((id) model.cycle_visualization(*id).map(|p| (*id,p))));
+     outputs.visualization_chosen <+ vis_cycled.unwrap();
 
 
     // === Visualization toggle ===
@@ -2064,11 +2351,11 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
 
     // === Register Visualization ===
 
-    def _register_visualization = inputs.register_visualization.map(f!([visualizations](handle) {
-        if let Some(handle) = handle {
-            visualizations.add(handle);
+    eval inputs.register_visualization ((definition) {
+        if let Some(definition) = definition {
+            model.register_visualization(definition);
         }
-    }));
+    });
 
 
     // === Entering and Exiting Nodes ===
@@ -2078,6 +2365,33 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     outputs.node_exited  <+ inputs.exit_node;
 
 
+    // === Breadcrumbs ===
+
+    eval inputs.set_breadcrumbs ((methods) model.breadcrumbs.frp.set_breadcrumbs.emit(methods));
+    outputs.breadcrumb_selected <+ model.breadcrumbs.frp.outputs.breadcrumb_selected;
+
+
+    // === Warning ===
+
+    eval inputs.set_warning ((warning) model.warning.frp.set_message.emit(warning));
+
+
+    // === Execution Error ===
+
+    eval inputs.set_execution_error ((error) model.error.frp.set_message.emit(error));
+
+
+    // === Clipping ===
+
+    eval inputs.set_clipping_area ((area) model.set_clipping_area(*area));
+
+
+    // === Undo and Redo ===
+
+    outputs.undo_requested <+ inputs.undo;
+    outputs.redo_requested <+ inputs.redo;
+
+
     // === OUTPUTS REBIND ===
 
     outputs.some_edge_targets_detached <+ inputs.some_edge_targets_detached;
@@ -2106,14 +2420,6 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     outputs.connection_removed <+ outputs.edge_removed;
 
 
-    // === Status ===
-
-    def is_active_src = source::<bool>();
-    def is_empty_src  = source::<bool>();
-    def is_active = is_active_src.sampler();
-    def is_empty  = is_empty_src.sampler();
-
-
     // === Remove implementation ===
     outputs.node_removed <+ inputs.remove_node;
 
@@ -2131,10 +2437,6 @@ fn new_graph_editor(app:&Application) -> GraphEditor {
     outputs.edge_removed <+ edges_to_rm;
     }
 
-    // FIXME This is a temporary solution. Should be replaced by a real thing once layout
-    //       management is implemented.
-    is_active_src.emit(true);
-
     let status = FrpStatus {is_active,is_empty};
 
     let node_release = touch.nodes.up.clone_ref();
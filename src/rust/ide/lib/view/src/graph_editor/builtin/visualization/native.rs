@@ -1,7 +1,11 @@
 //! Examples of defining visualization in Rust using web_sys or ensogl.
 
 pub mod bubble_chart;
+pub mod plot;
 pub mod raw_text;
+pub mod table;
 
 pub use bubble_chart::*;
+pub use plot::*;
 pub use raw_text::*;
+pub use table::*;
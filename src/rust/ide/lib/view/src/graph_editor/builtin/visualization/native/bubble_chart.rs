@@ -56,11 +56,8 @@ pub struct BubbleChartModel {
 
 impl BubbleChartModel {
     fn receive_data(&self, data:&Data) -> Result<(),DataError> {
-        let data_inner = match data {
-            Data::Json {content} => content,
-            _ => todo!() // FIXME
-        };
-        let data_inner:&serde_json::Value = data_inner;
+        let data_inner = data.as_json()?;
+        let data_inner:&serde_json::Value = &data_inner;
         let data_inner: Rc<Vec<Vector3<f32>>> = if let Ok(result) = serde_json::from_value(data_inner.clone()) {
             result
         } else {
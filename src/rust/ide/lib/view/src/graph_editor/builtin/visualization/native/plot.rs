@@ -0,0 +1,735 @@
+//! Scatter and line plot visualisations implemented using the native shape and text systems.
+//!
+//! Both plots consume an array of points, either as JSON array of `[x,y]` pairs, or as a binary
+//! payload. The binary payload is either the UTF-8 encoded JSON document, or the
+//! `BINARY_POINTS_MAGIC` followed by the coordinates of the points encoded as consecutive
+//! little-endian `f32` values.
+//!
+//! The plot is panned with the mouse wheel and zoomed with the mouse wheel while holding `ctrl`.
+//! The wheel is captured while the mouse cursor is over the plot, so the other wheel handlers of
+//! the scene ignore it, see `WheelCapture`. Hovering a point shows its coordinates.
+//!
+//! The plot of large data displays at most `MAX_POINTS` dots or line vertices, see `decimate`.
+
+use crate::prelude::*;
+
+use crate::graph_editor::component::node::port;
+use crate::graph_editor::component::visualization::*;
+use crate::graph_editor::component::visualization;
+
+use enso_frp as frp;
+use ensogl::control::callback;
+use ensogl::control::io::mouse;
+use ensogl::data::color;
+use ensogl::display::scene::Scene;
+use ensogl::display::scene::WheelCaptureGuard;
+use ensogl::display::shape;
+use ensogl::display::traits::*;
+use ensogl::display::Sprite;
+use ensogl::display;
+use ensogl::gui::component;
+use std::cmp::Ordering;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const TEXT_SIZE       : f32 = 12.0;
+// FIXME hardcoded literal, the same as in the node's port manager.
+const GLYPH_WIDTH     : f32 = 7.224_609_4;
+const MARGIN_LEFT     : f32 = 50.0;
+const MARGIN_BOTTOM   : f32 = 30.0;
+const MARGIN_TOP      : f32 = 10.0;
+const MARGIN_RIGHT    : f32 = 10.0;
+const TICK_COUNT      : f32 = 5.0;
+const TICK_LENGTH     : f32 = 4.0;
+const LINE_WIDTH      : f32 = 1.5;
+const POINT_RADIUS    : f32 = 3.0;
+const HOVER_DISTANCE  : f32 = 8.0;
+const TOOLTIP_OFFSET  : f32 = 8.0;
+/// The margin around the data, as a fraction of the data's extent.
+const DATA_MARGIN     : f32 = 0.05;
+/// The zoom factor change per one pixel of the mouse wheel movement.
+const ZOOM_SPEED      : f32 = 0.002;
+/// The maximum number of dots of the scatter plot, or vertices of the line plot, displayed.
+const MAX_POINTS      : usize = 2000;
+
+/// The prefix of the binary payload with the points' coordinates, distinguishing it from the
+/// UTF-8 encoded JSON document.
+pub const BINARY_POINTS_MAGIC : &[u8] = b"EPLT";
+
+
+
+// ==============
+// === Shapes ===
+// ==============
+
+/// A single point of the scatter plot.
+pub mod point {
+    use super::*;
+    use ensogl::display::shape::*;
+    use ensogl::display::scene::Scene;
+    use ensogl::display::Sprite;
+    use ensogl::display::Buffer;
+    use ensogl::display::Attribute;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let point = Circle(POINT_RADIUS.px());
+            let point = point.fill(color::Rgba::new(0.17,0.46,0.15,1.0));
+            point.into()
+        }
+    }
+}
+
+/// A single segment of the line plot.
+pub mod segment {
+    use super::*;
+    use ensogl::display::shape::*;
+    use ensogl::display::scene::Scene;
+    use ensogl::display::Sprite;
+    use ensogl::display::Buffer;
+    use ensogl::display::Attribute;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let width   : Var<Pixels> = "input_size.x".into();
+            let height  : Var<Pixels> = "input_size.y".into();
+            let segment = Rect((&width,&height));
+            let segment = segment.fill(color::Rgba::new(0.17,0.46,0.15,1.0));
+            segment.into()
+        }
+    }
+}
+
+/// A line of the axes: either the axis itself or one of its ticks.
+pub mod axis {
+    use super::*;
+    use ensogl::display::shape::*;
+    use ensogl::display::scene::Scene;
+    use ensogl::display::Sprite;
+    use ensogl::display::Buffer;
+    use ensogl::display::Attribute;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let width  : Var<Pixels> = "input_size.x".into();
+            let height : Var<Pixels> = "input_size.y".into();
+            let line   = Rect((&width,&height));
+            let line   = line.fill(color::Rgba::new(1.0,1.0,1.0,0.3));
+            line.into()
+        }
+    }
+}
+
+
+
+// ==============
+// === Bounds ===
+// ==============
+
+/// An axis-aligned rectangle, describing either the visible part of the data or the area of the
+/// plot.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub struct Bounds {
+    pub min : Vector2,
+    pub max : Vector2,
+}
+
+impl Bounds {
+    /// The bounds of all the points, with a margin around them. If all the points have the same
+    /// coordinate, the bounds are extended so they are not empty.
+    pub fn containing(points:&[Vector2]) -> Self {
+        let first    = points.first().copied().unwrap_or_else(zero);
+        let min      = |a:Vector2,b:&Vector2| Vector2(a.x.min(b.x),a.y.min(b.y));
+        let max      = |a:Vector2,b:&Vector2| Vector2(a.x.max(b.x),a.y.max(b.y));
+        let min      = points.iter().fold(first,min);
+        let max      = points.iter().fold(first,max);
+        let non_zero = |length:f32| if length > 0.0 { length } else { 1.0 };
+        let size     = max - min;
+        let size     = Vector2(non_zero(size.x),non_zero(size.y));
+        let min      = (min + max - size) / 2.0;
+        let max      = min + size;
+        let margin   = size * DATA_MARGIN;
+        Self {min:min-margin,max:max+margin}
+    }
+
+    /// The width and height.
+    pub fn size(&self) -> Vector2 {
+        self.max - self.min
+    }
+
+    /// Check if the point is within the bounds.
+    pub fn contains(&self, point:Vector2) -> bool {
+        let within_x = self.min.x <= point.x && point.x <= self.max.x;
+        let within_y = self.min.y <= point.y && point.y <= self.max.y;
+        within_x && within_y
+    }
+
+    /// Scale the bounds by the given factor, keeping the focus point in place.
+    pub fn zoom(&self, focus:Vector2, factor:f32) -> Self {
+        let min = focus + (self.min - focus) * factor;
+        let max = focus + (self.max - focus) * factor;
+        Self {min,max}
+    }
+
+    /// Move the bounds by the given vector.
+    pub fn pan(&self, delta:Vector2) -> Self {
+        Self {min:self.min+delta,max:self.max+delta}
+    }
+
+    /// Clip the segment to the bounds, returning its visible part, if any.
+    pub fn clip(&self, start:Vector2, end:Vector2) -> Option<(Vector2,Vector2)> {
+        // Liang–Barsky algorithm: the segment is parametrized with `t` from 0 to 1, and for every
+        // edge the range of `t` is narrowed to the part inside the edge.
+        let delta     = end - start;
+        let mut t_min = 0.0_f32;
+        let mut t_max = 1.0_f32;
+        let edges     = [ (-delta.x, start.x - self.min.x), (delta.x, self.max.x - start.x)
+                        , (-delta.y, start.y - self.min.y), (delta.y, self.max.y - start.y) ];
+        for (p,q) in edges.iter() {
+            if p.abs() < std::f32::EPSILON {
+                if *q < 0.0 { return None }
+            } else if *p < 0.0 {
+                t_min = t_min.max(q / p);
+            } else {
+                t_max = t_max.min(q / p);
+            }
+        }
+        if t_min > t_max { None } else { Some((start + delta * t_min, start + delta * t_max)) }
+    }
+}
+
+
+
+// =============
+// === Ticks ===
+// =============
+
+/// A step between ticks being a round number, giving about `TICK_COUNT` ticks in the range.
+fn tick_step(range:f32) -> f32 {
+    let raw       = range / TICK_COUNT;
+    let magnitude = 10.0_f32.powf(raw.log10().floor());
+    let residual  = raw / magnitude;
+    let nice      = if      residual > 5.0 { 10.0 }
+                    else if residual > 2.0 { 5.0  }
+                    else if residual > 1.0 { 2.0  }
+                    else                   { 1.0  };
+    nice * magnitude
+}
+
+/// All multiples of the step within the range.
+fn ticks(min:f32, max:f32, step:f32) -> Vec<f32> {
+    if !(step.is_finite() && step > 0.0) { return default() }
+    let first = (min / step).ceil() as i64;
+    let last  = (max / step).floor() as i64;
+    (first..=last).map(|index| index as f32 * step).collect()
+}
+
+/// The tick's label, with as many decimal places as the step requires.
+fn tick_label(value:f32, step:f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}",decimals,value)
+}
+
+fn text_width(text:&str) -> f32 {
+    text.chars().count() as f32 * GLYPH_WIDTH
+}
+
+
+
+// ==================
+// === Decimation ===
+// ==================
+
+/// Reduce the number of displayed points, given in the screen coordinates. Only the first point in
+/// each square of the `POINT_RADIUS` size is kept, as the next ones would mostly overlap it. If
+/// there are still more than `MAX_POINTS` points, every n-th of them is kept.
+fn decimate(positions:impl IntoIterator<Item=Vector2>) -> Vec<Vector2> {
+    let mut occupied = HashSet::new();
+    let positions    = positions.into_iter().filter(|position| {
+        let square = (position / POINT_RADIUS).map(|coordinate| coordinate.floor() as i64);
+        occupied.insert((square.x,square.y))
+    }).collect_vec();
+    let step = (positions.len() + MAX_POINTS - 1) / MAX_POINTS;
+    positions.into_iter().step_by(step.max(1)).collect()
+}
+
+
+
+// ====================
+// === Data Parsing ===
+// ====================
+
+/// Parse the plot's points. The points with non-finite coordinates are skipped.
+fn parse_points(data:&Data) -> Result<Vec<Vector2>,DataError> {
+    let points = match data {
+        Data::Binary {content} if content.starts_with(BINARY_POINTS_MAGIC) => {
+            let coordinates = &content[BINARY_POINTS_MAGIC.len()..];
+            if coordinates.len() % 8 != 0 {
+                return Err(DataError::InvalidDataType)
+            }
+            let coordinate = |bytes:&[u8]| {
+                f32::from_le_bytes([bytes[0],bytes[1],bytes[2],bytes[3]])
+            };
+            coordinates.chunks_exact(8).map(|point| {
+                Vector2(coordinate(&point[0..4]),coordinate(&point[4..8]))
+            }).collect()
+        }
+        _ => data.deserialize::<Vec<Vector2>>()?,
+    };
+    Ok(points.into_iter().filter(|point| point.x.is_finite() && point.y.is_finite()).collect())
+}
+
+
+
+// ================
+// === PlotKind ===
+// ================
+
+/// The way the points are displayed.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum PlotKind {
+    /// Each point is displayed as a dot.
+    Scatter,
+    /// The points, sorted by the x coordinate, are connected with a line.
+    Line,
+}
+
+impl PlotKind {
+    fn path(self) -> Path {
+        match self {
+            PlotKind::Scatter => Path::builtin("Scatter Plot (native)"),
+            PlotKind::Line    => Path::builtin("Line Plot (native)"),
+        }
+    }
+}
+
+
+
+// =================
+// === PlotModel ===
+// =================
+
+/// The state of the plot and the views of its visible part.
+#[derive(Clone,CloneRef,Debug)]
+#[allow(missing_docs)]
+pub struct PlotModel {
+    logger         : Logger,
+    display_object : display::object::Instance,
+    scene          : Scene,
+    kind           : PlotKind,
+    points         : Rc<RefCell<Vec<Vector2>>>,
+    /// The visible part of the data.
+    viewport       : Rc<Cell<Bounds>>,
+    size           : Rc<Cell<Vector2>>,
+    mouse_position : Rc<Cell<Vector2>>,
+    /// Keeps the wheel events captured while the mouse cursor is over the plot, so they pan and
+    /// zoom the plot instead of navigating the scene.
+    wheel_capture  : Rc<RefCell<Option<WheelCaptureGuard>>>,
+    axes           : Rc<RefCell<Vec<component::ShapeView<axis::Shape>>>>,
+    labels         : Rc<RefCell<Vec<component::ShapeView<port::label::Shape>>>>,
+    dots           : Rc<RefCell<Vec<component::ShapeView<point::Shape>>>>,
+    segments       : Rc<RefCell<Vec<component::ShapeView<segment::Shape>>>>,
+    tooltip        : component::ShapeView<port::label::Shape>,
+}
+
+impl PlotModel {
+    fn new(scene:&Scene, kind:PlotKind) -> Self {
+        let logger         = Logger::new("Plot");
+        let display_object = display::object::Instance::new(&logger);
+        let scene          = scene.clone_ref();
+        let points         = default();
+        let viewport       = Rc::new(Cell::new(Bounds::containing(&[])));
+        let size           = Rc::new(Cell::new(Vector2(200.0,200.0)));
+        let mouse_position = Rc::new(Cell::new(zero()));
+        let wheel_capture  = default();
+        let axes           = default();
+        let labels         = default();
+        let dots           = default();
+        let segments       = default();
+        let tooltip        = component::ShapeView::new(&logger,&scene);
+        Self {logger,display_object,scene,kind,points,viewport,size,mouse_position,wheel_capture
+             ,axes,labels,dots,segments,tooltip}
+    }
+
+    fn receive_data(&self, data:&Data) -> Result<(),DataError> {
+        let mut points = parse_points(data)?;
+        debug!(self.logger, "Received {points.len()} points.");
+        if self.kind == PlotKind::Line {
+            points.sort_by(|a,b| a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal));
+        }
+        self.viewport.set(Bounds::containing(&points));
+        *self.points.borrow_mut() = points;
+        self.update();
+        Ok(())
+    }
+
+    fn set_size(&self, size:Vector2) {
+        self.size.set(size);
+        self.update();
+    }
+
+    fn set_mouse_position(&self, position:Vector2) {
+        self.mouse_position.set(position);
+        self.update_wheel_capture();
+        self.update_tooltip();
+    }
+
+    /// Capture the wheel while the plot is hovered, and release it otherwise.
+    fn update_wheel_capture(&self) {
+        let hovered           = self.is_hovered();
+        let mut wheel_capture = self.wheel_capture.borrow_mut();
+        if !hovered {
+            *wheel_capture = None;
+        } else if wheel_capture.is_none() {
+            *wheel_capture = Some(self.scene.mouse.wheel_capture.capture());
+        }
+    }
+
+    fn handle_wheel(&self, event:&mouse::OnWheel) {
+        if self.wheel_capture.borrow().is_some() {
+            let mouse = self.local_mouse_position();
+            let area  = self.plot_area();
+            event.prevent_default();
+            let viewport = self.viewport.get();
+            let viewport = if event.ctrl_key() {
+                let factor = (event.delta_y() as f32 * ZOOM_SPEED).exp();
+                viewport.zoom(self.screen_to_data(mouse),factor)
+            } else {
+                let delta = Vector2(event.delta_x() as f32,-event.delta_y() as f32);
+                let scale = viewport.size().component_div(&area.size());
+                viewport.pan(delta.component_mul(&scale))
+            };
+            self.viewport.set(viewport);
+            self.update();
+        }
+    }
+
+    /// The mouse position in the plot's coordinate system, the origin being its center.
+    fn local_mouse_position(&self) -> Vector2 {
+        let mouse    = self.mouse_position.get();
+        let mouse    = self.scene.screen_to_scene_coordinates(Vector3(mouse.x,mouse.y,0.0));
+        let position = self.display_object.global_position();
+        Vector2(mouse.x - position.x,mouse.y - position.y)
+    }
+
+    /// Check whether the mouse cursor is over the area of the displayed plot.
+    fn is_hovered(&self) -> bool {
+        let visible = !self.display_object.is_orphan() && self.display_object.is_visible();
+        visible && self.plot_area().contains(self.local_mouse_position())
+    }
+
+    /// The area of the plot, without the margins containing the axes' labels.
+    fn plot_area(&self) -> Bounds {
+        let half = self.size.get() / 2.0;
+        let min  = Vector2(-half.x + MARGIN_LEFT,-half.y + MARGIN_BOTTOM);
+        let max  = Vector2( half.x - MARGIN_RIGHT, half.y - MARGIN_TOP);
+        Bounds {min,max}
+    }
+
+    /// Return the function transforming the data coordinates to the position in the plot.
+    fn data_to_screen(&self) -> impl Fn(Vector2) -> Vector2 {
+        let area     = self.plot_area();
+        let viewport = self.viewport.get();
+        let scale    = area.size().component_div(&viewport.size());
+        move |point| area.min + (point - viewport.min).component_mul(&scale)
+    }
+
+    fn screen_to_data(&self, position:Vector2) -> Vector2 {
+        let area     = self.plot_area();
+        let viewport = self.viewport.get();
+        let scale    = viewport.size().component_div(&area.size());
+        viewport.min + (position - area.min).component_mul(&scale)
+    }
+
+    fn update(&self) {
+        self.update_axes();
+        match self.kind {
+            PlotKind::Scatter => self.update_dots(),
+            PlotKind::Line    => self.update_segments(),
+        }
+        self.update_tooltip();
+    }
+
+    /// Update the axes along the left and bottom edge of the plot, their ticks and labels.
+    fn update_axes(&self) {
+        let area       = self.plot_area();
+        let viewport   = self.viewport.get();
+        let to_screen  = self.data_to_screen();
+        let mut lines  = vec![(area.min,Vector2(area.max.x,area.min.y))
+                             ,(area.min,Vector2(area.min.x,area.max.y))];
+        let mut labels = vec![];
+        let x_step     = tick_step(viewport.size().x);
+        for x in ticks(viewport.min.x,viewport.max.x,x_step) {
+            let position = to_screen(Vector2(x,viewport.min.y)).x;
+            let text     = tick_label(x,x_step);
+            let label_x  = position - text_width(&text) / 2.0;
+            let label_y  = area.min.y - TICK_LENGTH - TEXT_SIZE * 1.5;
+            lines.push((Vector2(position,area.min.y - TICK_LENGTH),Vector2(position,area.min.y)));
+            labels.push((text,Vector2(label_x,label_y)));
+        }
+        let y_step = tick_step(viewport.size().y);
+        for y in ticks(viewport.min.y,viewport.max.y,y_step) {
+            let position = to_screen(Vector2(viewport.min.x,y)).y;
+            let text     = tick_label(y,y_step);
+            let label_x  = area.min.x - TICK_LENGTH * 2.0 - text_width(&text);
+            let label_y  = position - TEXT_SIZE / 2.0;
+            lines.push((Vector2(area.min.x - TICK_LENGTH,position),Vector2(area.min.x,position)));
+            labels.push((text,Vector2(label_x,label_y)));
+        }
+
+        let mut axes = self.axes.borrow_mut();
+        axes.resize_with(lines.len(),|| self.new_view());
+        for (view,(start,end)) in axes.iter().zip(lines) {
+            place_segment(view,&view.shape.sprite,start,end);
+        }
+        let mut label_views = self.labels.borrow_mut();
+        label_views.resize_with(labels.len(),|| self.new_view());
+        for (view,(text,position)) in label_views.iter().zip(labels) {
+            view.shape.label.set_text(text);
+            view.set_position(Vector3(position.x,position.y,0.0));
+        }
+    }
+
+    /// Update the dots of the scatter plot. Only the points within the plot's area are displayed,
+    /// decimated if there are too many of them.
+    fn update_dots(&self) {
+        let area      = self.plot_area();
+        let to_screen = self.data_to_screen();
+        let points    = self.points.borrow();
+        let positions = points.iter().map(|point| to_screen(*point));
+        let positions = decimate(positions.filter(|position| area.contains(*position)));
+        let mut dots  = self.dots.borrow_mut();
+        dots.resize_with(positions.len(),|| {
+            let dot  = self.new_view::<point::Shape>();
+            let size = (POINT_RADIUS + 1.0) * 2.0;
+            dot.shape.sprite.size.set(Vector2(size,size));
+            dot
+        });
+        for (dot,position) in dots.iter().zip(positions) {
+            dot.set_position(Vector3(position.x,position.y,0.0));
+        }
+    }
+
+    /// Update the segments of the line plot, see `line_segments`.
+    fn update_segments(&self) {
+        let area         = self.plot_area();
+        let to_screen    = self.data_to_screen();
+        let points       = self.points.borrow();
+        let lines        = line_segments(points.iter().map(|point| to_screen(*point)),area);
+        let mut segments = self.segments.borrow_mut();
+        segments.resize_with(lines.len(),|| self.new_view());
+        for (segment,(start,end)) in segments.iter().zip(lines) {
+            place_segment(segment,&segment.shape.sprite,start,end);
+        }
+    }
+
+    /// Show the coordinates of the point closest to the mouse cursor, if it is close enough. The
+    /// points are searched only while the plot is hovered.
+    fn update_tooltip(&self) {
+        if !self.is_hovered() {
+            self.tooltip.unset_parent();
+            return
+        }
+        let mouse     = self.local_mouse_position();
+        let to_screen = self.data_to_screen();
+        let points    = self.points.borrow();
+        let distance  = |point:&Vector2| (to_screen(*point) - mouse).norm();
+        let nearest   = points.iter().min_by(|a,b| {
+            distance(a).partial_cmp(&distance(b)).unwrap_or(Ordering::Equal)
+        });
+        let nearest   = nearest.filter(|point| distance(point) <= HOVER_DISTANCE);
+        match nearest {
+            Some(point) => {
+                let position = to_screen(*point) + Vector2(TOOLTIP_OFFSET,TOOLTIP_OFFSET);
+                self.tooltip.shape.label.set_text(format!("({}, {})",point.x,point.y));
+                self.tooltip.set_position(Vector3(position.x,position.y,0.0));
+                self.display_object.add_child(&self.tooltip);
+            }
+            None => self.tooltip.unset_parent(),
+        }
+    }
+
+    fn new_view<S:shape::system::Shape>(&self) -> component::ShapeView<S> {
+        let view = component::ShapeView::new(&self.logger,&self.scene);
+        self.display_object.add_child(&view);
+        view
+    }
+}
+
+/// Stretch and rotate the sprite of the rectangular shape, so it forms a line between the points.
+/// The segments of the line connecting the points, given in the screen coordinates. The points
+/// are decimated like the dots of the scatter plot, and the segments are clipped to the area.
+fn line_segments
+(positions:impl IntoIterator<Item=Vector2>, area:Bounds) -> Vec<(Vector2,Vector2)> {
+    let positions = decimate(positions);
+    positions.into_iter().tuple_windows().filter_map(|(start,end)| area.clip(start,end)).collect()
+}
+
+fn place_segment(view:&impl display::Object, sprite:&Sprite, start:Vector2, end:Vector2) {
+    let delta    = end - start;
+    let center   = (start + end) / 2.0;
+    let rotation = delta.y.atan2(delta.x);
+    sprite.size.set(Vector2(delta.norm(),LINE_WIDTH));
+    view.set_position(Vector3(center.x,center.y,0.0));
+    view.set_rotation(Vector3(0.0,0.0,rotation));
+}
+
+
+
+// ============
+// === Plot ===
+// ============
+
+/// Scatter or line plot, with axes adjusted to the displayed part of the data.
+#[derive(Debug,Shrinkwrap)]
+#[allow(missing_docs)]
+pub struct Plot {
+    #[shrinkwrap(main_field)]
+    model   : PlotModel,
+    frp     : visualization::instance::Frp,
+    network : frp::Network,
+    wheel   : callback::Handle,
+}
+
+impl Plot {
+    /// Definition of the plot of the given kind.
+    pub fn definition(kind:PlotKind) -> Definition {
+        Definition::new(
+            Signature::new_for_any_type(kind.path(),Format::Json),
+            move |scene| { Ok(Self::new(scene,kind).into()) }
+        )
+    }
+
+    /// Constructor.
+    pub fn new(scene:&Scene, kind:PlotKind) -> Self {
+        let network = frp::Network::new();
+        let frp     = visualization::instance::Frp::new(&network);
+        let model   = PlotModel::new(scene,kind);
+        let wheel   = scene.mouse.mouse_manager.on_wheel.add(f!((event) model.handle_wheel(event)));
+        Self {model,frp,network,wheel} . init(scene)
+    }
+
+    fn init(self, scene:&Scene) -> Self {
+        let network = &self.network;
+        let model   = self.model.clone_ref();
+        let frp     = self.frp.clone_ref();
+        let mouse   = &scene.mouse.frp;
+        frp::extend! { network
+            eval mouse.position ((position) model.set_mouse_position(*position));
+            eval frp.set_size   ((size)     model.set_size(*size));
+            eval frp.send_data  ([frp](data) {
+                if let Err(e) = model.receive_data(data) {
+                    frp.data_receive_error.emit(Some(e));
+                }
+             });
+        }
+        self
+    }
+}
+
+impl From<Plot> for Instance {
+    fn from(t:Plot) -> Self {
+        Self::new(&t,&t.frp,&t.network)
+    }
+}
+
+impl display::Object for Plot {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clipping_segments() {
+        let bounds = Bounds {min:Vector2(0.0,0.0),max:Vector2(10.0,10.0)};
+        let clip   = |start:(f32,f32), end:(f32,f32)| {
+            bounds.clip(Vector2(start.0,start.1),Vector2(end.0,end.1))
+        };
+        let segment = |start:(f32,f32), end:(f32,f32)| {
+            Some((Vector2(start.0,start.1),Vector2(end.0,end.1)))
+        };
+        assert_eq!(clip((1.0,2.0),(3.0,4.0))    , segment((1.0,2.0),(3.0,4.0)));
+        assert_eq!(clip((-5.0,5.0),(5.0,5.0))   , segment((0.0,5.0),(5.0,5.0)));
+        assert_eq!(clip((-5.0,-5.0),(15.0,15.0)), segment((0.0,0.0),(10.0,10.0)));
+        assert_eq!(clip((-5.0,-5.0),(-1.0,-1.0)), None);
+        assert_eq!(clip((-1.0,0.0),(-1.0,10.0)) , None);
+        assert_eq!(clip((5.0,20.0),(20.0,5.0))  , None);
+    }
+
+    #[test]
+    fn computing_ticks() {
+        assert_eq!(tick_step(5.0)  , 1.0);
+        assert_eq!(tick_step(7.0)  , 2.0);
+        assert_eq!(tick_step(12.0) , 5.0);
+        assert_eq!(tick_step(30.0) , 10.0);
+        assert_eq!(tick_step(100.0), 20.0);
+        assert_eq!(ticks(-1.0,7.5,2.0)        , vec![0.0,2.0,4.0,6.0]);
+        assert_eq!(ticks(-4.0,4.0,4.0)        , vec![-4.0,0.0,4.0]);
+        assert_eq!(ticks(0.0,1.0,0.0)         , Vec::<f32>::new());
+        assert_eq!(ticks(0.0,1.0,std::f32::NAN), Vec::<f32>::new());
+        assert_eq!(tick_label(4.0,2.0) , "4");
+        assert_eq!(tick_label(0.5,0.25), "0.5");
+    }
+
+    #[test]
+    fn parsing_points() {
+        let json   = serde_json::json!([[1.0,2.0],[3.0,4.0]]).into();
+        let points = parse_points(&json).unwrap();
+        assert_eq!(points, vec![Vector2(1.0,2.0),Vector2(3.0,4.0)]);
+
+        let coordinates = [1.0,2.0,std::f32::NAN,3.0,4.0,std::f32::INFINITY,5.0,6.0];
+        let bytes       = coordinates.iter().flat_map(|c| c.to_le_bytes().to_vec());
+        let bytes       = BINARY_POINTS_MAGIC.iter().copied().chain(bytes).collect_vec();
+        let points      = parse_points(&bytes.into()).unwrap();
+        assert_eq!(points, vec![Vector2(1.0,2.0),Vector2(5.0,6.0)]);
+
+        let mut truncated = BINARY_POINTS_MAGIC.to_vec();
+        truncated.extend_from_slice(&[0;7]);
+        assert!(parse_points(&truncated.into()).is_err());
+        assert!(parse_points(&serde_json::json!({"x":1}).into()).is_err());
+    }
+
+    #[test]
+    fn parsing_malformed_json() {
+        // The binary payloads without the magic prefix are JSON, even if they could be read as
+        // coordinates.
+        let malformed = b"[[1.0,2.0],[3.0,".to_vec();
+        assert_eq!(malformed.len() % 8, 0);
+        assert!(parse_points(&malformed.into()).is_err());
+        assert!(parse_points(&b"[[1.0,2.0]]".to_vec().into()).is_ok());
+    }
+
+    #[test]
+    fn decimating_dots() {
+        let overlapping = vec![Vector2(0.5,0.5),Vector2(1.0,1.0),Vector2(10.0,0.5)];
+        assert_eq!(decimate(overlapping), vec![Vector2(0.5,0.5),Vector2(10.0,0.5)]);
+        let many = (0..MAX_POINTS * 3 + 1).map(|index| Vector2(index as f32 * 10.0,0.0));
+        let dots = decimate(many);
+        assert!(dots.len() <= MAX_POINTS);
+        assert_eq!(dots[1], Vector2(40.0,0.0));
+    }
+
+    #[test]
+    fn decimating_line() {
+        let area     = Bounds {min:Vector2(0.0,-10.0),max:Vector2(1e9,10.0)};
+        let many     = (0..MAX_POINTS * 10).map(|index| Vector2(index as f32 * 10.0,0.0));
+        let segments = line_segments(many,area);
+        assert!(segments.len() < MAX_POINTS);
+        assert_eq!(segments[0], (Vector2(0.0,0.0),Vector2(100.0,0.0)));
+    }
+}
@@ -0,0 +1,805 @@
+//! Table visualisation implemented using the native shape and text systems.
+//!
+//! The table consumes column-oriented data, either as JSON:
+//!
+//! ```json
+//! { "columns" : [ { "name" : "x", "values" : [1,2,3] }, { "name" : "y", "values" : ["a","b"] } ] }
+//! ```
+//!
+//! or as a binary payload. The binary payload is either the UTF-8 encoded JSON document, or the
+//! binary table starting with `BINARY_MAGIC`, see `TableData::from_binary`.
+//!
+//! Only the rows and columns fitting into the visualization are displayed, so the number of
+//! created shapes does not depend on the size of the data. The table is scrolled with the mouse
+//! wheel, and sorted by clicking the column's header.
+
+use crate::prelude::*;
+
+use crate::graph_editor::component::node::port;
+use crate::graph_editor::component::visualization::*;
+use crate::graph_editor::component::visualization;
+
+use enso_frp as frp;
+use ensogl::control::callback;
+use ensogl::control::io::mouse;
+use ensogl::data::color;
+use ensogl::display::scene::Scene;
+use ensogl::display::scene::WheelCaptureGuard;
+use ensogl::display::traits::*;
+use ensogl::display;
+use ensogl::gui::component;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const TEXT_SIZE         : f32         = 12.0;
+// FIXME hardcoded literal, the same as in the node's port manager.
+const GLYPH_WIDTH       : f32         = 7.224_609_4;
+const ROW_HEIGHT        : f32         = 20.0;
+const COLUMN_WIDTH      : f32         = 100.0;
+const PADDING           : f32         = 6.0;
+const TEXT_COLOR        : color::Rgba = color::Rgba::new(1.0, 1.0, 1.0, 0.7);
+const HEADER_TEXT_COLOR : color::Rgba = color::Rgba::new(1.0, 1.0, 1.0, 1.0);
+const ASCENDING_MARK    : &str        = " ▲";
+const DESCENDING_MARK   : &str        = " ▼";
+
+/// The bytes starting the binary table payload, distinguishing it from the UTF-8 encoded JSON.
+pub const BINARY_MAGIC : &[u8] = b"ETBL";
+
+/// The type tags of the columns in the binary table payload.
+mod binary_tag {
+    pub const NUMBERS  : u8 = 0;
+    pub const BOOLEANS : u8 = 1;
+    pub const TEXTS    : u8 = 2;
+}
+
+
+
+// ==============
+// === Header ===
+// ==============
+
+/// Background of the column's header.
+pub mod header {
+    use super::*;
+    use ensogl::display::shape::*;
+    use ensogl::display::scene::Scene;
+    use ensogl::display::Sprite;
+    use ensogl::display::Buffer;
+    use ensogl::display::Attribute;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let width  : Var<Pixels> = "input_size.x".into();
+            let height : Var<Pixels> = "input_size.y".into();
+            let shape  = Rect((&width,&height));
+            let shape  = shape.fill(color::Rgba::new(1.0,1.0,1.0,0.1));
+            shape.into()
+        }
+    }
+}
+
+
+
+// =================
+// === TableData ===
+// =================
+
+/// The value of a cell in a column of mixed types.
+#[derive(Clone,Debug,Deserialize,PartialEq)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum CellValue {
+    Missing,
+    Boolean(bool),
+    Number(f64),
+    Text(String),
+    /// A nested array or object, displayed as JSON.
+    Other(serde_json::Value),
+}
+
+/// The values of a single column. The columns of a single type are stored without the type
+/// information of each cell, the missing values being `None`.
+#[derive(Clone,Debug,Deserialize,PartialEq)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum ColumnValues {
+    Numbers  (Vec<Option<f64>>),
+    Booleans (Vec<Option<bool>>),
+    Texts    (Vec<Option<String>>),
+    Mixed    (Vec<CellValue>),
+}
+
+impl ColumnValues {
+    /// The number of values in the column.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Numbers  (values) => values.len(),
+            Self::Booleans (values) => values.len(),
+            Self::Texts    (values) => values.len(),
+            Self::Mixed    (values) => values.len(),
+        }
+    }
+
+    /// Check if the column has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compare the values in the given rows. The missing values come first, also in the rows
+    /// beyond the column's length.
+    pub fn compare(&self, a:usize, b:usize) -> Ordering {
+        fn compare_rows<T>
+        (values:&[Option<T>], a:usize, b:usize, cmp:fn(&T,&T)->Ordering) -> Ordering {
+            let get = |row:usize| values.get(row).and_then(|value| value.as_ref());
+            compare_optional(get(a),get(b),cmp)
+        }
+        match self {
+            Self::Numbers  (values) => compare_rows(values,a,b,compare_numbers),
+            Self::Booleans (values) => compare_rows(values,a,b,Ord::cmp),
+            Self::Texts    (values) => compare_rows(values,a,b,Ord::cmp),
+            Self::Mixed    (values) => compare_values(values.get(a),values.get(b)),
+        }
+    }
+
+    /// The text of the value in the given row. The missing values have empty text.
+    pub fn text(&self, row:usize) -> String {
+        fn text<T:ToString>(values:&[Option<T>], row:usize) -> String {
+            values.get(row).and_then(|value| value.as_ref()).map(T::to_string).unwrap_or_default()
+        }
+        match self {
+            Self::Numbers  (values) => text(values,row),
+            Self::Booleans (values) => text(values,row),
+            Self::Texts    (values) => text(values,row),
+            Self::Mixed    (values) => match values.get(row) {
+                None | Some(CellValue::Missing) => String::new(),
+                Some(CellValue::Boolean(value)) => value.to_string(),
+                Some(CellValue::Number(value))  => value.to_string(),
+                Some(CellValue::Text(value))    => value.clone(),
+                Some(CellValue::Other(value))   => value.to_string(),
+            },
+        }
+    }
+}
+
+/// A single named column of the table.
+#[derive(Clone,Debug,Deserialize,PartialEq)]
+#[allow(missing_docs)]
+pub struct Column {
+    pub name   : String,
+    pub values : ColumnValues,
+}
+
+/// Column-oriented data displayed by the table.
+#[derive(Clone,Debug,Default,Deserialize,PartialEq)]
+#[allow(missing_docs)]
+pub struct TableData {
+    pub columns : Vec<Column>,
+}
+
+/// The column the table is sorted by, and the sorting direction.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub struct Sorting {
+    pub column    : usize,
+    pub ascending : bool,
+}
+
+impl TableData {
+    /// Decode the table from the data received by the visualization.
+    pub fn decode(data:&Data) -> Result<Self,DataError> {
+        match data {
+            Data::Binary {content} if content.starts_with(BINARY_MAGIC) => {
+                let table = Self::from_binary(&content[BINARY_MAGIC.len()..]);
+                table.ok_or(DataError::InvalidDataType)
+            }
+            _ => data.deserialize(),
+        }
+    }
+
+    /// Decode the table from the binary format following `BINARY_MAGIC`. All numbers are little
+    /// endian. The table starts with the `u32` number of columns, each column consisting of:
+    /// - the column's name, as the `u32` length followed by the UTF-8 bytes,
+    /// - the `u8` type tag, see `binary_tag`,
+    /// - the `u32` number of values, followed by the values: `f64` numbers, `NaN` being missing;
+    ///   `u8` booleans, `0` and `1` being false and true, and `2` being missing; texts encoded as
+    ///   the names, the length `u32::MAX` being missing.
+    ///
+    /// Returns `None` if the bytes are not a valid table.
+    pub fn from_binary(bytes:&[u8]) -> Option<Self> {
+        let mut reader   = BinaryReader {bytes};
+        let column_count = reader.u32()?;
+        let columns      = (0..column_count).map(|_| reader.column()).collect::<Option<_>>()?;
+        reader.bytes.is_empty().as_some(TableData {columns})
+    }
+
+    /// The number of rows, being the length of the longest column.
+    pub fn row_count(&self) -> usize {
+        self.columns.iter().map(|column| column.values.len()).max().unwrap_or(0)
+    }
+
+    /// The text displayed in the cell, truncated to fit the column's width. Columns shorter than
+    /// the table have empty cells in last rows.
+    pub fn cell_text(&self, column:usize, row:usize) -> String {
+        let text = self.columns.get(column).map(|column| column.values.text(row));
+        truncate(text.unwrap_or_default())
+    }
+
+    /// Return indices of all rows in the order given by the sorting.
+    pub fn sorted_rows(&self, sorting:Option<Sorting>) -> Vec<usize> {
+        let mut rows = (0..self.row_count()).collect_vec();
+        let sorted   = sorting.and_then(|sorting| {
+            self.columns.get(sorting.column).map(|column| (&column.values,sorting.ascending))
+        });
+        if let Some((values,ascending)) = sorted {
+            rows.sort_by(|a,b| {
+                let ordering = values.compare(*a,*b);
+                if ascending { ordering } else { ordering.reverse() }
+            });
+        }
+        rows
+    }
+}
+
+fn compare_numbers(a:&f64, b:&f64) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// Compare the optional values with the given function, the missing values being the first.
+fn compare_optional<T>(a:Option<&T>, b:Option<&T>, cmp:impl Fn(&T,&T)->Ordering) -> Ordering {
+    match (a,b) {
+        (Some(a),Some(b)) => cmp(a,b),
+        _                 => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// Compare values of cells. Numbers, texts and booleans are compared by their value. Values of
+/// different types are ordered by their type, the missing values being the first.
+fn compare_values(a:Option<&CellValue>, b:Option<&CellValue>) -> Ordering {
+    fn type_rank(value:Option<&CellValue>) -> usize {
+        match value {
+            None | Some(CellValue::Missing) => 0,
+            Some(CellValue::Boolean(_))     => 1,
+            Some(CellValue::Number(_))      => 2,
+            Some(CellValue::Text(_))        => 3,
+            Some(CellValue::Other(_))       => 4,
+        }
+    }
+    match (a,b) {
+        (Some(CellValue::Boolean(a)),Some(CellValue::Boolean(b))) => a.cmp(b),
+        (Some(CellValue::Number(a)) ,Some(CellValue::Number(b)))  => compare_numbers(a,b),
+        (Some(CellValue::Text(a))   ,Some(CellValue::Text(b)))    => a.cmp(b),
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+fn truncate(text:String) -> String {
+    let max_length = ((COLUMN_WIDTH - 2.0 * PADDING) / GLYPH_WIDTH) as usize;
+    if text.chars().count() <= max_length { text } else {
+        let mut truncated = text.chars().take(max_length - 1).collect::<String>();
+        truncated.push('…');
+        truncated
+    }
+}
+
+
+// === BinaryReader ===
+
+/// Reads the binary table payload, see `TableData::from_binary`. Each read returns `None` if there
+/// are not enough bytes left.
+#[derive(Debug)]
+struct BinaryReader<'a> {
+    bytes : &'a [u8],
+}
+
+impl<'a> BinaryReader<'a> {
+    fn take(&mut self, count:usize) -> Option<&'a [u8]> {
+        (self.bytes.len() >= count).as_some_from(|| {
+            let (taken,rest) = self.bytes.split_at(count);
+            self.bytes = rest;
+            taken
+        })
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let mut array = [0;4];
+        array.copy_from_slice(self.take(4)?);
+        Some(u32::from_le_bytes(array))
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        let mut array = [0;8];
+        array.copy_from_slice(self.take(8)?);
+        Some(f64::from_le_bytes(array))
+    }
+
+    /// Read the optional text. The outer `None` means an invalid payload.
+    fn text(&mut self) -> Option<Option<String>> {
+        let length = self.u32()?;
+        if length == u32::max_value() { Some(None) } else {
+            let bytes = self.take(length as usize)?;
+            String::from_utf8(bytes.to_vec()).ok().map(Some)
+        }
+    }
+
+    fn boolean(&mut self) -> Option<Option<bool>> {
+        match self.u8()? {
+            0 => Some(Some(false)),
+            1 => Some(Some(true)),
+            2 => Some(None),
+            _ => None,
+        }
+    }
+
+    fn number(&mut self) -> Option<Option<f64>> {
+        self.f64().map(|number| (!number.is_nan()).as_some(number))
+    }
+
+    fn column(&mut self) -> Option<Column> {
+        let name  = self.text()??;
+        let tag   = self.u8()?;
+        let count = self.u32()?;
+        let values = match tag {
+            binary_tag::NUMBERS  => ColumnValues::Numbers (self.values(count,Self::number)?),
+            binary_tag::BOOLEANS => ColumnValues::Booleans(self.values(count,Self::boolean)?),
+            binary_tag::TEXTS    => ColumnValues::Texts   (self.values(count,Self::text)?),
+            _                    => return None,
+        };
+        Some(Column {name,values})
+    }
+
+    fn values<T>
+    (&mut self, count:u32, read:impl Fn(&mut Self) -> Option<Option<T>>) -> Option<Vec<Option<T>>> {
+        (0..count).map(|_| read(self)).collect()
+    }
+}
+
+
+
+// ==================
+// === HeaderCell ===
+// ==================
+
+/// The header of a single column, displaying the column's name. Clicking it emits the column's
+/// index. The header is reused for other columns when the table is scrolled.
+#[derive(Debug)]
+struct HeaderCell {
+    display_object : display::object::Instance,
+    label          : component::ShapeView<port::label::Shape>,
+    view           : component::ShapeView<header::Shape>,
+    column         : Rc<Cell<usize>>,
+    network        : frp::Network,
+}
+
+impl HeaderCell {
+    fn new(logger:&Logger, scene:&Scene, clicked:&frp::Source<usize>) -> Self {
+        let logger         = Logger::sub(logger,"header");
+        let display_object = display::object::Instance::new(&logger);
+        let label          = component::ShapeView::<port::label::Shape>::new(&logger,scene);
+        let view           = component::ShapeView::<header::Shape>::new(&logger,scene);
+        let column         = Rc::new(Cell::new(0));
+        label.shape.label.set_font_color(HEADER_TEXT_COLOR);
+        label.set_position(Vector3(PADDING - COLUMN_WIDTH / 2.0,-TEXT_SIZE / 2.0,0.0));
+        view.shape.sprite.size.set(Vector2(COLUMN_WIDTH - 1.0,ROW_HEIGHT));
+        display_object.add_child(&view);
+        display_object.add_child(&label);
+
+        let network = frp::Network::new();
+        let events  = &view.events;
+        frp::extend! { network
+            eval_ events.mouse_down (clicked.emit(column.get()));
+        }
+        Self {display_object,label,view,column,network}
+    }
+
+    /// Display the header of the given column with the given text.
+    fn set_column(&self, column:usize, text:&str) {
+        self.column.set(column);
+        self.label.shape.label.set_text(text);
+    }
+}
+
+impl display::Object for HeaderCell {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
+
+
+
+// ==================
+// === TableModel ===
+// ==================
+
+/// The state of the table and the views of its visible part.
+#[derive(Clone,CloneRef,Debug)]
+#[allow(missing_docs)]
+pub struct TableModel {
+    logger         : Logger,
+    display_object : display::object::Instance,
+    scene          : Scene,
+    headers        : Rc<RefCell<Vec<HeaderCell>>>,
+    cells          : Rc<RefCell<Vec<component::ShapeView<port::label::Shape>>>>,
+    data           : Rc<RefCell<TableData>>,
+    order          : Rc<RefCell<Vec<usize>>>,
+    sorting        : Rc<Cell<Option<Sorting>>>,
+    /// The first visible column and row. The row part is fractional, to accumulate small scroll
+    /// steps.
+    scroll         : Rc<Cell<Vector2>>,
+    size           : Rc<Cell<Vector2>>,
+    mouse_position : Rc<Cell<Vector2>>,
+    /// Keeps the wheel events captured while the mouse cursor is over the table, so they scroll
+    /// the table instead of navigating the scene.
+    wheel_capture  : Rc<RefCell<Option<WheelCaptureGuard>>>,
+    column_clicked : frp::Source<usize>,
+}
+
+impl TableModel {
+    fn new(scene:&Scene, column_clicked:&frp::Source<usize>) -> Self {
+        let logger         = Logger::new("Table");
+        let display_object = display::object::Instance::new(&logger);
+        let scene          = scene.clone_ref();
+        let headers        = default();
+        let cells          = default();
+        let data           = default();
+        let order          = default();
+        let sorting        = default();
+        let scroll         = default();
+        let size           = Rc::new(Cell::new(Vector2(200.0,200.0)));
+        let mouse_position = default();
+        let wheel_capture  = default();
+        let column_clicked = column_clicked.clone_ref();
+        Self {logger,display_object,scene,headers,cells,data,order,sorting,scroll,size
+             ,mouse_position,wheel_capture,column_clicked}
+    }
+
+    fn receive_data(&self, data:&Data) -> Result<(),DataError> {
+        let data = TableData::decode(data)?;
+        debug!(self.logger, "Received table with {data.columns.len()} columns.");
+        let sorting = self.sorting.get().filter(|sorting| sorting.column < data.columns.len());
+        *self.order.borrow_mut() = data.sorted_rows(sorting);
+        *self.data.borrow_mut()  = data;
+        self.sorting.set(sorting);
+        self.scroll.set(default());
+        self.update_headers();
+        self.update_cells();
+        Ok(())
+    }
+
+    fn set_size(&self, size:Vector2) {
+        self.size.set(size);
+        self.scroll_by(default());
+        self.update_headers();
+        self.update_cells();
+    }
+
+    /// Sort the table by the given column. Sorting again by the same column reverses the order.
+    fn sort_by(&self, column:usize) {
+        let ascending = match self.sorting.get() {
+            Some(sorting) if sorting.column == column => !sorting.ascending,
+            _                                         => true,
+        };
+        let sorting = Some(Sorting{column,ascending});
+        *self.order.borrow_mut() = self.data.borrow().sorted_rows(sorting);
+        self.sorting.set(sorting);
+        self.update_headers();
+        self.update_cells();
+    }
+
+    /// Scroll the table by the given number of columns and rows, keeping the scroll within the
+    /// table's bounds.
+    fn scroll_by(&self, delta:Vector2) {
+        let data        = self.data.borrow();
+        let max_column  = data.columns.len().saturating_sub(self.visible_column_count()) as f32;
+        let max_row     = data.row_count().saturating_sub(self.visible_row_count()) as f32;
+        let scroll      = self.scroll.get() + delta;
+        let scroll      = Vector2(scroll.x.max(0.0).min(max_column),scroll.y.max(0.0).min(max_row));
+        let old_columns = self.scroll.get().x as usize;
+        let old_rows    = self.scroll.get().y as usize;
+        self.scroll.set(scroll);
+        drop(data);
+        if scroll.x as usize != old_columns {
+            self.update_headers();
+        }
+        if scroll.x as usize != old_columns || scroll.y as usize != old_rows {
+            self.update_cells();
+        }
+    }
+
+    fn set_mouse_position(&self, position:Vector2) {
+        self.mouse_position.set(position);
+        let hovered           = self.is_hovered();
+        let mut wheel_capture = self.wheel_capture.borrow_mut();
+        if !hovered {
+            *wheel_capture = None;
+        } else if wheel_capture.is_none() {
+            *wheel_capture = Some(self.scene.mouse.wheel_capture.capture());
+        }
+    }
+
+    fn handle_wheel(&self, event:&mouse::OnWheel) {
+        if self.wheel_capture.borrow().is_some() {
+            event.prevent_default();
+            let columns = if event.shift_key() { event.delta_y() } else { event.delta_x() };
+            let rows    = if event.shift_key() { 0.0 }             else { event.delta_y() };
+            let delta   = Vector2(columns as f32 / COLUMN_WIDTH,rows as f32 / ROW_HEIGHT);
+            self.scroll_by(delta);
+        }
+    }
+
+    /// Check whether the mouse cursor is over the displayed table.
+    fn is_hovered(&self) -> bool {
+        let visible  = !self.display_object.is_orphan() && self.display_object.is_visible();
+        let mouse    = self.mouse_position.get();
+        let mouse    = self.scene.screen_to_scene_coordinates(Vector3(mouse.x,mouse.y,0.0));
+        let position = self.display_object.global_position();
+        let half     = self.size.get() / 2.0;
+        let inside_x = (mouse.x - position.x).abs() <= half.x;
+        let inside_y = (mouse.y - position.y).abs() <= half.y;
+        visible && inside_x && inside_y
+    }
+
+    fn visible_column_count(&self) -> usize {
+        ((self.size.get().x / COLUMN_WIDTH) as usize).max(1)
+    }
+
+    fn visible_row_count(&self) -> usize {
+        ((self.size.get().y / ROW_HEIGHT) as usize).saturating_sub(1)
+    }
+
+    /// The range of the displayed columns.
+    fn visible_columns(&self) -> Range<usize> {
+        let column_count = self.data.borrow().columns.len();
+        let first        = (self.scroll.get().x as usize).min(column_count);
+        let last         = (first + self.visible_column_count()).min(column_count);
+        first..last
+    }
+
+    /// The position of the center of the cell in the given visible column and row. The header row
+    /// has index 0.
+    fn cell_position(&self, visible_column:usize, visible_row:usize) -> Vector3 {
+        let size = self.size.get();
+        let x    = -size.x / 2.0 + (visible_column as f32 + 0.5) * COLUMN_WIDTH;
+        let y    =  size.y / 2.0 - (visible_row    as f32 + 0.5) * ROW_HEIGHT;
+        Vector3(x,y,0.0)
+    }
+
+    /// Update the header views to display the visible columns. The views are reused, so scrolling
+    /// and sorting do not create new shapes.
+    fn update_headers(&self) {
+        let data        = self.data.borrow();
+        let sorting     = self.sorting.get();
+        let columns     = self.visible_columns();
+        let mut headers = self.headers.borrow_mut();
+        headers.resize_with(columns.len(),|| {
+            let header = HeaderCell::new(&self.logger,&self.scene,&self.column_clicked);
+            self.display_object.add_child(&header);
+            header
+        });
+        for (visible_column,(header,column)) in headers.iter().zip(columns).enumerate() {
+            let mark = match sorting {
+                Some(Sorting{column:sorted,ascending}) if sorted == column =>
+                    if ascending { ASCENDING_MARK } else { DESCENDING_MARK },
+                _ => "",
+            };
+            let name = &data.columns[column].name;
+            header.set_column(column,&iformat!("{truncate(name.clone())}{mark}"));
+            header.set_position(self.cell_position(visible_column,0));
+        }
+    }
+
+    /// Update the cell views to display the visible part of the table. The views are reused, so
+    /// scrolling does not create new shapes.
+    fn update_cells(&self) {
+        let data       = self.data.borrow();
+        let order      = self.order.borrow();
+        let columns    = self.visible_columns();
+        let first_row  = self.scroll.get().y as usize;
+        let row_count  = self.visible_row_count().min(order.len().saturating_sub(first_row));
+        let cell_count = columns.len() * row_count;
+        let mut cells  = self.cells.borrow_mut();
+        cells.resize_with(cell_count,|| {
+            let cell = component::ShapeView::new(&self.logger,&self.scene);
+            cell.shape.label.set_font_color(TEXT_COLOR);
+            self.display_object.add_child(&cell);
+            cell
+        });
+        let positions = (0..row_count).cartesian_product(0..columns.len());
+        for (cell,(visible_row,visible_column)) in cells.iter().zip(positions) {
+            let row      = order[first_row + visible_row];
+            let column   = columns.start + visible_column;
+            let center   = self.cell_position(visible_column,visible_row + 1);
+            let position = center + Vector3(PADDING - COLUMN_WIDTH / 2.0,-TEXT_SIZE / 2.0,0.0);
+            cell.shape.label.set_text(data.cell_text(column,row));
+            cell.set_position(position);
+        }
+    }
+}
+
+
+
+// =============
+// === Table ===
+// =============
+
+/// Table visualization, displaying column-oriented data of any size.
+#[derive(Debug,Shrinkwrap)]
+#[allow(missing_docs)]
+pub struct Table {
+    #[shrinkwrap(main_field)]
+    model   : TableModel,
+    frp     : visualization::instance::Frp,
+    network : frp::Network,
+    wheel   : callback::Handle,
+}
+
+impl Table {
+    /// Definition of this visualization.
+    pub fn definition() -> Definition {
+        let path = Path::builtin("Table Visualization (native)");
+        Definition::new(
+            Signature::new_for_any_type(path,Format::Json),
+            |scene| { Ok(Self::new(scene).into()) }
+        )
+    }
+
+    /// Constructor.
+    pub fn new(scene:&Scene) -> Self {
+        let network = frp::Network::new();
+        let frp     = visualization::instance::Frp::new(&network);
+        frp::extend! { network
+            column_clicked <- source::<usize>();
+        }
+        let model = TableModel::new(scene,&column_clicked);
+        let wheel = scene.mouse.mouse_manager.on_wheel.add(f!((event) model.handle_wheel(event)));
+        Self {model,frp,network,wheel} . init(scene,&column_clicked)
+    }
+
+    fn init(self, scene:&Scene, column_clicked:&frp::Source<usize>) -> Self {
+        let network = &self.network;
+        let model   = self.model.clone_ref();
+        let frp     = self.frp.clone_ref();
+        let mouse   = &scene.mouse.frp;
+        frp::extend! { network
+            eval mouse.position   ((position) model.set_mouse_position(*position));
+            eval column_clicked   ((column)   model.sort_by(*column));
+            eval frp.set_size     ((size)     model.set_size(*size));
+            eval frp.send_data    ([frp](data) {
+                if let Err(e) = model.receive_data(data) {
+                    frp.data_receive_error.emit(Some(e));
+                }
+             });
+        }
+        self
+    }
+}
+
+impl From<Table> for Instance {
+    fn from(t:Table) -> Self {
+        Self::new(&t,&t.frp,&t.network)
+    }
+}
+
+impl display::Object for Table {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(json:serde_json::Value) -> TableData {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn decoding_typed_columns() {
+        let data = table(serde_json::json!({"columns":[
+            {"name":"numbers" , "values":[1,null,2.5]},
+            {"name":"booleans", "values":[true,null]},
+            {"name":"texts"   , "values":["a",null]},
+            {"name":"mixed"   , "values":[1,"a",null,[1]]},
+        ]}));
+        let values = data.columns.iter().map(|column| column.values.clone()).collect_vec();
+        assert_eq!(values[0], ColumnValues::Numbers(vec![Some(1.0),None,Some(2.5)]));
+        assert_eq!(values[1], ColumnValues::Booleans(vec![Some(true),None]));
+        assert_eq!(values[2], ColumnValues::Texts(vec![Some("a".into()),None]));
+        let mixed = vec![CellValue::Number(1.0),CellValue::Text("a".into()),CellValue::Missing
+                        ,CellValue::Other(serde_json::json!([1]))];
+        assert_eq!(values[3], ColumnValues::Mixed(mixed));
+        assert_eq!(data.row_count(), 4);
+        assert_eq!(data.cell_text(0,0), "1");
+        assert_eq!(data.cell_text(0,1), "");
+        assert_eq!(data.cell_text(1,3), "");
+        assert_eq!(data.cell_text(3,3), "[1]");
+    }
+
+    #[test]
+    fn decoding_binary_table() {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(b"x");
+        bytes.push(binary_tag::NUMBERS);
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&1.5f64.to_le_bytes());
+        bytes.extend_from_slice(&std::f64::NAN.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(b"y");
+        bytes.push(binary_tag::TEXTS);
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(b"ab");
+        bytes.extend_from_slice(&u32::max_value().to_le_bytes());
+
+        let data     = TableData::decode(&bytes.clone().into()).unwrap();
+        let expected = TableData {columns:vec![
+            Column {name:"x".into(), values:ColumnValues::Numbers(vec![Some(1.5),None])},
+            Column {name:"y".into(), values:ColumnValues::Texts(vec![Some("ab".into()),None])},
+        ]};
+        assert_eq!(data,expected);
+
+        let truncated = bytes[..bytes.len() - 1].to_vec();
+        assert!(TableData::decode(&truncated.into()).is_err());
+        let json = serde_json::to_vec(&serde_json::json!({"columns":[]})).unwrap();
+        assert_eq!(TableData::decode(&json.into()).unwrap(), TableData::default());
+    }
+
+    #[test]
+    fn sorting_rows() {
+        let data = table(serde_json::json!({"columns":[
+            {"name":"numbers", "values":[3,null,1,2]},
+            {"name":"mixed"  , "values":["b",1,null,"a",true]},
+        ]}));
+        let ascending  = |column| Some(Sorting {column,ascending:true});
+        let descending = |column| Some(Sorting {column,ascending:false});
+        assert_eq!(data.sorted_rows(None)          , vec![0,1,2,3,4]);
+        // The row beyond the column's length is missing as well.
+        assert_eq!(data.sorted_rows(ascending(0))  , vec![1,4,2,3,0]);
+        assert_eq!(data.sorted_rows(descending(0)) , vec![0,3,2,1,4]);
+        assert_eq!(data.sorted_rows(ascending(1))  , vec![2,4,1,3,0]);
+        assert_eq!(data.sorted_rows(ascending(5))  , vec![0,1,2,3,4]);
+    }
+
+    #[test]
+    fn comparing_values() {
+        use CellValue::*;
+        let compare = |a:&CellValue, b:&CellValue| compare_values(Some(a),Some(b));
+        assert_eq!(compare(&Number(1.0),&Number(2.0))         , Ordering::Less);
+        assert_eq!(compare(&Text("b".into()),&Text("a".into())), Ordering::Greater);
+        assert_eq!(compare(&Boolean(false),&Boolean(true))    , Ordering::Less);
+        assert_eq!(compare(&Missing,&Boolean(false))          , Ordering::Less);
+        assert_eq!(compare(&Number(1.0),&Text("1".into()))    , Ordering::Less);
+        assert_eq!(compare(&Number(std::f64::NAN),&Number(1.0)), Ordering::Equal);
+        assert_eq!(compare_values(None,Some(&Missing))        , Ordering::Equal);
+    }
+
+    #[test]
+    fn truncating_text() {
+        let max_length = ((COLUMN_WIDTH - 2.0 * PADDING) / GLYPH_WIDTH) as usize;
+        let fitting    = "a".repeat(max_length);
+        let too_long   = "ą".repeat(max_length + 1);
+        assert_eq!(truncate(String::new()), "");
+        assert_eq!(truncate(fitting.clone()), fitting);
+        let truncated = truncate(too_long);
+        assert_eq!(truncated.chars().count(), max_length);
+        assert!(truncated.ends_with('…'));
+    }
+}
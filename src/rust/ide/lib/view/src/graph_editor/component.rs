@@ -1,11 +1,15 @@
 //! Root module for graph component definitions.
 
+pub mod breadcrumbs;
 pub mod edge;
 pub mod edge2;
+pub mod message;
 pub mod node;
 pub mod visualization;
 pub mod project_name;
 
+pub use breadcrumbs::Breadcrumbs;
 pub use edge::Edge;
+pub use message::Message;
 pub use node::Node;
 pub use project_name::ProjectName;
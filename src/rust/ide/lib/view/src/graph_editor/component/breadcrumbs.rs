@@ -0,0 +1,270 @@
+//! This module provides a view for the breadcrumbs, displaying the stack of the entered methods and
+//! allowing to jump back to any of them.
+
+use crate::prelude::*;
+
+use crate::graph_editor::component::node::port;
+use crate::graph_editor::MethodPointer;
+
+use enso_frp as frp;
+use ensogl::data::color;
+use ensogl::display;
+use ensogl::display::Attribute;
+use ensogl::display::Buffer;
+use ensogl::display::object::ObjectOps;
+use ensogl::display::scene::Scene;
+use ensogl::display::shape::*;
+use ensogl::display::Sprite;
+use ensogl::gui::component;
+use logger::enabled::Logger;
+use logger::AnyLogger;
+use nalgebra::Vector2;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const TEXT_SIZE        : f32         = 12.0;
+// FIXME hardcoded literal, the same as in the node's port manager.
+const GLYPH_WIDTH      : f32         = 7.224_609_4;
+const TEXT_COLOR       : color::Rgba = color::Rgba::new(1.0, 1.0, 1.0, 0.7);
+const HOVER_TEXT_COLOR : color::Rgba = color::Rgba::new(1.0, 1.0, 1.0, 1.0);
+const SEPARATOR        : &str        = " > ";
+
+
+
+// ==================
+// === Background ===
+// ==================
+
+mod background {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (style:Style) {
+            let bg_color = color::Rgba::new(0.0,0.0,0.0,0.000_001);
+            Plane().fill(bg_color).into()
+        }
+    }
+}
+
+
+
+// =================
+// === FrpInputs ===
+// =================
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct FrpInputs {
+    /// Set the displayed methods, starting with the root one.
+    pub set_breadcrumbs : frp::Source<Vec<MethodPointer>>,
+}
+
+impl FrpInputs {
+    /// Create new FrpInputs.
+    pub fn new(network:&frp::Network) -> Self {
+        frp::extend! {network
+            def set_breadcrumbs = source();
+        }
+        Self{set_breadcrumbs}
+    }
+}
+
+
+
+// ==================
+// === FrpOutputs ===
+// ==================
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct FrpOutputs {
+    /// Emitted with the index of the clicked breadcrumb, the root one having index 0.
+    pub breadcrumb_selected : frp::Source<usize>,
+}
+
+impl FrpOutputs {
+    /// Create new FrpOutputs.
+    pub fn new(network:&frp::Network) -> Self {
+        frp::extend! {network
+            def breadcrumb_selected = source();
+        }
+        Self{breadcrumb_selected}
+    }
+}
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct Frp {
+    pub inputs  : FrpInputs,
+    pub outputs : FrpOutputs,
+    pub network : frp::Network,
+}
+
+impl Deref for Frp {
+    type Target = FrpInputs;
+    fn deref(&self) -> &Self::Target {
+        &self.inputs
+    }
+}
+
+impl Frp {
+    /// Create new Frp.
+    pub fn new() -> Self {
+        let network = frp::Network::new();
+        let inputs  = FrpInputs::new(&network);
+        let outputs = FrpOutputs::new(&network);
+        Self{network,inputs,outputs}
+    }
+}
+
+impl Default for Frp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+// ==================
+// === Breadcrumb ===
+// ==================
+
+/// A single clickable breadcrumb, displaying the name of one method of the stack.
+#[derive(Debug)]
+struct Breadcrumb {
+    display_object : display::object::Instance,
+    label          : component::ShapeView<port::label::Shape>,
+    view           : component::ShapeView<background::Shape>,
+    width          : f32,
+    network        : frp::Network,
+}
+
+impl Breadcrumb {
+    fn new
+    (logger:&Logger, scene:&Scene, text:&str, index:usize, selected:&frp::Source<usize>) -> Self {
+        let logger         = Logger::sub(logger,iformat!("breadcrumb {index}"));
+        let display_object = display::object::Instance::new(&logger);
+        let label          = component::ShapeView::<port::label::Shape>::new(&logger,scene);
+        let view           = component::ShapeView::<background::Shape>::new(&logger,scene);
+        let width          = text.chars().count() as f32 * GLYPH_WIDTH;
+        label.shape.label.set_text(text);
+        label.mod_position(|t| t.y -= TEXT_SIZE / 2.0);
+        view.shape.sprite.size.set(Vector2::new(width,TEXT_SIZE * 1.5));
+        view.set_position(Vector3::new(width / 2.0,0.0,0.0));
+        display_object.add_child(&label);
+        display_object.add_child(&view);
+
+        let network = frp::Network::new();
+        let shape   = label.shape.clone_ref();
+        let events  = &view.events;
+        frp::extend! { network
+            eval_ events.mouse_over (shape.label.set_font_color(HOVER_TEXT_COLOR));
+            eval_ events.mouse_out  (shape.label.set_font_color(TEXT_COLOR));
+            eval_ events.mouse_down (selected.emit(index));
+        }
+        Self {display_object,label,view,width,network}
+    }
+}
+
+impl display::Object for Breadcrumb {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
+
+
+
+// ========================
+// === BreadcrumbsModel ===
+// ========================
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct BreadcrumbsModel {
+    logger         : Logger,
+    display_object : display::object::Instance,
+    scene          : Scene,
+    breadcrumbs    : Rc<RefCell<Vec<Breadcrumb>>>,
+    selected       : frp::Source<usize>,
+}
+
+impl BreadcrumbsModel {
+    /// Create new BreadcrumbsModel.
+    pub fn new(scene:&Scene, frp:&Frp) -> Self {
+        let logger         = Logger::new("Breadcrumbs");
+        let display_object = display::object::Instance::new(&logger);
+        let scene          = scene.clone_ref();
+        let breadcrumbs    = default();
+        let selected       = frp.outputs.breadcrumb_selected.clone_ref();
+        Self{logger,display_object,scene,breadcrumbs,selected}
+    }
+
+    fn set_breadcrumbs(&self, methods:&[MethodPointer]) {
+        info!(self.logger, "Setting {methods.len()} breadcrumbs.");
+        for breadcrumb in self.breadcrumbs.borrow_mut().drain(..) {
+            breadcrumb.unset_parent();
+        }
+        let mut offset  = 0.0;
+        let breadcrumbs = methods.iter().enumerate().map(|(index,method)| {
+            let text       = if index == 0 { method.name.clone() }
+                             else          { iformat!("{SEPARATOR}{method.name}") };
+            let breadcrumb = Breadcrumb::new(&self.logger,&self.scene,&text,index,&self.selected);
+            breadcrumb.set_position(Vector3::new(offset,0.0,0.0));
+            offset += breadcrumb.width;
+            self.display_object.add_child(&breadcrumb);
+            breadcrumb
+        }).collect();
+        *self.breadcrumbs.borrow_mut() = breadcrumbs;
+    }
+}
+
+impl display::Object for BreadcrumbsModel {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
+
+
+
+// ===================
+// === Breadcrumbs ===
+// ===================
+
+/// The view of the breadcrumbs, one for each method on the execution stack. Clicking one of them
+/// emits its index, so the stack can be popped up to the clicked method.
+#[derive(Debug,Clone,CloneRef,Shrinkwrap)]
+#[allow(missing_docs)]
+pub struct Breadcrumbs {
+    #[shrinkwrap(main_field)]
+    model   : Rc<BreadcrumbsModel>,
+    pub frp : Frp
+}
+
+impl Breadcrumbs {
+    /// Create a new Breadcrumbs view.
+    pub fn new(scene:&Scene) -> Self {
+        let frp     = Frp::new();
+        let model   = Rc::new(BreadcrumbsModel::new(scene,&frp));
+        let network = &frp.network;
+        frp::extend! { network
+            eval frp.inputs.set_breadcrumbs ((methods) model.set_breadcrumbs(methods));
+        }
+        Self{frp,model}
+    }
+}
+
+impl display::Object for Breadcrumbs {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
@@ -0,0 +1,157 @@
+//! This module provides a view for a single line of text informing the user about problems which
+//! are not bound to any particular node, e.g. a module with malformed metadata.
+
+use crate::prelude::*;
+
+use crate::graph_editor::component::node::port;
+
+use enso_frp as frp;
+use ensogl::data::color;
+use ensogl::display;
+use ensogl::display::object::ObjectOps;
+use ensogl::display::scene::Scene;
+use ensogl::gui::component;
+use logger::enabled::Logger;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const TEXT_SIZE : f32 = 12.0;
+
+
+
+// =================
+// === FrpInputs ===
+// =================
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct FrpInputs {
+    /// Set the displayed message. `None` hides the message.
+    pub set_message : frp::Source<Option<String>>,
+}
+
+impl FrpInputs {
+    /// Create new FrpInputs.
+    pub fn new(network:&frp::Network) -> Self {
+        frp::extend! {network
+            def set_message = source();
+        }
+        Self{set_message}
+    }
+}
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct Frp {
+    pub inputs  : FrpInputs,
+    pub network : frp::Network,
+}
+
+impl Deref for Frp {
+    type Target = FrpInputs;
+    fn deref(&self) -> &Self::Target {
+        &self.inputs
+    }
+}
+
+impl Frp {
+    /// Create new Frp.
+    pub fn new() -> Self {
+        let network = frp::Network::new();
+        let inputs  = FrpInputs::new(&network);
+        Self{network,inputs}
+    }
+}
+
+impl Default for Frp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+// ====================
+// === MessageModel ===
+// ====================
+
+#[derive(Debug,Clone,CloneRef)]
+#[allow(missing_docs)]
+pub struct MessageModel {
+    logger         : Logger,
+    display_object : display::object::Instance,
+    label          : component::ShapeView<port::label::Shape>,
+}
+
+impl MessageModel {
+    /// Create new MessageModel displaying the text in the given color.
+    pub fn new(scene:&Scene, color:color::Rgba) -> Self {
+        let logger         = Logger::new("Message");
+        let display_object = display::object::Instance::new(&logger);
+        let label          = component::ShapeView::<port::label::Shape>::new(&logger,scene);
+        label.shape.label.set_font_color(color);
+        label.mod_position(|t| t.y -= TEXT_SIZE / 2.0);
+        Self{logger,display_object,label}
+    }
+
+    fn set_message(&self, message:&Option<String>) {
+        match message {
+            Some(message) => {
+                info!(self.logger, "Displaying message: {message}");
+                self.label.shape.label.set_text(message);
+                self.display_object.add_child(&self.label);
+            }
+            None => self.label.unset_parent(),
+        }
+    }
+}
+
+impl display::Object for MessageModel {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
+
+
+
+// ===============
+// === Message ===
+// ===============
+
+/// The view of a single line of text, displayed until it is replaced or hidden.
+#[derive(Debug,Clone,CloneRef,Shrinkwrap)]
+#[allow(missing_docs)]
+pub struct Message {
+    #[shrinkwrap(main_field)]
+    model   : Rc<MessageModel>,
+    pub frp : Frp
+}
+
+impl Message {
+    /// Create a new Message view displaying the text in the given color.
+    pub fn new(scene:&Scene, color:color::Rgba) -> Self {
+        let frp     = Frp::new();
+        let model   = Rc::new(MessageModel::new(scene,color));
+        let network = &frp.network;
+        frp::extend! { network
+            eval frp.inputs.set_message ((message) model.set_message(message));
+        }
+        Self{frp,model}
+    }
+}
+
+impl display::Object for Message {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
@@ -35,6 +35,32 @@ use crate::graph_editor::component::node::port::output::OutputPorts;
 
 pub const NODE_SHAPE_PADDING : f32 = 40.0;
 pub const NODE_SHAPE_RADIUS  : f32 = 14.0;
+pub const ERROR_BORDER_WIDTH : f32 = 3.0;
+pub const VALUE_LABEL_OFFSET : f32 = 18.0;
+pub const ERROR_LABEL_OFFSET : f32 = 36.0;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// The error which occurred when evaluating the node's expression.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct Error {
+    /// The message describing the error, displayed below the node.
+    pub message : String,
+}
+
+
+
+// ==================
+// === ShortValue ===
+// ==================
+
+/// A compact, textual preview of the value computed for the node, e.g. `5` or `[1, 2, 3]`.
+#[derive(Clone,Debug,Shrinkwrap)]
+pub struct ShortValue(pub ImString);
 
 
 
@@ -47,7 +73,7 @@ pub mod shape {
     use super::*;
 
     ensogl::define_shape_system! {
-        (style:Style, selection:f32) {
+        (style:Style, selection:f32, error:f32) {
             let bg_color = style.get("graph_editor.node.background.color").color().unwrap_or_else(|| color::Rgba::new(1.0,0.0,0.0,1.0).into());
             let selection_color = style.get("graph_editor.node.selection.color").color().unwrap_or_else(|| color::Rgba::new(1.0,0.0,0.0,1.0).into());
             let _selection_size = style.get("graph_editor.node.selection.size").number().unwrap_or(8.0);
@@ -94,7 +120,19 @@ pub mod shape {
             let select         = select2 - select;
             let select         = select.fill(color::Rgba::from(selection_color));
 
-            let out = select + shadow + shape;
+
+            // === Error ===
+
+            let error_size   = ERROR_BORDER_WIDTH.px();
+            let error_width  = &width  + &error_size * 2.0;
+            let error_height = &height + &error_size * 2.0;
+            let error_radius = (NODE_SHAPE_RADIUS + ERROR_BORDER_WIDTH).px();
+            let error_outer  = Rect((&error_width,&error_height)).corners_radius(error_radius);
+            let error_inner  = Rect((&width,&height)).corners_radius(NODE_SHAPE_RADIUS.px());
+            let error_color  : Var<color::Rgba> = "srgba(0.93,0.27,0.23,input_error)".into();
+            let error_border = (error_outer - error_inner).fill(error_color);
+
+            let out = select + shadow + error_border + shape;
             out.into()
         }
     }
@@ -133,6 +171,10 @@ pub struct InputEvents {
     pub deselect          : frp::Source,
     pub set_expression    : frp::Source<Expression>,
     pub set_visualization : frp::Source<Option<visualization::Instance>>,
+    pub set_error         : frp::Source<Option<Error>>,
+    pub set_short_value   : frp::Source<Option<ShortValue>>,
+    /// Show the short value preview permanently, not only when the output ports are hovered.
+    pub set_value_pinned  : frp::Source<bool>,
 }
 
 impl InputEvents {
@@ -142,8 +184,12 @@ impl InputEvents {
             def deselect          = source();
             def set_expression    = source();
             def set_visualization = source();
+            def set_error         = source();
+            def set_short_value   = source();
+            def set_value_pinned  = source();
         }
-        Self {select,deselect,set_expression,set_visualization}
+        Self {select,deselect,set_expression,set_visualization,set_error,set_short_value
+             ,set_value_pinned}
     }
 }
 
@@ -203,6 +249,8 @@ pub struct NodeModel {
     pub ports          : port::Manager,
     pub visualization  : visualization::Container,
     pub output_ports   : OutputPorts,
+    pub error_label    : component::ShapeView<port::label::Shape>,
+    pub value_label    : component::ShapeView<port::label::Shape>,
 }
 
 pub const CORNER_RADIUS : f32 = 14.0;
@@ -253,6 +301,23 @@ impl NodeModel {
         });
         display_object.add_child(&ports);
 
+        let error_logger = Logger::sub(&logger,"error_label");
+        let error_label  = component::ShapeView::<port::label::Shape>::new(&error_logger,&scene);
+        error_label.shape.label.set_font_color(color::Rgba::new(0.93,0.27,0.23,1.0));
+        error_label.mod_position(|t| {
+            t.x = TEXT_OFF;
+            t.y = -ERROR_LABEL_OFFSET;
+        });
+        display_object.add_child(&error_label);
+
+        let value_logger = Logger::sub(&logger,"value_label");
+        let value_label  = component::ShapeView::<port::label::Shape>::new(&value_logger,&scene);
+        value_label.mod_position(|t| {
+            t.x = TEXT_OFF;
+            t.y = -VALUE_LABEL_OFFSET;
+        });
+        display_object.add_child(&value_label);
+
         let frp = Frp{input};
 
 
@@ -262,7 +327,7 @@ impl NodeModel {
 
 
         Self {scene,display_object,logger,frp,main_area,drag_area,output_ports,ports
-             ,visualization} . init()
+             ,visualization,error_label,value_label} . init()
     }
 
     fn init(self) -> Self {
@@ -298,6 +363,18 @@ impl NodeModel {
         self.output_ports.mod_position(|t| t.y = height/2.0);
     }
 
+    fn set_error(&self, error:&Option<Error>) {
+        let message = error.as_ref().map(|error| error.message.as_str()).unwrap_or("");
+        let error   = if error.is_some() { 1.0 } else { 0.0 };
+        self.main_area.shape.error.set(error);
+        self.error_label.shape.label.set_text(message);
+    }
+
+    fn set_value_preview(&self, value:Option<&ShortValue>) {
+        let text = value.map(|value| value.0.to_string()).unwrap_or_default();
+        self.value_label.shape.label.set_text(text);
+    }
+
     pub fn visualization(&self) -> &visualization::Container {
         &self.visualization
     }
@@ -317,6 +394,16 @@ impl Node {
             eval_ inputs.deselect (selection.set_target_value(0.0));
 
             eval inputs.set_expression ((expr) model.set_expression(expr));
+            eval inputs.set_error      ((error) model.set_error(error));
+
+            port_hovered   <- any(...);
+            port_hovered   <+ model.output_ports.frp.port_mouse_over.constant(true);
+            port_hovered   <+ model.output_ports.frp.port_mouse_out.constant(false);
+            value_visible  <- all_with(&port_hovered,&inputs.set_value_pinned,|h,p| *h || *p);
+            value_preview  <- all_with(&inputs.set_short_value,&value_visible,
+                |value,visible| if *visible {value.clone()} else {None}
+            );
+            eval value_preview ((value) model.set_value_preview(value.as_ref()));
 
             eval inputs.set_visualization ((content)
                 model.visualization.frp.set_visualization.emit(content)
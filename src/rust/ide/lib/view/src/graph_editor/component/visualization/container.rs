@@ -249,6 +249,7 @@ pub struct ContainerModel {
     display_object  : display::object::Instance,
     frp             : Frp,
     visualization   : RefCell<Option<visualization::Instance>>,
+    data            : RefCell<Option<visualization::Data>>,
     scene           : Scene,
     view            : View,
     fullscreen_view : FullscreenView,
@@ -261,13 +262,14 @@ impl ContainerModel {
         let logger          = Logger::sub(logger,"visualization_container");
         let display_object  = display::object::Instance::new(&logger);
         let visualization   = default();
+        let data            = default();
         let frp             = Frp::new(&network,scene);
         let view            = View::new(&logger,scene);
         let fullscreen_view = FullscreenView::new(&logger,scene);
         let scene           = scene.clone_ref();
         let is_fullscreen   = default();
-        Self {logger,frp,visualization,display_object,view,fullscreen_view,scene,is_fullscreen}
-            . init()
+        Self {logger,frp,visualization,data,display_object,view,fullscreen_view,scene
+             ,is_fullscreen}.init()
     }
 
     fn init(self) -> Self {
@@ -311,16 +313,21 @@ impl ContainerModel {
         self.set_visibility(!self.is_visible())
     }
 
+    /// Replaces the current visualization, passing the last received data to the new one.
     fn set_visualization(&self, visualization:Option<visualization::Instance>) {
         if let Some(visualization) = visualization {
             let size = self.frp.size.value();
+            self.visualization.borrow().for_each_ref(|previous| previous.unset_parent());
             visualization.set_size.emit(size);
-            self.view.add_child(&visualization);
+            if self.is_fullscreen.get() { self.fullscreen_view.add_child(&visualization) }
+            else                        { self.view.add_child(&visualization) }
+            self.data.borrow().for_each_ref(|data| visualization.send_data.emit(data));
             self.visualization.replace(Some(visualization));
         }
     }
 
     fn set_visualization_data(&self, data:&visualization::Data) {
+        self.data.replace(Some(data.clone()));
         self.visualization.borrow().for_each_ref(|vis| vis.send_data.emit(data))
     }
 
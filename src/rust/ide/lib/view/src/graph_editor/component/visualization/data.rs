@@ -2,6 +2,7 @@
 
 use crate::prelude::*;
 
+use serde::de::DeserializeOwned;
 use std::str::FromStr;
 
 
@@ -30,6 +31,35 @@ impl From<serde_json::Value> for Json {
     }
 }
 
+
+
+// ==============
+// === Binary ===
+// ==============
+
+/// Raw binary data with a fast clone operation. Used for transmitting visualization data via FRP
+/// networks without decoding it first.
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct Binary {
+    rc : Rc<Vec<u8>>
+}
+
+impl Deref for Binary {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        &self.rc
+    }
+}
+
+impl From<Vec<u8>> for Binary {
+    fn from(t:Vec<u8>) -> Self {
+        let rc = Rc::new(t);
+        Self {rc}
+    }
+}
+
+
+
 // ===================
 // === Data Format ===
 // ====================
@@ -77,8 +107,43 @@ impl Default for Format {
 #[derive(Clone,Debug)]
 #[allow(missing_docs)]
 pub enum Data {
-    Json { content : Json },
-    Binary, // TODO replace with actual binary data stream.
+    Json   { content : Json   },
+    Binary { content : Binary },
+}
+
+impl Data {
+    /// Get the data as JSON. The binary data is expected to be a UTF-8 encoded JSON document, and
+    /// is parsed on every call.
+    pub fn as_json(&self) -> Result<Json,DataError> {
+        match self {
+            Data::Json   {content} => Ok(content.clone_ref()),
+            Data::Binary {content} => {
+                let value = serde_json::from_slice::<serde_json::Value>(content);
+                value.map(Json::from).map_err(|_| DataError::InvalidDataType)
+            }
+        }
+    }
+
+    /// Deserialize the data to the given type. The binary data is expected to be a UTF-8 encoded
+    /// JSON document, and is deserialized directly, without building the intermediate JSON value.
+    pub fn deserialize<T:DeserializeOwned>(&self) -> Result<T,DataError> {
+        let result = match self {
+            Data::Json   {content} => T::deserialize(content.deref()),
+            Data::Binary {content} => serde_json::from_slice(content),
+        };
+        result.map_err(|_| DataError::InvalidDataType)
+    }
+
+    /// Get the data as bytes. The JSON data is serialized on every call.
+    pub fn as_binary(&self) -> Result<Binary,DataError> {
+        match self {
+            Data::Binary {content} => Ok(content.clone_ref()),
+            Data::Json   {content} => {
+                let bytes = serde_json::to_vec(content.deref());
+                bytes.map(Binary::from).map_err(|_| DataError::InvalidDataType)
+            }
+        }
+    }
 }
 
 impl Default for Data {
@@ -95,6 +160,13 @@ impl From<serde_json::Value> for Data {
     }
 }
 
+impl From<Vec<u8>> for Data {
+    fn from(t:Vec<u8>) -> Self {
+        let content = t.into();
+        Self::Binary {content}
+    }
+}
+
 
 
 // ==============
@@ -145,3 +217,47 @@ impl MockDataGenerator3D {
         ]
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn json_to_binary() {
+        let value  = json!({"x":[1,2,3],"label":"points"});
+        let data   = Data::from(value.clone());
+        let binary = data.as_binary().unwrap();
+        let parsed = serde_json::from_slice::<serde_json::Value>(&binary).unwrap();
+        assert_eq!(parsed,value);
+        assert_eq!(*data.as_json().unwrap(),value);
+    }
+
+    #[test]
+    fn binary_to_json() {
+        let data = Data::from(br#"{"x":[1,2,3]}"#.to_vec());
+        assert_eq!(*data.as_json().unwrap(),json!({"x":[1,2,3]}));
+        assert_eq!(&*data.as_binary().unwrap(),br#"{"x":[1,2,3]}"#);
+        assert_eq!(data.deserialize::<HashMap<String,Vec<i32>>>().unwrap()["x"],vec![1,2,3]);
+    }
+
+    #[test]
+    fn converting_invalid_data() {
+        let not_json = Data::from(b"{\"x\":".to_vec());
+        assert!(matches!(not_json.as_json(), Err(DataError::InvalidDataType)));
+        assert!(matches!(not_json.deserialize::<Vec<i32>>(), Err(DataError::InvalidDataType)));
+
+        let not_utf8 = Data::from(vec![0xff,0xfe,0xfd]);
+        assert!(matches!(not_utf8.as_json(), Err(DataError::InvalidDataType)));
+
+        let not_a_list = Data::from(json!({"x":1}));
+        assert!(matches!(not_a_list.deserialize::<Vec<i32>>(), Err(DataError::InvalidDataType)));
+    }
+}
@@ -30,6 +30,10 @@ extern "C" {
     #[allow(unsafe_code)]
     fn __Visualization__() -> JsValue;
 
+    #[allow(unsafe_code)]
+    #[wasm_bindgen(catch)]
+    fn __createFunction__(args:&str, body:&str) -> Result<js_sys::Function,JsValue>;
+
     #[allow(unsafe_code)]
     pub type Visualization;
 
@@ -47,6 +51,13 @@ pub fn js_class() -> JsValue {
     __Visualization__()
 }
 
+/// Creates a JavaScript function with the given arguments and body. Unlike
+/// `js_sys::Function::new_with_args`, it returns the `SyntaxError` thrown if the body is not valid
+/// JavaScript code.
+pub fn create_function(args:&str, body:&str) -> Result<js_sys::Function,JsValue> {
+    __createFunction__(args,body)
+}
+
 
 
 // =====================
@@ -73,9 +73,9 @@
 //!
 //! - [Optional] **Field `inputFormat`**
 //!   The static field `inputFormat` is used to determine what format the data should be provided
-//!   to the `onDataReceived` function. Currently, the only valid option is "json", but it will be
-//!   possible to set it to "binary" in the future. In the later case, it is up to the visualization
-//!   author to manage the binary stream received from the server.
+//!   to the `onDataReceived` function. The valid options are "json" (the default) and "binary". In
+//!   the later case, the data is provided as an `ArrayBuffer` and it is up to the visualization
+//!   author to decode the bytes received from the server.
 //!
 //! - [Optional] **Constructor**
 //!   The visualization will be instantiated by providing the constructor with a configuration
@@ -154,9 +154,10 @@ impl Definition {
         let source       = source.as_ref();
         let source       = source;
         let context      = JsValue::NULL;
-        let function     = js_sys::Function::new_with_args(binding::JS_CLASS_NAME,&source);
+        let function     = binding::create_function(binding::JS_CLASS_NAME,&source);
+        let function     = function.map_err(Error::InvalidFunction)?;
         let js_class     = binding::js_class();
-        let class        = function.call1(&context,&js_class).map_err(Error::InvalidFunction)?;
+        let class        = function.call1(&context,&js_class).map_err(Error::EvaluationError)?;
 
         let library      = library.into();
         let input_type   = try_str_field(&class,field::INPUT_TYPE).unwrap_or_default();
@@ -172,7 +173,8 @@ impl Definition {
     }
 
     fn new_instance(&self, scene:&Scene) -> InstantiationResult {
-        let instance = Instance::new(&self.class, scene)
+        let input_format = *self.signature.input_format;
+        let instance     = Instance::new(&self.class,input_format,scene)
             .map_err(InstantiationError::ConstructorError)?;
         Ok(instance.into())
     }
@@ -214,7 +216,11 @@ pub type FallibleDefinition = Result<Definition,Error>;
 #[derive(Clone,Debug)]
 #[allow(missing_docs)]
 pub enum Error {
+    /// The source is not a valid JavaScript function body, e.g. because of a syntax error.
     InvalidFunction(JsValue),
+    /// The function defined by the source threw an exception when called.
+    EvaluationError(JsValue),
+    /// The function defined by the source returned an invalid visualization class.
     InvalidClass(InvalidClass),
 }
 
@@ -224,6 +230,9 @@ impl Display for Error {
             Error::InvalidFunction(value)  => {
                 f.write_fmt(format_args!("Provided value is not a valid function: {:?}",value))
             },
+            Error::EvaluationError(value)  => {
+                f.write_fmt(format_args!("Provided function failed to evaluate: {:?}",value))
+            },
             Error::InvalidClass(value)  => {
                 f.write_fmt(format_args!("Provided value is not a valid class: {:?}",value))
             },
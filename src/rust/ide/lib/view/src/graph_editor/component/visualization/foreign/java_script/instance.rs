@@ -89,6 +89,7 @@ pub struct InstanceModel {
         on_data_received    : Rc<Option<js_sys::Function>>,
         set_size            : Rc<Option<js_sys::Function>>,
         object              : Rc<js_sys::Object>,
+        input_format        : data::Format,
         #[derivative(Debug="ignore")]
         preprocessor_change : PreprocessorCallbackCell,
 }
@@ -136,7 +137,8 @@ impl InstanceModel {
     }
 
     /// Tries to create a InstanceModel from the given visualisation class.
-    pub fn from_class(class:&JsValue) -> result::Result<Self, Error> {
+    pub fn from_class
+    (class:&JsValue, input_format:data::Format) -> result::Result<Self, Error> {
         let root_node                     = Self::create_root()?;
         let (preprocessor_change,closure) = Self::preprocessor_change_callback();
         let init_data                     = JsConsArgs::new(root_node.clone_ref(), closure);
@@ -147,7 +149,8 @@ impl InstanceModel {
         let set_size                      = Rc::new(set_size);
         let logger                        = Logger::new("Instance");
         let object                        = Rc::new(object);
-        Ok(InstanceModel{object,on_data_received,set_size,root_node,logger,preprocessor_change})
+        Ok(InstanceModel{object,on_data_received,set_size,root_node,logger,preprocessor_change
+                        ,input_format})
     }
 
     /// Hooks the root node into the given scene.
@@ -163,20 +166,33 @@ impl InstanceModel {
         self.root_node.set_size(size);
     }
 
-   fn receive_data(&self, data:&Data) -> result::Result<(),DataError> {
-        let data_json = match data {
-           Data::Json {content} => content,
-           _ => todo!() // FIXME
-        };
-        let data_json:&serde_json::Value = data_json.deref();
-        let data_js   = match JsValue::from_serde(data_json) {
-            Ok(value) => value,
-            Err(_)    => return Err(DataError::InvalidDataType),
+    fn receive_data(&self, data:&Data) -> result::Result<(),DataError> {
+        let data_js = match (self.input_format,data) {
+            (data::Format::Json  , Data::Json   {content}) => Self::json_to_js(content)?,
+            (data::Format::Json  , Data::Binary {content}) => Self::json_bytes_to_js(content)?,
+            (data::Format::Binary, _                     ) => Self::bytes_to_js(&data.as_binary()?),
         };
         self.try_call1(&self.on_data_received, &data_js)
             .map_err(|_| DataError::InternalComputationError)?;
         Ok(())
-   }
+    }
+
+    fn json_to_js(json:&data::Json) -> result::Result<JsValue,DataError> {
+        let json:&serde_json::Value = json.deref();
+        JsValue::from_serde(json).map_err(|_| DataError::InvalidDataType)
+    }
+
+    /// Parses the UTF-8 encoded JSON document on the JS side, so no intermediate
+    /// `serde_json::Value` has to be built.
+    fn json_bytes_to_js(bytes:&[u8]) -> result::Result<JsValue,DataError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| DataError::InvalidDataType)?;
+        js_sys::JSON::parse(text).map_err(|_| DataError::InvalidDataType)
+    }
+
+    /// Copies the bytes into a new `ArrayBuffer`.
+    fn bytes_to_js(bytes:&[u8]) -> JsValue {
+        js_sys::Uint8Array::from(bytes).buffer().into()
+    }
 
     /// Helper method to call methods on the wrapped javascript object.
     fn try_call1(&self, method:&Option<js_sys::Function>, arg:&JsValue)
@@ -210,10 +226,11 @@ pub struct Instance {
 
 impl Instance {
     /// Constructor.
-    pub fn new(class:&JsValue, scene:&Scene) -> result::Result<Instance, Error>  {
+    pub fn new
+    (class:&JsValue, input_format:data::Format, scene:&Scene) -> result::Result<Instance, Error> {
         let network = default();
         let frp     = visualization::instance::Frp::new(&network);
-        let model   = InstanceModel::from_class(class)?;
+        let model   = InstanceModel::from_class(class,input_format)?;
         model.set_dom_layer(&scene.dom.layers.main);
         Ok(Instance{model,frp,network}.init_frp().inti_preprocessor_change_callback())
     }
@@ -11,3 +11,7 @@ export class Visualization {
 export function __Visualization__() {
     return Visualization
 }
+
+export function __createFunction__(args, body) {
+    return new Function(args, body)
+}
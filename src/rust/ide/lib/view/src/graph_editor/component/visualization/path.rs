@@ -4,6 +4,9 @@ use crate::prelude::*;
 
 use crate::graph_editor::data;
 
+use serde::Deserialize;
+use serde::Serialize;
+
 
 
 // ============
@@ -24,7 +27,8 @@ im_string_newtype!{
 
 /// A fully qualified path of a visualization definition. Contains both the library name and the
 /// visualization name.
-#[derive(Clone,CloneRef,Debug,Eq,Hash,PartialEq)]
+#[derive(Clone,CloneRef,Debug,Eq,Hash,PartialEq,Serialize,Deserialize)]
+#[serde(from="SerializedPath",into="SerializedPath")]
 #[allow(missing_docs)]
 pub struct Path {
     pub library : data::LibraryName,
@@ -45,3 +49,55 @@ impl Path {
         Self::new(library,name)
     }
 }
+
+
+// === Serialization ===
+
+/// The form in which the `Path` is serialized, e.g. to remember the visualization chosen for the
+/// node.
+#[derive(Clone,Debug,Serialize,Deserialize)]
+struct SerializedPath {
+    library : String,
+    name    : String,
+}
+
+impl From<SerializedPath> for Path {
+    fn from(t:SerializedPath) -> Self {
+        Self::new(t.library,t.name)
+    }
+}
+
+impl From<Path> for SerializedPath {
+    fn from(t:Path) -> Self {
+        let library = t.library.to_string();
+        let name    = t.name.to_string();
+        Self {library,name}
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization_round_trip() {
+        let path       = Path::new("Standard.Table","Table");
+        let serialized = serde_json::to_value(&path).unwrap();
+        let expected   = serde_json::json!({"library":"Standard.Table","name":"Table"});
+        assert_eq!(serialized,expected);
+        let deserialized:Path = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized,path);
+    }
+
+    #[test]
+    fn deserializing_malformed_path() {
+        let malformed = serde_json::json!({"name":"Table"});
+        assert!(serde_json::from_value::<Path>(malformed).is_err());
+    }
+}
@@ -5,6 +5,7 @@
 use crate::prelude::*;
 
 use crate::graph_editor::builtin;
+use crate::graph_editor::builtin::visualization::native::PlotKind;
 use crate::graph_editor::component::visualization;
 use crate::graph_editor::data::EnsoType;
 
@@ -40,16 +41,43 @@ impl Registry {
         let registry = Self::new();
         registry.add(builtin::visualization::native::BubbleChart::definition());
         registry.add(builtin::visualization::native::RawText::definition());
+        registry.add(builtin::visualization::native::Table::definition());
+        registry.add(builtin::visualization::native::Plot::definition(PlotKind::Scatter));
+        registry.add(builtin::visualization::native::Plot::definition(PlotKind::Line));
         registry.try_add_java_script(builtin::visualization::java_script::bubble_visualization());
         registry
     }
 
-    /// Register a new `visualization::Definition`.
+    /// Register a new `visualization::Definition`. The definition previously registered under the
+    /// same `visualization::Path` is replaced, keeping its place among the definitions for its
+    /// input type.
     pub fn add(&self, class:impl Into<visualization::Definition>) {
-        let class = class.into();
-        let sig   = &class.signature;
-        self.type_map.borrow_mut().entry(sig.input_type.clone()).or_default().push(class.clone_ref());
-        self.path_map.borrow_mut().entry(sig.path.clone()).insert(class);
+        let class    = class.into();
+        let sig      = &class.signature;
+        let previous = self.path_map.borrow_mut().insert(sig.path.clone(),class.clone_ref());
+        let mut type_map = self.type_map.borrow_mut();
+        let previous_tp  = previous.map(|previous| previous.signature.input_type.clone());
+        if let Some(previous_tp) = previous_tp.filter(|tp| tp != &sig.input_type) {
+            if let Some(definitions) = type_map.get_mut(&previous_tp) {
+                definitions.retain(|definition| definition.signature.path != sig.path);
+            }
+        }
+        let definitions = type_map.entry(sig.input_type.clone()).or_default();
+        let same_path   = definitions.iter_mut().find(|def| def.signature.path == sig.path);
+        match same_path {
+            Some(definition) => *definition = class.clone_ref(),
+            None             => definitions.push(class.clone_ref()),
+        }
+    }
+
+    /// Remove the `visualization::Definition` registered under the given `visualization::Path`.
+    pub fn remove(&self, path:&visualization::Path) -> Option<visualization::Definition> {
+        let removed = self.path_map.borrow_mut().remove(path)?;
+        let tp      = &removed.signature.input_type;
+        if let Some(definitions) = self.type_map.borrow_mut().get_mut(tp) {
+            definitions.retain(|definition| &definition.signature.path != path);
+        }
+        Some(removed)
     }
 
     /// Register a new `visualization::java_script::Definition`. If creating the class fails, it
@@ -66,9 +94,23 @@ impl Registry {
     }
 
     /// Return all `visualization::Class`es that can create a visualization for the given datatype.
+    /// The ones defined for exactly this type come first, followed by the ones accepting any type.
     pub fn valid_sources(&self, tp:&EnsoType) -> Vec<visualization::Definition>{
         let type_map = self.type_map.borrow();
-        type_map.get(tp).cloned().unwrap_or_default()
+        let any      = EnsoType::any();
+        let for_type = type_map.get(tp).into_iter().flatten();
+        let for_any  = type_map.get(&any).into_iter().flatten().filter(|_| tp != &any);
+        for_type.chain(for_any).cloned().collect()
+    }
+
+    /// Return the definition of the visualization preferred for the given datatype: the first one
+    /// registered for exactly this type, or the builtin raw text visualization if there is none or
+    /// the type is `Any`.
+    pub fn default_definition(&self, tp:&EnsoType) -> visualization::Definition {
+        let type_map = self.type_map.borrow();
+        let for_type = type_map.get(tp).filter(|_| tp != &EnsoType::any());
+        let for_type = for_type.and_then(|definitions| definitions.first().cloned());
+        for_type.unwrap_or_else(builtin::visualization::native::RawText::definition)
     }
 
     /// Return the `visualization::Definition` registered for the given `visualization::Path`.
@@ -90,3 +132,61 @@ impl Default for Registry {
         Registry::new()
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::graph_editor::builtin::visualization::native::RawText;
+    use crate::graph_editor::component::visualization::data::Format;
+
+    fn definition(name:&str, input_type:&str) -> visualization::Definition {
+        let path      = visualization::Path::new("Test",name);
+        let signature = visualization::Signature::new(path,input_type,Format::Json);
+        visualization::Definition::new(signature, |scene| Ok(RawText::new(scene).into()))
+    }
+
+    fn names(definitions:&[visualization::Definition]) -> Vec<String> {
+        definitions.iter().map(|definition| definition.signature.path.name.to_string()).collect()
+    }
+
+    fn registry() -> Registry {
+        let registry = Registry::new();
+        registry.add(definition("Any","Any"));
+        registry.add(definition("Number","Number"));
+        registry.add(definition("Text","Text"));
+        registry.add(definition("Other Number","Number"));
+        registry
+    }
+
+    #[test]
+    fn valid_sources_for_exact_type_come_first() {
+        let registry = registry();
+        let sources  = |tp:&str| names(&registry.valid_sources(&tp.into()));
+        assert_eq!(sources("Number") , vec!["Number","Other Number","Any"]);
+        assert_eq!(sources("Text")   , vec!["Text","Any"]);
+        assert_eq!(sources("Vector") , vec!["Any"]);
+        assert_eq!(sources("Any")    , vec!["Any"]);
+
+        // Registering the definition again replaces it in place.
+        registry.add(definition("Number","Number"));
+        assert_eq!(sources("Number") , vec!["Number","Other Number","Any"]);
+    }
+
+    #[test]
+    fn default_definition_for_type() {
+        let registry = registry();
+        let default  = |tp:&str| registry.default_definition(&tp.into()).signature.path;
+        let raw_text = RawText::definition().signature.path;
+        assert_eq!(default("Number") , visualization::Path::new("Test","Number"));
+        assert_eq!(default("Text")   , visualization::Path::new("Test","Text"));
+        assert_eq!(default("Vector") , raw_text);
+        assert_eq!(default("Any")    , raw_text);
+    }
+}
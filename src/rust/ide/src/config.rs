@@ -12,7 +12,10 @@ pub struct Startup {
     /// WebSocket endpoint of the project manager service.
     pub project_manager_endpoint : String,
     /// The project name we want to open on startup passed from the optional `--project` argument
-    pub project_name : ProjectName
+    pub project_name : ProjectName,
+    /// Whether the traffic with the services should be recorded, set by the optional `--record`
+    /// argument. See `crate::transport::recording`.
+    pub record_traffic : bool,
 }
 
 impl Startup {
@@ -24,6 +27,7 @@ impl Startup {
         let project_name = project_name.unwrap_or_else(|| {
             ProjectName::new(constants::DEFAULT_PROJECT_NAME)
         });
-        Startup{project_manager_endpoint,project_name}
+        let record_traffic = arguments.contains_key("record");
+        Startup{project_manager_endpoint,project_name,record_traffic}
     }
 }
@@ -2,10 +2,14 @@
 //!
 //! This controller provides access to a specific graph. It lives under a module controller, as
 //! each graph belongs to some module.
+pub mod clipboard;
 pub mod executed;
 
 use crate::prelude::*;
 
+use crate::controller::graph::clipboard::ClipboardContent;
+use crate::controller::graph::clipboard::CopiedNode;
+use crate::double_representation::alias_analysis;
 use crate::double_representation::alias_analysis::NormalizedName;
 use crate::double_representation::alias_analysis::LocatedName;
 use crate::double_representation::definition;
@@ -16,6 +20,7 @@ pub use crate::double_representation::graph::LocationHint;
 use crate::double_representation::node;
 use crate::double_representation::node::NodeInfo;
 use crate::model::module::NodeMetadata;
+use crate::model::module::Position;
 
 use ast::crumbs::InfixCrumb;
 use enso_protocol::language_server;
@@ -505,7 +510,6 @@ impl Handle {
     /// Introducing identifier not included on this list should have no side-effects on the name
     /// resolution in the code in this graph.
     pub fn used_names(&self) -> FallibleResult<Vec<LocatedName>> {
-        use double_representation::alias_analysis;
         let def   = self.graph_definition_info()?;
         let body  = def.body();
         let usage = match body.shape() {
@@ -526,9 +530,16 @@ impl Handle {
     /// Suggests a variable name for storing results of the given node. Name will get a number
     /// appended to avoid conflicts with other identifiers used in the graph.
     pub fn variable_name_for(&self, node:&NodeInfo) -> FallibleResult<ast::known::Var> {
-        let base_name   = Self::variable_name_base_for(node);
+        let base_name = Self::variable_name_base_for(node);
+        self.unused_variable_name(base_name)
+    }
+
+    /// Suggests a variable name with the given base. Name will get a number appended to avoid
+    /// conflicts with other identifiers used in the graph.
+    pub fn unused_variable_name(&self, base_name:impl Str) -> FallibleResult<ast::known::Var> {
+        let base_name   = base_name.as_ref();
         let unavailable = self.used_names()?.into_iter().filter_map(|name| {
-            let is_relevant = name.item.starts_with(base_name.as_str());
+            let is_relevant = name.item.starts_with(base_name);
             is_relevant.then(name.item)
         }).collect::<HashSet<_>>();
         let name = (1..).find_map(|i| {
@@ -735,6 +746,112 @@ impl Handle {
         Ok(())
     }
 
+    /// Collapses the given nodes into a new method named `new_method_name`.
+    ///
+    /// The new method is placed in the module right after the root definition containing this
+    /// graph. The collapsed nodes are replaced with a single node calling the new method, whose id
+    /// is returned. See `double_representation::refactorings::collapse` for details.
+    pub fn collapse
+    (&self, nodes:impl IntoIterator<Item=node::Id>, new_method_name:impl Str)
+    -> FallibleResult<node::Id> {
+        use double_representation::refactorings::collapse::collapse;
+        let new_method_name = new_method_name.as_ref();
+        info!(self.logger, "Collapsing nodes into a new method `{new_method_name}`");
+        let _transaction    = self.module.open_transaction();
+        let module_name     = self.module.path.module_name();
+        let root_definition = self.id.crumbs.first().ok_or(module::EmptyDefinitionId)?;
+        let nodes           = nodes.into_iter().collect_vec();
+        let ast_so_far      = self.module.ast();
+        let definition      = module::locate(&ast_so_far,&self.id)?;
+        let graph           = GraphInfo::from_definition(definition.item);
+        let position        = self.mean_position(&nodes);
+        let module_info     = module::Info {ast:ast_so_far.clone()};
+        let collapsed       = collapse(&module_info,&graph,nodes,new_method_name,module_name)?;
+        let updated_ast     = collapsed.updated_definition.ast.into();
+        let ast             = ast_so_far.set_traversing(&definition.crumbs,updated_ast)?;
+        let mut module      = module::Info {ast};
+        module.add_method_after(collapsed.new_method,root_definition)?;
+        self.module.update_ast(module.ast);
+        if let Some(position) = position {
+            let collapsed_node = collapsed.collapsed_node;
+            self.module.with_node_metadata(collapsed_node, |md| md.position = Some(position));
+        }
+        Ok(collapsed.collapsed_node)
+    }
+
+    /// The mean position of the given nodes, ignoring nodes without a position set.
+    fn mean_position(&self, nodes:&[node::Id]) -> Option<Position> {
+        let metadata  = nodes.iter().filter_map(|id| self.module.node_metadata(*id).ok());
+        let positions = metadata.filter_map(|md| md.position).collect_vec();
+        if positions.is_empty() {
+            None
+        } else {
+            let sum = positions.iter().fold(Vector2::zeros(), |sum,position| sum + position.vector);
+            Some(Position {vector:sum / positions.len() as f32})
+        }
+    }
+
+    /// Describes the given nodes as a text which may be put into the clipboard and later pasted
+    /// with `paste_nodes`, possibly into another graph or module.
+    pub fn copy_nodes(&self, nodes:impl IntoIterator<Item=node::Id>) -> FallibleResult<String> {
+        let nodes     = nodes.into_iter().collect::<HashSet<_>>();
+        let all_nodes = self.nodes()?;
+        if let Some(id) = nodes.iter().find(|id| !all_nodes.iter().any(|n| n.info.id() == **id)) {
+            return Err(NodeNotFound(*id).into())
+        }
+        info!(self.logger, "Copying {nodes.len()} nodes");
+        let copied = all_nodes.into_iter().filter(|node| nodes.contains(&node.info.id()));
+        let nodes  = copied.map(|node| CopiedNode::new(&node.info,node.metadata)).collect();
+        ClipboardContent {nodes}.to_text()
+    }
+
+    /// Pastes the nodes described by the text returned from `copy_nodes` at the end of the graph.
+    ///
+    /// The pasted nodes get new ids. Identifiers introduced by the pasted nodes which clash with
+    /// names already used in the graph are renamed, along with their usages in the other pasted
+    /// nodes. The nodes are positioned around the given position, keeping their relative layout.
+    /// Returns the ids of pasted nodes.
+    pub fn paste_nodes(&self, text:&str, position:Position) -> FallibleResult<Vec<node::Id>> {
+        let _transaction = self.module.open_transaction();
+        let mut content  = ClipboardContent::from_text(text)?;
+        content.move_center_to(position);
+        info!(self.logger, "Pasting {content.nodes.len()} nodes");
+        let mut renames = HashMap::<NormalizedName,String>::new();
+        let mut pasted  = Vec::new();
+        for copied in content.nodes {
+            // Parsing the code again gives the node fresh ids.
+            let ast        = self.parser.parse_line(copied.code())?;
+            let node       = NodeInfo::from_line_ast(&ast).ok_or(FailedToCreateNode)?;
+            let usage      = alias_analysis::analyse_node(&node);
+            let used_names = self.used_names()?;
+            let mut node_renames = usage.used.into_iter().filter_map(|name| {
+                let new_name = renames.get(&name.item)?.clone();
+                Some((name,new_name))
+            }).collect_vec();
+            for name in usage.introduced {
+                let is_clashing = used_names.iter().any(|used| used.item == name.item);
+                if is_clashing {
+                    let new_name = self.unused_variable_name(name.item.as_str())?.repr();
+                    renames.insert(name.item.clone(),new_name.clone());
+                    node_renames.push((name,new_name));
+                } else {
+                    renames.remove(&name.item);
+                }
+            }
+            let node = clipboard::rename_identifiers(node,&node_renames)?;
+            self.update_definition_ast(|definition| {
+                let mut graph = GraphInfo::from_definition(definition);
+                graph.add_node(node.ast().clone_ref(),LocationHint::End)?;
+                Ok(graph.source)
+            })?;
+            if let Some(metadata) = copied.metadata {
+                self.module.set_node_metadata(node.id(),metadata);
+            }
+            pasted.push(node.id());
+        }
+        Ok(pasted)
+    }
+
     /// Subscribe to updates about changes in this graph.
     pub fn subscribe(&self) -> impl Stream<Item=Notification> {
         let module_sub = self.module.subscribe();
@@ -1011,7 +1128,7 @@ main =
             // === Add node ===
             let id       = ast::Id::new_v4();
             let position = Some(model::module::Position::new(10.0,20.0));
-            let metadata = NodeMetadata {position};
+            let metadata = NodeMetadata {position,..default()};
             let info     = NewNodeInfo {
                 expression    : "a+b".into(),
                 metadata      : Some(metadata),
@@ -1220,6 +1337,54 @@ main =
         })
     }
 
+    #[wasm_bindgen_test]
+    fn collapsing_nodes() {
+        let mut test = Fixture::set_up();
+        const PROGRAM:&str = "main =\n    a = 1\n    b = a + 1\n    c = b * 2\n    print c";
+        test.run_graph_for_main(PROGRAM, |module, graph| async move {
+            let nodes     = graph.nodes().unwrap();
+            let collapsed = vec![nodes[1].info.id(),nodes[2].info.id()];
+            let position  = |x,y| NodeMetadata {position:Some(Position::new(x,y)),..default()};
+            graph.module.set_node_metadata(collapsed[0],position(0.0,0.0));
+            graph.module.set_node_metadata(collapsed[1],position(20.0,-40.0));
+            let new_node  = graph.collapse(collapsed,"foo").unwrap();
+            let expected_program = "main =\n    a = 1\n    c = Main.foo a\n    print c\n\n\
+                                    foo a =\n    b = a + 1\n    c = b * 2\n    c";
+            module.expect_code(expected_program);
+            let node = graph.node(new_node).unwrap();
+            assert_eq!(node.info.expression().repr(), "Main.foo a");
+            assert_eq!(node.metadata.unwrap().position, Some(Position::new(10.0,-20.0)));
+            // Both the new method and the new node's position are undone at once.
+            graph.module.undo().unwrap();
+            module.expect_code(PROGRAM);
+            assert!(graph.node(new_node).is_err());
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn copying_and_pasting_nodes() {
+        let mut test = Fixture::set_up();
+        const PROGRAM:&str = "main =\n    a = 1\n    b = a + 1\n    print b";
+        test.run_graph_for_main(PROGRAM, |module, graph| async move {
+            let nodes = graph.nodes().unwrap();
+            let (a,b) = (nodes[0].info.id(),nodes[1].info.id());
+            let position = |x,y| NodeMetadata {position:Some(Position::new(x,y)),..default()};
+            graph.module.set_node_metadata(a,position(0.0,0.0));
+            graph.module.set_node_metadata(b,position(0.0,20.0));
+            let text   = graph.copy_nodes(vec![a,b]).unwrap();
+            let pasted = graph.paste_nodes(&text,Position::new(100.0,100.0)).unwrap();
+            let expected_program = "main =\n    a = 1\n    b = a + 1\n    print b\n    a1 = 1\n    \
+                                    b1 = a1 + 1";
+            module.expect_code(expected_program);
+            assert_eq!(pasted.len(), 2);
+            assert!(!pasted.contains(&a) && !pasted.contains(&b));
+            let positions = pasted.iter().map(|id| graph.node(*id).unwrap().metadata.unwrap());
+            let positions = positions.map(|metadata| metadata.position.unwrap()).collect_vec();
+            assert_eq!(positions, vec![Position::new(100.0,90.0),Position::new(100.0,110.0)]);
+            assert!(graph.copy_nodes(vec![node::Id::new_v4()]).is_err());
+        })
+    }
+
     #[wasm_bindgen_test]
     fn suggested_names() {
         let parser = Parser::new_or_panic();
@@ -0,0 +1,181 @@
+//! The text format of graph nodes copied to the clipboard and utilities used when pasting them.
+
+use crate::prelude::*;
+
+use crate::controller::graph::FailedToCreateNode;
+use crate::double_representation::alias_analysis::LocatedName;
+use crate::double_representation::node::NodeInfo;
+use crate::model::module::NodeMetadata;
+use crate::model::module::Position;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// ==================
+// === CopiedNode ===
+// ==================
+
+/// A single node copied to the clipboard.
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub struct CopiedNode {
+    /// The code of node's expression.
+    pub expression : String,
+    /// The code of node's pattern, if the node has one.
+    pub pattern : Option<String>,
+    /// The node's metadata, containing its position.
+    pub metadata : Option<NodeMetadata>,
+}
+
+impl CopiedNode {
+    /// Describe the given node for the clipboard.
+    pub fn new(node:&NodeInfo, metadata:Option<NodeMetadata>) -> Self {
+        let expression = node.expression().repr();
+        let pattern    = node.pattern().map(|pattern| pattern.repr());
+        CopiedNode {expression,pattern,metadata}
+    }
+
+    /// The code of the whole node's line.
+    pub fn code(&self) -> String {
+        match &self.pattern {
+            Some(pattern) => iformat!("{pattern} = {self.expression}"),
+            None          => self.expression.clone(),
+        }
+    }
+}
+
+
+
+// ========================
+// === ClipboardContent ===
+// ========================
+
+/// The nodes copied to the clipboard.
+///
+/// The nodes are kept in order of their lines in the graph, so each node is placed after the nodes
+/// it depends on. The connections between copied nodes are expressed by the identifiers in their
+/// code.
+#[derive(Clone,Debug,Default,Deserialize,Serialize)]
+pub struct ClipboardContent {
+    /// The copied nodes.
+    pub nodes : Vec<CopiedNode>,
+}
+
+impl ClipboardContent {
+    /// Serialize the content to the text which can be put into the clipboard.
+    pub fn to_text(&self) -> FallibleResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize the content from the clipboard's text.
+    pub fn from_text(text:&str) -> FallibleResult<Self> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// The center of copied nodes' positions. Returns `None` if no node has position set.
+    pub fn center(&self) -> Option<Vector2<f32>> {
+        let positions = self.positions().collect_vec();
+        let sum       = positions.iter().fold(Vector2::zeros(),|sum,position| sum + position);
+        (!positions.is_empty()).and_option_from(|| Some(sum / positions.len() as f32))
+    }
+
+    /// Move all the nodes' positions, so their center is placed at the given position.
+    pub fn move_center_to(&mut self, position:Position) {
+        if let Some(center) = self.center() {
+            let offset    = position.vector - center;
+            let positions = self.nodes.iter_mut().filter_map(|node| node.metadata.as_mut());
+            for position in positions.filter_map(|metadata| metadata.position.as_mut()) {
+                position.vector += offset;
+            }
+        }
+    }
+
+    fn positions<'a>(&'a self) -> impl Iterator<Item=Vector2<f32>> + 'a {
+        let metadata = self.nodes.iter().filter_map(|node| node.metadata.as_ref());
+        metadata.filter_map(|metadata| metadata.position).map(|position| position.vector)
+    }
+}
+
+
+
+// ================
+// === Renaming ===
+// ================
+
+/// Replace the given node's identifiers with new names. The identifiers' locations are relative to
+/// the node's AST, as returned by alias analysis.
+pub fn rename_identifiers
+(node:NodeInfo, renames:&[(LocatedName,String)]) -> FallibleResult<NodeInfo> {
+    let mut ast = node.ast().clone_ref();
+    for (name,new_name) in renames {
+        let old_ast = ast.get_traversing(&name.crumbs)?;
+        // Keep the id, as the identifier may be the node's whole expression.
+        let new_ast = match old_ast.id {
+            Some(id) => Ast::var_with_id(new_name.as_str(),id),
+            None     => Ast::var(new_name.as_str()),
+        };
+        ast = ast.set_traversing(&name.crumbs,new_ast)?;
+    }
+    Ok(NodeInfo::from_line_ast(&ast).ok_or(FailedToCreateNode)?)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::double_representation::alias_analysis::analyse_node;
+    use crate::double_representation::alias_analysis::NormalizedName;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn copied_node(expression:&str, position:Option<(f32,f32)>) -> CopiedNode {
+        let position = position.map(|(x,y)| Position::new(x,y));
+        CopiedNode {
+            expression : expression.to_string(),
+            pattern    : None,
+            metadata   : Some(NodeMetadata {position,..default()}),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn serializing_and_moving_content() {
+        let nodes = vec![
+            copied_node("foo",Some((0.0,0.0))),
+            copied_node("bar",Some((10.0,20.0))),
+            copied_node("baz",None),
+        ];
+        let mut content = ClipboardContent {nodes};
+        assert_eq!(content.center(), Some(Vector2::new(5.0,10.0)));
+        content.move_center_to(Position::new(100.0,100.0));
+        let text      = content.to_text().unwrap();
+        let restored  = ClipboardContent::from_text(&text).unwrap();
+        let positions = restored.positions().collect_vec();
+        assert_eq!(positions, vec![Vector2::new(95.0,90.0),Vector2::new(105.0,110.0)]);
+        assert_eq!(restored.nodes[2].expression, "baz");
+        assert!(ClipboardContent::from_text("not a clipboard content").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn renaming_identifiers() {
+        let parser  = parser::Parser::new_or_panic();
+        let ast     = parser.parse_line("a = a + b").unwrap();
+        let node    = NodeInfo::from_line_ast(&ast).unwrap();
+        let id      = node.id();
+        let usage   = analyse_node(&node);
+        let used_b  = usage.used.iter().find(|name| name.item == NormalizedName::new("b"));
+        let renames = vec![
+            (usage.introduced[0].clone(),"a1".to_string()),
+            (used_b.unwrap().clone()    ,"b1".to_string()),
+        ];
+        let renamed = rename_identifiers(node,&renames).unwrap();
+        assert_eq!(renamed.ast().repr(), "a1 = a + b1");
+        assert_eq!(renamed.id(), id);
+    }
+}
@@ -6,9 +6,14 @@
 use crate::prelude::*;
 
 use crate::model::execution_context::ComputedValueInfoRegistry;
+use crate::model::execution_context::EntryPoint;
+use crate::model::execution_context::ExecutionStatus;
+use crate::model::execution_context::LocalCall;
+use crate::model::execution_context::PopOnEmptyStack;
 use crate::model::execution_context::Visualization;
 use crate::model::execution_context::VisualizationId;
 use crate::model::execution_context::VisualizationUpdateData;
+use crate::model::module::StoredExecutionContext;
 use crate::model::synchronized::ExecutionContext;
 
 use enso_protocol::language_server::MethodPointer;
@@ -31,6 +36,22 @@ pub struct NoResolvedMethod(double_representation::node::Id);
 
 
 
+// ===================
+// === MetadataKey ===
+// ===================
+
+/// The place in the project metadata where the state of the execution context is stored: the
+/// module keeping the metadata and the name of the context in them.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct MetadataKey {
+    /// The module whose project metadata contain the execution context.
+    pub module : model::module::Path,
+    /// The key of the execution context in `ProjectMetadata::execution_contexts`.
+    pub name   : String,
+}
+
+
+
 // ====================
 // === Notification ===
 // ====================
@@ -45,10 +66,15 @@ pub enum Notification {
     /// The notification from the execution context about the computed value information
     /// being updated.
     ComputedValueInfo(crate::model::execution_context::ComputedValueExpressions),
+    /// The notification from the execution context about its execution status having changed,
+    /// e.g. the execution failed.
+    ExecutionStatus(ExecutionStatus),
     /// Notification emitted when the node has been entered.
     EnteredNode(double_representation::node::Id),
     /// Notification emitted when the node was step out.
     SteppedOutOfNode(double_representation::node::Id),
+    /// Notification emitted when the execution context switched to another entry point.
+    EntryPointChanged(EntryPoint),
 }
 
 
@@ -71,17 +97,28 @@ pub struct Handle {
     /// The publisher allowing sending notification to subscribed entities. Note that its outputs is
     /// merged with publishers from the stored graph and execution controllers.
     notifier:crate::notification::Publisher<Notification>,
+    /// The place in the project metadata where the entry point and the call stack are stored
+    /// whenever they change. If `None`, they are not stored.
+    metadata_key:Rc<RefCell<Option<MetadataKey>>>,
 }
 
 impl Handle {
-    /// Create handle for the executed graph that will be running the given method.
+    /// Create handle for the executed graph that will be running the given entry point.
+    ///
+    /// The entry point may be given just as the method pointer, if the method is to be called
+    /// without any arguments.
     pub async fn new
-    ( parent  : impl AnyLogger
-    , project : Rc<model::Project>
-    , method  : MethodPointer
+    ( parent      : impl AnyLogger
+    , project     : Rc<model::Project>
+    , entry_point : impl Into<EntryPoint>
     ) -> FallibleResult<Self> {
-        let graph     = controller::Graph::new_method(parent,&*project,&method).await?;
-        let execution = project.create_execution_context(method.clone()).await?;
+        let entry_point = entry_point.into();
+        let method      = entry_point.method.clone();
+        // The module is opened and the execution context created in parallel, so the requests
+        // may be batched (see `language_server::API::batch_each_poll`).
+        let graph       = controller::Graph::new_method(parent,&*project,&method);
+        let execution   = project.create_execution_context(entry_point);
+        let (graph,execution) = futures::try_join!(graph,execution)?;
         Ok(Self::new_internal(graph,project,execution))
     }
 
@@ -106,8 +143,9 @@ impl Handle {
     ) -> Self {
         let logger   = Logger::sub(&graph.logger,"Executed");
         let graph    = Rc::new(RefCell::new(graph));
-        let notifier = default();
-        Handle {logger,graph,execution_ctx,project,notifier}
+        let notifier     = default();
+        let metadata_key = default();
+        Handle {logger,graph,execution_ctx,project,notifier,metadata_key}
     }
 
     /// See `attach_visualization` in `ExecutionContext`.
@@ -132,16 +170,75 @@ impl Handle {
         self.execution_ctx.computed_value_info_registry()
     }
 
+    /// See `execution_status` in `ExecutionContext`.
+    pub fn execution_status(&self) -> ExecutionStatus {
+        self.execution_ctx.execution_status()
+    }
+
+    /// See `current_method` in `ExecutionContext`.
+    pub fn current_method(&self) -> MethodPointer {
+        self.execution_ctx.current_method()
+    }
+
+    /// The local calls on the execution context's stack, from the outermost one.
+    pub fn call_stack(&self) -> Vec<LocalCall> {
+        self.execution_ctx.stack_items().collect()
+    }
+
+    /// See `entry_point` in `ExecutionContext`.
+    pub fn entry_point(&self) -> EntryPoint {
+        self.execution_ctx.entry_point()
+    }
+
+    /// The identifier of the execution context in the Language Server.
+    pub fn execution_context_id(&self) -> model::execution_context::Id {
+        self.execution_ctx.id()
+    }
+
+    /// Switch the execution context to the given entry point, e.g. to run another method of the
+    /// module or to call the same method with different arguments.
+    ///
+    /// The whole call stack is replaced, and the graph controller is switched to the entry point's
+    /// method. Fails if the method's graph cannot be retrieved.
+    ///
+    /// If the execution context fails to switch after some local calls were already popped, the
+    /// graph controller is switched to the method of the remaining top frame.
+    pub async fn set_entry_point(&self, entry_point:EntryPoint) -> FallibleResult<()> {
+        self.storing_state(self.switch_entry_point(entry_point)).await
+    }
+
+    async fn switch_entry_point(&self, entry_point:EntryPoint) -> FallibleResult<()> {
+        info!(self.logger, "Setting entry point to {entry_point.method.name}.");
+        let method     = &entry_point.method;
+        let graph      = controller::Graph::new_method(&self.logger,&self.project,method).await?;
+        let stack_size = self.call_stack().len();
+        if let Err(err) = self.execution_ctx.set_entry_point(entry_point.clone()).await {
+            if self.call_stack().len() != stack_size {
+                let method = self.execution_ctx.current_method();
+                let graph  = controller::Graph::new_method(&self.logger,&self.project,&method);
+                self.graph.replace(graph.await?);
+                let entry_point = self.entry_point();
+                self.notifier.publish(Notification::EntryPointChanged(entry_point)).await;
+            }
+            return Err(err)
+        }
+        self.graph.replace(graph);
+        self.notifier.publish(Notification::EntryPointChanged(entry_point)).await;
+        Ok(())
+    }
+
     /// Subscribe to updates about changes in this executed graph.
     ///
     /// The stream of notification contains both notifications from the graph and from the execution
     /// context.
     pub fn subscribe(&self) -> impl Stream<Item=Notification> {
-        let registry     = self.execution_ctx.computed_value_info_registry();
-        let value_stream = registry.subscribe().map(Notification::ComputedValueInfo).boxed_local();
-        let graph_stream = self.graph().subscribe().map(Notification::Graph).boxed_local();
-        let self_stream  = self.notifier.subscribe().boxed_local();
-        futures::stream::select_all(vec![value_stream,graph_stream,self_stream])
+        let registry      = self.execution_ctx.computed_value_info_registry();
+        let value_stream  = registry.subscribe().map(Notification::ComputedValueInfo).boxed_local();
+        let status_stream = self.execution_ctx.subscribe_execution_status();
+        let status_stream = status_stream.map(Notification::ExecutionStatus).boxed_local();
+        let graph_stream  = self.graph().subscribe().map(Notification::Graph).boxed_local();
+        let self_stream   = self.notifier.subscribe().boxed_local();
+        futures::stream::select_all(vec![value_stream,status_stream,graph_stream,self_stream])
     }
 
     /// Enter node by given ID.
@@ -157,11 +254,25 @@ impl Handle {
         let registry   = self.execution_ctx.computed_value_info_registry();
         let node_info  = registry.get(&node).ok_or_else(|| NotEvaluatedYet(node))?;
         let method_ptr = node_info.method_pointer.as_ref().ok_or_else(|| NoResolvedMethod(node))?;
-        let graph      = controller::Graph::new_method(&self.logger,&self.project,&method_ptr).await?;
-        let call       = model::execution_context::LocalCall {
+        let call       = LocalCall {
             call       : node,
             definition : method_ptr.as_ref().clone()
         };
+        self.enter_call(call).await
+    }
+
+    /// Push the given local call to the execution context and switch the graph to the called
+    /// method.
+    ///
+    /// Fails if the called method's graph cannot be retrieved.
+    pub async fn enter_call(&self, call:LocalCall) -> FallibleResult<()> {
+        self.storing_state(self.push_call(call)).await
+    }
+
+    async fn push_call(&self, call:LocalCall) -> FallibleResult<()> {
+        let node  = call.call;
+        let graph = controller::Graph::new_method(&self.logger,&self.project,&call.definition);
+        let graph = graph.await?;
         self.execution_ctx.push(call).await?;
 
         debug!(self.logger,"Replacing graph with {graph:?}.");
@@ -177,14 +288,123 @@ impl Handle {
     /// Fails if this execution context is already at the stack's root or if the parent graph
     /// cannot be retrieved.
     pub async fn exit_node(&self) -> FallibleResult<()> {
-        let frame  = self.execution_ctx.pop().await?;
-        let method = self.execution_ctx.current_method();
-        let graph  = controller::Graph::new_method(&self.logger,&self.project,&method).await?;
-        self.graph.replace(graph);
-        self.notifier.publish(Notification::SteppedOutOfNode(frame.call)).await;
+        self.exit_nodes(1).await
+    }
+
+    /// Leave the given number of nodes at once, e.g. to jump directly to some ancestor method.
+    ///
+    /// Fails if the stack has fewer local calls than the given count, or if the target graph cannot
+    /// be retrieved. If some local call cannot be popped, the graph is switched to the method of
+    /// the calls popped so far and the error is returned.
+    pub async fn exit_nodes(&self, count:usize) -> FallibleResult<()> {
+        self.storing_state(self.pop_calls(count)).await
+    }
+
+    async fn pop_calls(&self, count:usize) -> FallibleResult<()> {
+        if count > self.call_stack().len() {
+            return Err(PopOnEmptyStack().into())
+        }
+        let mut exited_call = None;
+        let mut result      = Ok(());
+        for _ in 0..count {
+            match self.execution_ctx.pop().await {
+                Ok(call) => exited_call = Some(call.call),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+        if let Some(exited_call) = exited_call {
+            let method = self.execution_ctx.current_method();
+            let graph  = controller::Graph::new_method(&self.logger,&self.project,&method).await?;
+            self.graph.replace(graph);
+            self.notifier.publish(Notification::SteppedOutOfNode(exited_call)).await;
+        }
+        result
+    }
+
+    /// Restore the entry point and the call stack stored in the project metadata under the given
+    /// key, e.g. in the previous session, and keep storing them there as they change. If nothing
+    /// is stored under the key, the execution context is left intact.
+    ///
+    /// Nothing is stored while restoring, so if some of the calls cannot be entered, e.g. because
+    /// the code has been changed since, the stored stack is kept intact. The stack stops at the
+    /// last call entered successfully and the error is returned.
+    pub async fn restore_from_metadata(&self, key:MetadataKey) -> FallibleResult<()> {
+        let module = self.project.module(key.module.clone()).await?;
+        let stored = module.project_metadata().execution_contexts.remove(&key.name);
+        *self.metadata_key.borrow_mut() = Some(key);
+        let stored = match stored {
+            Some(stored) => stored,
+            None         => return Ok(()),
+        };
+        let stack  = &stored.call_stack;
+        info!(self.logger, "Restoring execution context with {stack.len()} frames.");
+        if stored.entry_point != self.entry_point() {
+            self.switch_entry_point(stored.entry_point).await?;
+        }
+        for call in stored.call_stack {
+            self.push_call(call).await?;
+        }
         Ok(())
     }
 
+    /// Store the entry point and the call stack in the project metadata under the given key, and
+    /// keep storing them there as they change.
+    pub async fn store_in_metadata(&self, key:MetadataKey) {
+        *self.metadata_key.borrow_mut() = Some(key);
+        self.store_state().await;
+    }
+
+    /// Remove the entry point and the call stack from the project metadata and stop storing them,
+    /// e.g. when the execution context is closed by the user.
+    pub async fn remove_from_metadata(&self) {
+        let key = self.metadata_key.borrow_mut().take();
+        if let Some(key) = key {
+            match self.project.module(key.module).await {
+                Ok(module) => module.with_project_metadata(|md| {
+                    md.execution_contexts.remove(&key.name);
+                }),
+                Err(err) => warning!(self.logger, "Failed to remove the execution context: {err}"),
+            }
+        }
+    }
+
+    /// The state of the execution context which is stored in the project metadata.
+    fn stored_state(&self) -> StoredExecutionContext {
+        let entry_point = self.entry_point();
+        let call_stack  = self.call_stack();
+        StoredExecutionContext {entry_point,call_stack}
+    }
+
+    /// Run the operation changing the execution context, and store its state if it has changed,
+    /// also if the operation failed midway.
+    async fn storing_state<T>
+    (&self, operation:impl Future<Output=FallibleResult<T>>) -> FallibleResult<T> {
+        let previous = self.stored_state();
+        let result   = operation.await;
+        if self.stored_state() != previous {
+            self.store_state().await;
+        }
+        result
+    }
+
+    /// Store the entry point and the call stack in the project metadata, so they can be restored
+    /// when the project is opened again.
+    async fn store_state(&self) {
+        let key = self.metadata_key.borrow().clone();
+        if let Some(key) = key {
+            let state = self.stored_state();
+            match self.project.module(key.module).await {
+                Ok(module) => module.with_project_metadata(|md| {
+                    md.execution_contexts.insert(key.name,state);
+                }),
+                Err(err) => warning!(self.logger, "Failed to store the execution context: {err}"),
+            }
+        }
+    }
+
     /// Get the controller for the currently active graph.
     ///
     /// Note that the controller returned by this method may change as the nodes are stepped into.
@@ -204,14 +424,63 @@ mod tests {
     use super::*;
 
     use crate::executor::test_utils::TestWithLocalPoolExecutor;
+    use crate::model::synchronized::execution_context::tests::MockData;
 
     use enso_protocol::language_server;
+    use json_rpc::error::RpcError;
+    use json_rpc::expect_call;
     use utils::test::traits::*;
     use wasm_bindgen_test::wasm_bindgen_test;
     use wasm_bindgen_test::wasm_bindgen_test_configure;
 
     wasm_bindgen_test_configure!(run_in_browser);
 
+    /// Create the given number of local calls of the mock context's main method.
+    fn mock_local_calls(data:&MockData, count:usize) -> Vec<LocalCall> {
+        let definition = data.main_method_pointer();
+        let call       = || LocalCall {call:Uuid::new_v4(), definition:definition.clone()};
+        std::iter::repeat_with(call).take(count).collect()
+    }
+
+    /// Create the executed graph of the mock context's main method with the given local calls on
+    /// the stack. The expectations for the execution context's calls are set on `ls`, while the
+    /// module is opened by the mock project.
+    async fn mock_executed_graph
+    (data:&MockData, calls:&[LocalCall], ls:language_server::MockClient) -> Handle {
+        let path    = data.module_path.file_path().clone();
+        let project = model::project::test::setup_mock_project(|client| {
+            model::project::test::mock_calls_for_opening_text_file(client,path,"main = 2 + 2");
+            // Each change of the stored execution context edits the module's metadata.
+            for _ in 0..3 {
+                client.expect.apply_text_file_edit(|_| Ok(()));
+            }
+        }, |_| {});
+        let project    = Rc::new(project);
+        let method     = data.main_method_pointer();
+        let graph      = controller::Graph::new_method(Logger::default(),&project,&method).await;
+        let id         = data.context_id;
+        let mut model  = data.create_model();
+        for call in calls {
+            model.push(call.clone());
+        }
+        expect_call!(ls.destroy_execution_context(id) => Ok(()));
+        let connection = language_server::Connection::new_mock_rc(ls);
+        let execution  = ExecutionContext::new_mock(id,model,connection);
+        Handle::new_internal(graph.unwrap(),project,Rc::new(execution))
+    }
+
+    fn mock_metadata_key(data:&MockData) -> MetadataKey {
+        let module = data.module_path.clone();
+        let name   = "main".to_string();
+        MetadataKey {module,name}
+    }
+
+    async fn stored_context(executed_graph:&Handle, key:&MetadataKey) -> StoredExecutionContext {
+        let module   = executed_graph.project.module(key.module.clone()).await.unwrap();
+        let contexts = module.project_metadata().execution_contexts;
+        contexts[&key.name].clone()
+    }
+
     // Test that checks that value computed notification is properly relayed by the executed graph.
     #[wasm_bindgen_test]
     fn dispatching_value_computed_notification() {
@@ -243,10 +512,123 @@ mod tests {
 
         // Observing that notification was relayed.
         let observed_notification = notifications.expect_next();
-        let typename_in_registry  = registry.get(&update.id).unwrap().typename.clone();
+        let info_in_registry      = registry.get(&update.id).unwrap();
         let expected_typename     = update.typename.clone().map(ImString::new);
+        let expected_short_value  = update.short_value.clone().map(ImString::new);
         assert_eq!(observed_notification,Notification::ComputedValueInfo(vec![update.id]));
-        assert_eq!(typename_in_registry,expected_typename);
+        assert_eq!(info_in_registry.typename,expected_typename);
+        assert_eq!(info_in_registry.short_value,expected_short_value);
+        notifications.expect_pending();
+    }
+
+    // Test that checks that the execution failure is relayed by the executed graph.
+    #[wasm_bindgen_test]
+    fn dispatching_execution_failure_notification() {
+        let mut fixture    = TestWithLocalPoolExecutor::set_up();
+        let mut ls         = language_server::MockClient::default();
+        let execution_data = model::synchronized::execution_context::tests::MockData::new();
+        let execution      = execution_data.context_provider(&mut ls);
+        let graph_data     = controller::graph::tests::MockData::new_inline("1 + 2");
+        let connection     = language_server::Connection::new_mock_rc(ls);
+        let (_,graph)      = graph_data.create_controllers_with_ls(connection.clone_ref());
+        let execution      = Rc::new(execution(connection.clone_ref()));
+        let project        = model::project::test::setup_mock_project(|_| {}, |_| {});
+        let executed_graph = Handle::new_internal(graph,Rc::new(project),execution.clone_ref());
+
+        let mut notifications = executed_graph.subscribe().boxed_local();
+        let context_id = execution_data.context_id;
+        let message    = "Execution failed.".to_string();
+        let failure    = language_server::ExecutionFailed {context_id,message:message.clone()};
+        execution.handle_execution_failed(failure);
+        fixture.run_until_stalled();
+
+        let expected = ExecutionStatus::Failed {message};
+        assert_eq!(notifications.expect_next(),Notification::ExecutionStatus(expected.clone()));
+        assert_eq!(executed_graph.execution_status(),expected);
         notifications.expect_pending();
     }
+    // Test that checks exiting several nodes at once, as when selecting a breadcrumb.
+    #[wasm_bindgen_test]
+    fn exiting_nodes() {
+        let data  = MockData::new();
+        let calls = mock_local_calls(&data,3);
+        let ls    = language_server::MockClient::default();
+        let id    = data.context_id;
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+
+        TestWithLocalPoolExecutor::set_up().run_task(async move {
+            let executed_graph    = mock_executed_graph(&data,&calls,ls).await;
+            let mut notifications = executed_graph.subscribe().boxed_local();
+            executed_graph.exit_nodes(2).await.unwrap();
+            assert_eq!(executed_graph.call_stack(),calls[..1].to_vec());
+            let expected = Notification::SteppedOutOfNode(calls[1].call);
+            assert_eq!(notifications.expect_next(),expected);
+            // There are not enough local calls, so nothing is popped.
+            assert!(executed_graph.exit_nodes(2).await.is_err());
+            assert_eq!(executed_graph.call_stack(),calls[..1].to_vec());
+        });
+    }
+
+    // Test that checks that when some local call cannot be popped, the graph, the stored stack and
+    // the notification reflect the calls popped so far.
+    #[wasm_bindgen_test]
+    fn exiting_nodes_with_failed_pop() {
+        let data  = MockData::new();
+        let calls = mock_local_calls(&data,3);
+        let key   = mock_metadata_key(&data);
+        let ls    = language_server::MockClient::default();
+        let id    = data.context_id;
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+        expect_call!(ls.pop_from_execution_context(id) => Err(RpcError::LostConnection));
+
+        TestWithLocalPoolExecutor::set_up().run_task(async move {
+            let executed_graph    = mock_executed_graph(&data,&calls,ls).await;
+            let mut notifications = executed_graph.subscribe().boxed_local();
+            executed_graph.store_in_metadata(key.clone()).await;
+            assert!(executed_graph.exit_nodes(2).await.is_err());
+            assert_eq!(executed_graph.call_stack(),calls[..2].to_vec());
+            let expected = Notification::SteppedOutOfNode(calls[2].call);
+            assert_eq!(notifications.expect_next(),expected);
+            let stored = stored_context(&executed_graph,&key).await;
+            assert_eq!(stored.call_stack,calls[..2].to_vec());
+        });
+    }
+
+    // Test that checks that the stored stack is not overwritten when restoring it fails midway.
+    #[wasm_bindgen_test]
+    fn restoring_execution_context() {
+        let data   = MockData::new();
+        let calls  = mock_local_calls(&data,2);
+        let key    = mock_metadata_key(&data);
+        let stored = StoredExecutionContext {
+            entry_point : EntryPoint::new(data.main_method_pointer()),
+            call_stack  : calls.clone(),
+        };
+        let ls     = language_server::MockClient::default();
+        let id     = data.context_id;
+        let frames = calls.iter().map(|call| {
+            let call = language_server::LocalCall {expression_id:call.call};
+            language_server::StackItem::LocalCall(call)
+        }).collect_vec();
+        expect_call!(ls.push_to_execution_context(id,frames[0].clone()) => Ok(()));
+        expect_call!(ls.push_to_execution_context(id,frames[1].clone())
+            => Err(RpcError::LostConnection));
+
+        TestWithLocalPoolExecutor::set_up().run_task(async move {
+            let executed_graph = mock_executed_graph(&data,&[],ls).await;
+            // Nothing is stored under the key, so nothing is restored.
+            let other_key = MetadataKey {name:"other".to_string(), ..key.clone()};
+            executed_graph.restore_from_metadata(other_key).await.unwrap();
+            assert!(executed_graph.call_stack().is_empty());
+
+            let module = executed_graph.project.module(key.module.clone()).await.unwrap();
+            module.with_project_metadata(|md| {
+                md.execution_contexts.insert(key.name.clone(),stored.clone());
+            });
+            assert!(executed_graph.restore_from_metadata(key.clone()).await.is_err());
+            assert_eq!(executed_graph.call_stack(),calls[..1].to_vec());
+            assert_eq!(stored_context(&executed_graph,&key).await,stored);
+        });
+    }
 }
@@ -46,11 +46,18 @@ impl Handle {
     /// Create a module controller for given path.
     pub async fn new
     (parent:impl AnyLogger, path:Path, project:&model::Project) -> FallibleResult<Self> {
-        let logger          = Logger::sub(parent,format!("Module Controller {}", path));
-        let model           = project.module(path).await?;
+        let model = project.module(path).await?;
+        Ok(Self::new_for_model(parent,model,project))
+    }
+
+    /// Create a module controller for the already opened module model.
+    pub fn new_for_model
+    (parent:impl AnyLogger, model:Rc<model::synchronized::Module>, project:&model::Project)
+    -> Self {
+        let logger          = Logger::sub(parent,format!("Module Controller {}", model.path));
         let language_server = project.language_server_rpc.clone_ref();
         let parser          = project.parser.clone_ref();
-        Ok(Handle {model,language_server,parser,logger})
+        Handle {model,language_server,parser,logger}
     }
 
     /// Save the module to file.
@@ -1,12 +1,16 @@
 //! This module contains all structures related to Searcher Controller.
 
+pub mod matcher;
+
 use crate::prelude::*;
 
+use crate::double_representation::module::ImportInfo;
 use crate::notification;
 
 use data::text::TextLocation;
 use enso_protocol::language_server;
 use flo_stream::Subscriber;
+use json_rpc::error::RpcError;
 use parser::Parser;
 
 
@@ -26,6 +30,39 @@ pub enum Suggestion {
     // In future, other suggestion types will be added (like suggestions of actions, etc.).
 }
 
+impl Suggestion {
+    /// Match the suggestion with the pattern typed by user. Returns `None` if the suggestion does
+    /// not match.
+    pub fn match_pattern(&self, pattern:&str) -> Option<matcher::Match> {
+        match self {
+            Self::Completion(entry) => matcher::match_entry(pattern,entry),
+        }
+    }
+}
+
+/// A single entry of the displayed suggestion list: the suggestion with the information how it
+/// matches the current input pattern.
+#[derive(Clone,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub struct ListEntry {
+    pub suggestion : Suggestion,
+    pub match_info : matcher::Match,
+}
+
+impl ListEntry {
+    /// Filter the suggestions by the pattern and sort them, so the best matches are first. The
+    /// suggestions with equal scores keep their original order.
+    pub fn filter_and_rank(pattern:&str, suggestions:&[Suggestion]) -> Vec<ListEntry> {
+        let mut entries = suggestions.iter().filter_map(|suggestion| {
+            let match_info = suggestion.match_pattern(pattern)?;
+            let suggestion = suggestion.clone_ref();
+            Some(ListEntry {suggestion,match_info})
+        }).collect_vec();
+        entries.sort_by(|lhs,rhs| rhs.match_info.score.cmp(&lhs.match_info.score));
+        entries
+    }
+}
+
 /// List of suggestions available in Searcher.
 #[derive(Clone,CloneRef,Debug)]
 pub enum Suggestions {
@@ -34,7 +71,7 @@ pub enum Suggestions {
     /// The suggestion list is loaded.
     #[allow(missing_docs)]
     Loaded {
-        list : Rc<Vec<Suggestion>>
+        list : Rc<Vec<ListEntry>>
     },
     /// Loading suggestion list resulted in error.
     Error(Rc<failure::Error>)
@@ -58,7 +95,7 @@ impl Suggestions {
     }
 
     /// Get the list of suggestions. Returns None if still loading or error was returned.
-    pub fn list(&self) -> Option<&Vec<Suggestion>> {
+    pub fn list(&self) -> Option<&Vec<ListEntry>> {
         match self {
             Self::Loaded {list} => Some(list),
             _                   => None,
@@ -185,6 +222,15 @@ impl HasRepr for ParsedInput {
 // === Searcher Controller ===
 // ===========================
 
+/// The type name used by the engine for arguments accepting values of any type.
+const ANY_TYPE_NAME:&str = "Any";
+
+type CompletionRequest =
+    LocalBoxFuture<'static,json_rpc::Result<language_server::response::Completion>>;
+
+/// The completion request along with the handle allowing to cancel it.
+type CancellableCompletionRequest = (Option<language_server::CancellationHandle>,CompletionRequest);
+
 /// A fragment filled by single picked completion suggestion.
 ///
 /// We store such information in Searcher to better suggest the potential arguments, and to know
@@ -210,9 +256,39 @@ pub struct Data {
     pub input : ParsedInput,
     /// The suggestion list which should be displayed.
     pub suggestions : Suggestions,
+    /// The ids of the last suggestion list received from the engine, not filtered by the input
+    /// pattern. It is kept to filter and rank suggestions locally while user types, and to refresh
+    /// the list when the Suggestion Database changes.
+    pub engine_suggestions : Option<Rc<Vec<model::suggestion_database::EntryId>>>,
     /// All fragments of input which were added by picking suggestions. If the fragment will be
     /// changed by user, it will be removed from this list.
     pub fragments_added_by_picking : Vec<FragmentAddedByPickingSuggestion>,
+    /// The imports added to the module when picking suggestions. They should be removed when the
+    /// edited node is abandoned.
+    pub added_imports : Vec<model::module::QualifiedName>,
+    /// The handles of the completion requests for the currently loaded suggestion list. They are
+    /// cancelled once a new list is requested.
+    pub pending_completions : Vec<language_server::CancellationHandle>,
+}
+
+impl Data {
+    /// Update the displayed suggestions: the entries of the last list received from the engine are
+    /// taken from the database and filtered by the current input pattern. The entries missing in
+    /// the database are skipped.
+    ///
+    /// Returns `false` if no list was received from the engine yet, so nothing was updated.
+    fn refresh_suggestions(&mut self, database:&model::SuggestionDatabase) -> bool {
+        match &self.engine_suggestions {
+            Some(ids) => {
+                let entries      = ids.iter().filter_map(|id| database.get(*id));
+                let suggestions  = entries.map(Suggestion::Completion).collect_vec();
+                let list         = ListEntry::filter_and_rank(&self.input.pattern,&suggestions);
+                self.suggestions = Suggestions::Loaded {list:Rc::new(list)};
+                true
+            },
+            None => false,
+        }
+    }
 }
 
 /// Searcher Controller.
@@ -222,38 +298,65 @@ pub struct Data {
 /// accepting the Searcher input (pressing "Enter").
 #[derive(Clone,CloneRef,Debug)]
 pub struct Searcher {
-    logger          : Logger,
-    data            : Rc<RefCell<Data>>,
-    notifier        : notification::Publisher<Notification>,
-    module          : Rc<model::module::QualifiedName>,
-    position        : Immutable<TextLocation>,
-    database        : Rc<model::SuggestionDatabase>,
-    language_server : Rc<language_server::Connection>,
-    parser          : Parser,
+    logger            : Logger,
+    data              : Rc<RefCell<Data>>,
+    notifier          : notification::Publisher<Notification>,
+    module            : Rc<model::module::QualifiedName>,
+    module_controller : controller::Module,
+    position          : Immutable<TextLocation>,
+    database          : Rc<model::SuggestionDatabase>,
+    language_server   : Rc<language_server::Connection>,
+    parser            : Parser,
 }
 
 impl Searcher {
     /// Create new Searcher Controller.
     pub fn new
-    ( parent   : impl AnyLogger
-    , project  : &model::Project
-    , module   : model::module::Path
-    , position : TextLocation
+    ( parent            : impl AnyLogger
+    , project           : &model::Project
+    , module_controller : controller::Module
+    , position          : TextLocation
     ) -> Self {
-        let this = Self {
-            position        : Immutable(position),
-            logger          : Logger::sub(parent,"Searcher Controller"),
-            data            : default(),
-            notifier        : default(),
-            module          : Rc::new(project.qualified_module_name(&module)),
-            database        : project.suggestion_db.clone_ref(),
-            language_server : project.language_server_rpc.clone_ref(),
-            parser          : project.parser.clone_ref(),
+        let module = project.qualified_module_name(&module_controller.model.path);
+        let this   = Self {
+            module_controller,
+            position          : Immutable(position),
+            logger            : Logger::sub(parent,"Searcher Controller"),
+            data              : default(),
+            notifier          : default(),
+            module            : Rc::new(module),
+            database          : project.suggestion_db.clone_ref(),
+            language_server   : project.language_server_rpc.clone_ref(),
+            parser            : project.parser.clone_ref(),
         };
         this.reload_list();
+        this.watch_database();
         this
     }
 
+    /// Refresh the suggestion list each time the Suggestion Database changes, as the displayed
+    /// entries could be modified or removed. The task will finish once the controller is dropped.
+    fn watch_database(&self) {
+        let mut subscriber = self.database.subscribe();
+        let weak_data      = Rc::downgrade(&self.data);
+        let database       = self.database.clone_ref();
+        let notifier       = self.notifier.clone_ref();
+        executor::global::spawn(async move {
+            loop {
+                let notification = subscriber.next().await;
+                match (notification,weak_data.upgrade()) {
+                    (Some(_),Some(data)) => {
+                        let refreshed = data.borrow_mut().refresh_suggestions(&database);
+                        if refreshed {
+                            notifier.publish(Notification::NewSuggestionList).await;
+                        }
+                    },
+                    _ => break,
+                }
+            }
+        });
+    }
+
     /// Subscribe to controller's notifications.
     pub fn subscribe(&self) -> Subscriber<Notification> {
         self.notifier.subscribe()
@@ -278,6 +381,7 @@ impl Searcher {
         if old_id != new_id {
             self.reload_list()
         }
+        self.refilter_list();
         Ok(())
     }
 
@@ -285,10 +389,12 @@ impl Searcher {
     ///
     /// This function should be called when user chooses some completion suggestion. The picked
     /// suggestion will be remembered, and the searcher's input will be updated and returned by this
-    /// function.
+    /// function. If the suggestion is defined in a module which is not imported yet, the import
+    /// is added.
     pub fn pick_completion
     (&self, picked_suggestion:CompletionSuggestion) -> FallibleResult<String> {
         let added_ast         = self.parser.parse_line(&picked_suggestion.code_to_insert())?;
+        self.add_required_import(&picked_suggestion);
         let id                = self.data.borrow().input.next_completion_id();
         let picked_completion = FragmentAddedByPickingSuggestion {id,picked_suggestion};
         let pattern_offset    = self.data.borrow().input.pattern_offset;
@@ -318,6 +424,33 @@ impl Searcher {
         Ok(new_input)
     }
 
+    /// Abandon the edited node: remove all imports added when picking suggestions.
+    ///
+    /// This function should be called when user closes the Searcher without accepting the input.
+    pub fn abandon_node(&self) -> FallibleResult<()> {
+        let added_imports = std::mem::take(&mut self.data.borrow_mut().added_imports);
+        for import in added_imports {
+            info!(self.logger,"Removing import of {import} added by picking suggestion.");
+            self.module_controller.remove_import(&import)?;
+        }
+        Ok(())
+    }
+
+    /// Add the import of the module defining the suggestion, unless it is the current module or it
+    /// is already imported. The added import is remembered, so it may be removed when the node is
+    /// abandoned.
+    fn add_required_import(&self, suggestion:&CompletionSuggestion) {
+        let module           = &suggestion.module;
+        let is_current       = module == self.module.as_ref();
+        let import           = ImportInfo::from_qualified_name(module);
+        let already_imported = self.module_controller.imports().contains(&import);
+        if !is_current && !already_imported {
+            info!(self.logger,"Adding import of {module}.");
+            self.module_controller.add_import(module);
+            self.data.borrow_mut().added_imports.push(module.clone());
+        }
+    }
+
     fn invalidate_fragments_added_by_picking(&self) {
         let mut data = self.data.borrow_mut();
         let data     = data.deref_mut();
@@ -325,53 +458,140 @@ impl Searcher {
         data.fragments_added_by_picking.drain_filter(|frag| !frag.is_still_unmodified(input));
     }
 
+    /// Filter and rank again the last list received from the engine, using the current input
+    /// pattern. It does nothing if no list was received yet.
+    fn refilter_list(&self) {
+        let refreshed = self.data.borrow_mut().refresh_suggestions(&self.database);
+        if refreshed {
+            executor::global::spawn(self.notifier.publish(Notification::NewSuggestionList));
+        }
+    }
+
     /// Reload Suggestion List.
     ///
-    /// The current list will be set as "Loading" and Language Server will be requested for a new
-    /// list - once it be retrieved, the new list will be set and notification will be emitted.
+    /// The Language Server will be requested for a new list - once it be retrieved, the new list
+    /// will be set and notification will be emitted. Until then, the previously received list
+    /// is still available (filtered by the current pattern); if there is no such list, the
+    /// suggestions are set as "Loading".
     fn reload_list(&self) {
         let next_completion = self.data.borrow().input.next_completion_id();
-        let new_suggestions = if next_completion == CompletedFragmentId::Function {
-            self.get_suggestion_list_from_engine(None,None);
-            Suggestions::Loading
-        } else {
-            // TODO[ao] Requesting for argument.
-            Suggestions::Loaded {list:default()}
-        };
-        self.data.borrow_mut().suggestions = new_suggestions;
+        match next_completion {
+            CompletedFragmentId::Function => {
+                self.get_suggestion_list_from_engine(None,None);
+            },
+            CompletedFragmentId::Argument {index} => {
+                let return_type = self.expected_argument_type(index);
+                self.get_argument_suggestion_list_from_engine(return_type);
+            }
+        }
+        let mut data = self.data.borrow_mut();
+        if data.engine_suggestions.is_none() {
+            data.suggestions = Suggestions::Loading;
+        }
+    }
+
+    /// Get the picked function's argument definition at given position, if known.
+    ///
+    /// The function is known only if it was added by picking suggestion and was not modified by
+    /// user since then.
+    fn picked_function_argument
+    (&self, index:usize) -> Option<model::suggestion_database::Argument> {
+        let data      = self.data.borrow();
+        let mut frags = data.fragments_added_by_picking.iter();
+        let function = frags.find(|frag| frag.id == CompletedFragmentId::Function)?;
+        function.picked_suggestion.arguments_to_fill().get(index).cloned()
+    }
+
+    /// Get the type expected at the given argument position, if known. The generic `Any` type is
+    /// treated as unknown.
+    fn expected_argument_type(&self, index:usize) -> Option<String> {
+        let argument = self.picked_function_argument(index)?;
+        let is_specific = argument.arg_type != ANY_TYPE_NAME;
+        is_specific.and_option_from(|| Some(argument.arg_type))
     }
 
     fn get_suggestion_list_from_engine
     (&self, return_type:Option<String>, tags:Option<Vec<language_server::SuggestionEntryType>>) {
-        let ls          = &self.language_server;
-        let module      = self.module.as_ref();
-        let self_type   = None;
-        let position    = self.position.deref().into();
-        let request     = ls.completion(module,&position,&self_type,&return_type,&tags);
-        let data        = self.data.clone_ref();
-        let database    = self.database.clone_ref();
-        let logger      = self.logger.clone_ref();
-        let notifier    = self.notifier.clone_ref();
+        let request = self.completion_request(return_type,tags);
+        self.load_suggestion_list(vec![request]);
+    }
+
+    /// Request the engine for the suggestions for an argument of given type. Besides the
+    /// suggestions of the expected type, the local variables visible at the node's position are
+    /// also suggested, as their type is often unknown.
+    fn get_argument_suggestion_list_from_engine(&self, return_type:Option<String>) {
+        let mut requests = vec![self.completion_request(return_type.clone(),None)];
+        if return_type.is_some() {
+            let locals = Some(vec![language_server::SuggestionEntryType::Local]);
+            requests.push(self.completion_request(None,locals));
+        }
+        self.load_suggestion_list(requests);
+    }
+
+    fn completion_request
+    (&self, return_type:Option<String>, tags:Option<Vec<language_server::SuggestionEntryType>>)
+    -> CancellableCompletionRequest {
+        let ls        = self.language_server.with_options(default());
+        let module    = self.module.as_ref();
+        let self_type = None;
+        let position  = self.position.deref().into();
+        ls.completion(module,&position,&self_type,&return_type,&tags)
+    }
+
+    /// Wait for all given completion requests and set their merged results as the new suggestion
+    /// list. The order of requests is kept and duplicated entries are skipped.
+    ///
+    /// The requests for the previous list are cancelled, as their results would be overwritten
+    /// anyway.
+    fn load_suggestion_list(&self, requests:Vec<CancellableCompletionRequest>) {
+        let (handles,requests):(Vec<_>,Vec<_>) = requests.into_iter().unzip();
+        let handles  = handles.into_iter().flatten().collect();
+        let previous = std::mem::replace(&mut self.data.borrow_mut().pending_completions,handles);
+        for handle in previous {
+            handle.cancel();
+        }
+        let data     = self.data.clone_ref();
+        let database = self.database.clone_ref();
+        let logger   = self.logger.clone_ref();
+        let notifier = self.notifier.clone_ref();
         executor::global::spawn(async move {
             info!(logger,"Requesting new suggestion list.");
-            let response = request.await;
+            let responses = futures::future::join_all(requests).await;
+            let cancelled = |response:&json_rpc::Result<_>| match response {
+                Err(RpcError::Cancelled) => true,
+                _                        => false,
+            };
+            if responses.iter().any(cancelled) {
+                info!(logger,"Suggestion list request was cancelled.");
+                return
+            }
             info!(logger,"Received suggestions from Language Server.");
-            let new_suggestions = match response {
-                Ok(list) => {
-                    let entry_ids   = list.results.into_iter();
-                    let entries     = entry_ids.filter_map(|id| {
-                        let entry = database.get(id);
-                        if entry.is_none() {
-                            error!(logger,"Missing entry {id} in Suggestion Database.");
-                        }
-                        entry
-                    });
-                    let suggestions = entries.map(Suggestion::Completion);
-                    Suggestions::Loaded {list:Rc::new(suggestions.collect())}
+            let responses: json_rpc::Result<Vec<_>> = responses.into_iter().collect();
+            let engine_suggestions = responses.map(|lists| {
+                let mut visited = HashSet::new();
+                let entry_ids   = lists.into_iter().flat_map(|list| list.results);
+                let entry_ids   = entry_ids.filter(|id| visited.insert(*id));
+                let entry_ids   = entry_ids.filter(|id| {
+                    let is_present = database.get(*id).is_some();
+                    if !is_present {
+                        error!(logger,"Missing entry {id} in Suggestion Database.");
+                    }
+                    is_present
+                });
+                Rc::new(entry_ids.collect_vec())
+            });
+            let mut data = data.borrow_mut();
+            match engine_suggestions {
+                Ok(ids) => {
+                    data.engine_suggestions = Some(ids);
+                    data.refresh_suggestions(&database);
                 },
-                Err(error) => Suggestions::Error(Rc::new(error.into()))
-            };
-            data.borrow_mut().suggestions = new_suggestions;
+                Err(error) => {
+                    data.suggestions        = Suggestions::Error(Rc::new(error.into()));
+                    data.engine_suggestions = None;
+                }
+            }
+            drop(data);
             notifier.publish(Notification::NewSuggestionList).await;
         });
     }
@@ -396,6 +616,7 @@ mod test {
         searcher : Searcher,
         entry1   : CompletionSuggestion,
         entry2   : CompletionSuggestion,
+        entry3   : CompletionSuggestion,
         entry9   : CompletionSuggestion,
     }
 
@@ -404,15 +625,18 @@ mod test {
             let mut client  = language_server::MockClient::default();
             let module_path = Path::from_mock_module_name("Test");
             client_setup(&mut client);
+            let language_server   = language_server::Connection::new_mock_rc(client);
+            let parser            = Parser::new_or_panic();
+            let module_controller = controller::Module::new_mock(module_path.clone(),
+                "main = 2 + 2",default(),language_server.clone_ref(),parser.clone_ref()).unwrap();
             let searcher = Searcher {
-                logger          : default(),
-                data            : default(),
-                notifier        : default(),
-                module          : Rc::new(module_path.qualified_module_name("Test")),
-                position        : Immutable(TextLocation::at_document_begin()),
-                database        : default(),
-                language_server : language_server::Connection::new_mock_rc(client),
-                parser          : Parser::new_or_panic(),
+                module_controller,language_server,parser,
+                logger            : default(),
+                data              : default(),
+                notifier          : default(),
+                module            : Rc::new(module_path.qualified_module_name("Test")),
+                position          : Immutable(TextLocation::at_document_begin()),
+                database          : default(),
             };
             let entry1 = model::suggestion_database::Entry {
                 name          : "TestFunction1".to_string(),
@@ -428,15 +652,50 @@ mod test {
                 kind : model::suggestion_database::EntryKind::Local,
                 ..entry1.clone()
             };
+            let entry3 = model::suggestion_database::Entry {
+                name      : "TestMethod1".to_string(),
+                kind      : model::suggestion_database::EntryKind::Method,
+                self_type : Some("Test".to_string()),
+                arguments : vec![
+                    argument_definition("this","Any"),
+                    argument_definition("num","Number"),
+                    argument_definition("any","Any"),
+                ],
+                ..entry1.clone()
+            };
             let entry9 = entry1.clone().with_name("TestFunction2");
 
             searcher.database.put_entry(1,entry1);
             let entry1 = searcher.database.get(1).unwrap();
             searcher.database.put_entry(2,entry2);
             let entry2 = searcher.database.get(2).unwrap();
+            searcher.database.put_entry(3,entry3);
+            let entry3 = searcher.database.get(3).unwrap();
             searcher.database.put_entry(9,entry9);
             let entry9 = searcher.database.get(9).unwrap();
-            Fixture{searcher,entry1,entry2,entry9}
+            Fixture{searcher,entry1,entry2,entry3,entry9}
+        }
+    }
+
+    fn argument_definition(name:&str, arg_type:&str) -> model::suggestion_database::Argument {
+        model::suggestion_database::Argument {
+            name          : name.to_string(),
+            arg_type      : arg_type.to_string(),
+            is_suspended  : false,
+            default_value : None,
+        }
+    }
+
+    fn suggestions_in(suggestions:&Suggestions) -> Option<Vec<Suggestion>> {
+        let list = suggestions.list()?;
+        Some(list.iter().map(|entry| entry.suggestion.clone_ref()).collect())
+    }
+
+    fn completion_response(results:&[language_server::SuggestionEntryId])
+    -> language_server::response::Completion {
+        language_server::response::Completion {
+            results         : results.to_vec(),
+            current_version : default(),
         }
     }
 
@@ -463,7 +722,166 @@ mod test {
         assert!(searcher.suggestions().is_loading());
         test.run_until_stalled();
         let expected_list = vec![Suggestion::Completion(entry1),Suggestion::Completion(entry9)];
-        assert_eq!(searcher.suggestions().list(), Some(&expected_list));
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
+        let notification = subscriber.next().boxed_local().expect_ready();
+        assert_eq!(notification, Some(Notification::NewSuggestionList));
+    }
+
+    #[wasm_bindgen_test]
+    fn ignoring_cancelled_list() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let Fixture{searcher,..} = Fixture::new(|client| {
+            client.expect.completion(|_,_,_,_,_| Err(RpcError::Cancelled));
+        });
+
+        searcher.reload_list();
+        test.run_until_stalled();
+        assert!(searcher.suggestions().is_loading());
+    }
+
+    #[wasm_bindgen_test]
+    fn loading_list_for_argument() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let Fixture{searcher,entry1,entry2,entry3,entry9} = Fixture::new(|client| {
+            let locals = Some(vec![language_server::SuggestionEntryType::Local]);
+            expect_call!(client.completion(
+                module      = "Test.Test".to_string(),
+                position    = TextLocation::at_document_begin().into(),
+                self_type   = None,
+                return_type = Some("Number".to_string()),
+                tag         = None
+            ) => Ok(completion_response(&[1,9])));
+            expect_call!(client.completion(
+                module      = "Test.Test".to_string(),
+                position    = TextLocation::at_document_begin().into(),
+                self_type   = None,
+                return_type = None,
+                tag         = locals
+            ) => Ok(completion_response(&[2,9])));
+            expect_call!(client.completion(
+                module      = "Test.Test".to_string(),
+                position    = TextLocation::at_document_begin().into(),
+                self_type   = None,
+                return_type = None,
+                tag         = None
+            ) => Ok(completion_response(&[1,2,3,9])));
+        });
+
+        // The `this` argument is already filled by inserted code, so we expect `num` argument.
+        let new_input = searcher.pick_completion(entry3.clone_ref()).unwrap();
+        assert_eq!(new_input, "Test.TestMethod1 ");
+        assert!(searcher.suggestions().is_loading());
+        test.run_until_stalled();
+        let expected_list = vec![
+            Suggestion::Completion(entry1.clone_ref()),
+            Suggestion::Completion(entry9.clone_ref()),
+            Suggestion::Completion(entry2.clone_ref()),
+        ];
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
+
+        // The `any` argument accepts everything, so we ask for all suggestions.
+        searcher.set_input("Test.TestMethod1 12 ".to_string()).unwrap();
+        test.run_until_stalled();
+        let expected_list = vec![
+            Suggestion::Completion(entry1),
+            Suggestion::Completion(entry2),
+            Suggestion::Completion(entry3),
+            Suggestion::Completion(entry9),
+        ];
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
+    }
+
+    #[wasm_bindgen_test]
+    fn filtering_list_locally() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let Fixture{searcher,entry1,entry3,entry9,..} = Fixture::new(|client| {
+            client.expect.completion(|_,_,_,_,_| Ok(completion_response(&[1,2,3,9])));
+            client.expect.completion(|_,_,_,_,_| Ok(completion_response(&[1])));
+        });
+        searcher.reload_list();
+        test.run_until_stalled();
+        let mut subscriber = searcher.subscribe();
+
+        // Typing the function name does not require a new request.
+        searcher.set_input("TestF".to_string()).unwrap();
+        let expected_list = vec![
+            Suggestion::Completion(entry1.clone_ref()),
+            Suggestion::Completion(entry9.clone_ref()),
+        ];
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
+        let list     = searcher.suggestions();
+        let spans    = list.list().unwrap().iter().map(|entry| entry.match_info.spans.clone());
+        let expected = vec![vec![0..5],vec![0..5]];
+        assert_eq!(spans.collect_vec(), expected);
+        test.run_until_stalled();
+        let notification = subscriber.next().boxed_local().expect_ready();
+        assert_eq!(notification, Some(Notification::NewSuggestionList));
+
+        // The best matches are placed first.
+        searcher.set_input("tm".to_string()).unwrap();
+        let list = suggestions_in(&searcher.suggestions()).unwrap();
+        assert_eq!(list.first(), Some(&Suggestion::Completion(entry3)));
+
+        // While the new list is loaded, the old one is still filtered by the input.
+        searcher.set_input("TestFunction2 ".to_string()).unwrap();
+        assert!(!searcher.suggestions().is_loading());
+        searcher.set_input("TestFunction2 TestFunction2".to_string()).unwrap();
+        let expected_list = vec![Suggestion::Completion(entry9)];
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
+        test.run_until_stalled();
+        let expected_list = vec![];
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
+    }
+
+    #[wasm_bindgen_test]
+    fn adding_imports_when_picking() {
+        let _test = TestWithLocalPoolExecutor::set_up();
+        let Fixture{searcher,entry1,..} = Fixture::new(|client| {
+            for _ in 0..3 {
+                client.expect.completion(|_,_,_,_,_| Ok(completion_response(&[])));
+            }
+        });
+        let other_module_entry = model::suggestion_database::Entry {
+            name   : "OtherFunction".to_string(),
+            module : "Test.Other".to_string().try_into().unwrap(),
+            ..entry1.deref().clone()
+        };
+        searcher.database.put_entry(10,other_module_entry);
+        let entry10 = searcher.database.get(10).unwrap();
+
+        searcher.pick_completion(entry10.clone_ref()).unwrap();
+        searcher.module_controller.expect_code("import Test.Other\nmain = 2 + 2");
+        // Neither already imported nor current module is imported again.
+        searcher.pick_completion(entry10).unwrap();
+        searcher.pick_completion(entry1).unwrap();
+        searcher.module_controller.expect_code("import Test.Other\nmain = 2 + 2");
+
+        searcher.abandon_node().unwrap();
+        searcher.module_controller.expect_code("main = 2 + 2");
+    }
+
+    #[wasm_bindgen_test]
+    fn refreshing_list_on_database_update() {
+        use model::suggestion_database::Update;
+        use language_server::types::SuggestionDatabaseUpdateEvent;
+
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let Fixture{searcher,entry1,..} = Fixture::new(|client| {
+            client.expect.completion(|_,_,_,_,_| Ok(completion_response(&[1,9])));
+        });
+        searcher.watch_database();
+        searcher.reload_list();
+        test.run_until_stalled();
+        let mut subscriber = searcher.subscribe();
+
+        let update = SuggestionDatabaseUpdateEvent {
+            updates         : vec![Update::Remove {id:9}],
+            current_version : 1,
+        };
+        searcher.database.apply_update_event(update).unwrap();
+        test.run_until_stalled();
+        let expected_list = vec![Suggestion::Completion(entry1)];
+        assert_eq!(suggestions_in(&searcher.suggestions()), Some(expected_list));
         let notification = subscriber.next().boxed_local().expect_ready();
         assert_eq!(notification, Some(Notification::NewSuggestionList));
     }
@@ -540,7 +958,13 @@ mod test {
 
     #[wasm_bindgen_test]
     fn picked_completions_list_maintaining() {
-        let Fixture{searcher,entry1,entry2,..} = Fixture::new(|_|{});
+        let _test = TestWithLocalPoolExecutor::set_up();
+        let Fixture{searcher,entry1,entry2,..} = Fixture::new(|client| {
+            // Picking and typing arguments makes the searcher request for argument suggestions.
+            for _ in 0..5 {
+                client.expect.completion(|_,_,_,_,_| Ok(completion_response(&[])));
+            }
+        });
         let frags_borrow = || Ref::map(searcher.data.borrow(),|d| &d.fragments_added_by_picking);
 
         // Picking first suggestion.
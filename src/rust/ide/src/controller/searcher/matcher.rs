@@ -0,0 +1,269 @@
+//! A fuzzy matcher used by Searcher Controller to filter and rank suggestions locally, without
+//! asking the Language Server each time the user types a character.
+
+use crate::prelude::*;
+
+use crate::model::suggestion_database::Entry;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Score for each matched character.
+const CHAR_MATCH_SCORE:Score = 1;
+/// The number of the documentation's leading characters which are matched with the pattern. The
+/// rest of the documentation is skipped, as matching long texts on every keystroke is too slow.
+const DOCUMENTATION_MATCH_LIMIT:usize = 256;
+/// Bonus for character matched at the beginning of a word (including camel case humps).
+const WORD_START_BONUS:Score = 3;
+/// Bonus for character matched directly after the previously matched one.
+const CONSECUTIVE_BONUS:Score = 2;
+/// The weight of the score gathered by matching characters. The characters left unmatched are
+/// subtracted from the score with weight 1, so the text length decides only between otherwise
+/// equal matches.
+const MATCH_WEIGHT:Score = 16;
+
+
+
+// =============
+// === Match ===
+// =============
+
+/// The score of a match. The greater score means the better match.
+pub type Score = i64;
+
+/// The field of suggestion entry which was matched with the pattern.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub enum MatchedField {
+    Name,SelfType,Module,Documentation
+}
+
+impl MatchedField {
+    /// The multiplier of match's score: the matches of name are much more relevant than matches
+    /// found in documentation.
+    fn weight(self) -> Score {
+        match self {
+            Self::Name          => 8,
+            Self::SelfType      => 4,
+            Self::Module        => 2,
+            Self::Documentation => 1,
+        }
+    }
+}
+
+/// The information how the pattern was matched with a suggestion entry.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Match {
+    /// The score of the match, used for ranking suggestions.
+    pub score : Score,
+    /// The entry's field where the pattern was matched.
+    pub field : MatchedField,
+    /// The ranges of characters (not bytes) of the matched field, which were matched with the
+    /// pattern. They should be highlighted in the view.
+    pub spans : Vec<Range<usize>>,
+}
+
+impl Match {
+    /// The match of an empty pattern, which matches every entry.
+    pub fn trivial() -> Self {
+        Self {score:0, field:MatchedField::Name, spans:default()}
+    }
+}
+
+/// Match the pattern with the suggestion entry.
+///
+/// The pattern is matched with the entry's name, self type, module and documentation; the best
+/// match is returned. The pattern matches the text if its characters are a subsequence of the text
+/// (ignoring case), so the pattern may be e.g. the initials of the camel or snake case words. If
+/// pattern does not match any field, `None` is returned.
+pub fn match_entry(pattern:&str, entry:&Entry) -> Option<Match> {
+    if pattern.is_empty() {
+        return Some(Match::trivial())
+    }
+    let fields = [
+        (MatchedField::Name         , Some(entry.name.as_str())),
+        (MatchedField::SelfType     , entry.self_type.as_deref()),
+        (MatchedField::Module       , Some(entry.module.as_str())),
+        (MatchedField::Documentation, entry.documentation.as_deref().map(documentation_head)),
+    ];
+    let matches = fields.iter().filter_map(|(field,text)| {
+        let (score,spans) = match_text(pattern,(*text)?)?;
+        let score         = score * field.weight();
+        Some(Match {score,spans,field:*field})
+    });
+    // We prefer the first field if the scores are equal.
+    matches.fold(None,|best:Option<Match>,current| match best {
+        Some(best) if best.score >= current.score => Some(best),
+        _                                         => Some(current),
+    })
+}
+
+/// Match the pattern with the text. Returns the match's score and matched character ranges.
+///
+/// The cost is linear in the text length for each pattern character: the positions where the rest
+/// of the pattern can still be matched are computed once, before choosing the matched characters.
+fn match_text(pattern:&str, text:&str) -> Option<(Score,Vec<Range<usize>>)> {
+    let pattern     = pattern.chars().collect_vec();
+    let text        = text.chars().collect_vec();
+    let last_starts = last_match_starts(&pattern,&text)?;
+    let mut matched : Vec<usize> = default();
+    let mut score   = 0;
+    for (pattern_index,pattern_char) in pattern.iter().enumerate() {
+        let position    = matched.last().map_or(0,|i| i + 1);
+        // The character must be matched early enough to leave room for the rest of the pattern.
+        let candidates  = position..=last_starts[pattern_index];
+        let matches_at  = |index:&usize| chars_match(text[*index],*pattern_char);
+        let consecutive = if matched.is_empty() { None } else {
+            Some(position).filter(|i| candidates.contains(i)).filter(matches_at)
+        };
+        let word_start  = || candidates.clone().find(|i| is_word_start(&text,*i) && matches_at(i));
+        let any         = || candidates.clone().find(matches_at);
+        let index       = consecutive.or_else(word_start).or_else(any)?;
+        score += CHAR_MATCH_SCORE;
+        if consecutive.is_some()      { score += CONSECUTIVE_BONUS }
+        if is_word_start(&text,index) { score += WORD_START_BONUS  }
+        matched.push(index);
+    }
+    let unmatched = (text.len() - matched.len()) as Score;
+    let score     = score * MATCH_WEIGHT - unmatched.min(MATCH_WEIGHT - 1);
+    Some((score,spans_from_indices(&matched)))
+}
+
+/// For each pattern character, return the last text index where it can be matched so that the
+/// rest of the pattern is still a subsequence of the remaining text. Returns `None` if the pattern
+/// is not a subsequence of the text.
+fn last_match_starts(pattern:&[char], text:&[char]) -> Option<Vec<usize>> {
+    let mut last_starts = vec![0; pattern.len()];
+    let mut end         = text.len();
+    for (pattern_index,pattern_char) in pattern.iter().enumerate().rev() {
+        end = (0..end).rev().find(|i| chars_match(text[*i],*pattern_char))?;
+        last_starts[pattern_index] = end;
+    }
+    Some(last_starts)
+}
+
+/// The beginning of the documentation which is matched with the pattern.
+fn documentation_head(documentation:&str) -> &str {
+    let end = documentation.char_indices().nth(DOCUMENTATION_MATCH_LIMIT).map(|(index,_)| index);
+    &documentation[..end.unwrap_or_else(|| documentation.len())]
+}
+
+/// Check if the characters are equal, ignoring case.
+fn chars_match(text_char:char, pattern_char:char) -> bool {
+    text_char.to_lowercase().eq(pattern_char.to_lowercase())
+}
+
+/// Check if the character at given index begins a new word. The words are separated by any
+/// non-alphanumeric characters (like `_` in snake case or `.` in qualified names) or begin with
+/// capital letter (as in camel case).
+fn is_word_start(text:&[char], index:usize) -> bool {
+    match index.checked_sub(1).map(|i| text[i]) {
+        None           => true,
+        Some(previous) => {
+            let current = text[index];
+            let after_separator = !previous.is_alphanumeric() && current.is_alphanumeric();
+            let camel_case_hump = previous.is_lowercase() && current.is_uppercase();
+            after_separator || camel_case_hump
+        }
+    }
+}
+
+/// Group the sorted character indices into ranges of consecutive indices.
+fn spans_from_indices(indices:&[usize]) -> Vec<Range<usize>> {
+    let mut spans : Vec<Range<usize>> = default();
+    for index in indices {
+        match spans.last_mut() {
+            Some(span) if span.end == *index => span.end += 1,
+            _                                => spans.push(*index..*index + 1),
+        }
+    }
+    spans
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::suggestion_database::EntryKind;
+
+    fn mock_entry(name:&str) -> Entry {
+        Entry {
+            name          : name.to_string(),
+            kind          : EntryKind::Method,
+            module        : "Project.Main".to_string().try_into().unwrap(),
+            arguments     : vec![],
+            return_type   : "Number".to_string(),
+            documentation : Some("Some documentation".to_string()),
+            self_type     : Some("Number".to_string()),
+        }
+    }
+
+    #[test]
+    fn matching_text() {
+        assert_eq!(match_text("",          "foo").map(|m| m.1), Some(vec![]));
+        assert_eq!(match_text("foo",       "foo").map(|m| m.1), Some(vec![0..3]));
+        assert_eq!(match_text("FO",        "foo").map(|m| m.1), Some(vec![0..2]));
+        assert_eq!(match_text("fb",        "foo_bar").map(|m| m.1), Some(vec![0..1,4..5]));
+        assert_eq!(match_text("tf",        "testFunction").map(|m| m.1), Some(vec![0..1,4..5]));
+        assert_eq!(match_text("ab",        "xab_a").map(|m| m.1), Some(vec![1..3]));
+        assert_eq!(match_text("baz",       "foo_bar").map(|m| m.1), None);
+        assert_eq!(match_text("oof",       "foo").map(|m| m.1), None);
+    }
+
+    #[test]
+    fn ranking_matches() {
+        let score = |pattern,text| match_text(pattern,text).unwrap().0;
+        // Initials are better than random subsequence.
+        assert!(score("tf","testFunction") > score("tf","toffee"));
+        // Consecutive characters are better than scattered ones.
+        assert!(score("fun","function") > score("fun","fauna"));
+        // Between equal matches the shorter text wins.
+        assert!(score("foo","foo") > score("foo","foo_bar"));
+    }
+
+    #[test]
+    fn matching_entry() {
+        let entry = mock_entry("testFunction");
+
+        let name_match = match_entry("tf",&entry).unwrap();
+        assert_eq!(name_match.field, MatchedField::Name);
+        assert_eq!(name_match.spans, vec![0..1,4..5]);
+
+        let module_match = match_entry("Main",&entry).unwrap();
+        assert_eq!(module_match.field, MatchedField::Module);
+        assert_eq!(module_match.spans, vec![8..12]);
+
+        let doc_match = match_entry("docu",&entry).unwrap();
+        assert_eq!(doc_match.field, MatchedField::Documentation);
+
+        assert_eq!(match_entry("",&entry), Some(Match::trivial()));
+        assert_eq!(match_entry("xyz",&entry), None);
+        assert!(name_match.score > doc_match.score);
+    }
+
+    #[test]
+    fn matching_only_documentation_head() {
+        let mut entry = mock_entry("testFunction");
+        let padding   = "x".repeat(DOCUMENTATION_MATCH_LIMIT);
+        entry.documentation = Some(iformat!("{padding} hidden"));
+        assert_eq!(match_entry("hidden",&entry), None);
+    }
+
+    #[test]
+    fn finding_last_match_starts() {
+        let chars = |text:&str| text.chars().collect_vec();
+        assert_eq!(last_match_starts(&chars("ab"),&chars("abab")), Some(vec![2,3]));
+        assert_eq!(last_match_starts(&chars("AB"),&chars("xaxb")), Some(vec![1,3]));
+        assert_eq!(last_match_starts(&chars("ba"),&chars("ab")) , None);
+        assert_eq!(last_match_starts(&chars("") ,&chars("ab")) , Some(vec![]));
+    }
+}
@@ -6,8 +6,13 @@
 use crate::prelude::*;
 
 use crate::constants::VISUALIZATION_DIRECTORY;
+use crate::notification;
 
 use enso_protocol::language_server;
+use enso_protocol::language_server::CapabilityRegistration;
+use enso_protocol::language_server::FileEvent;
+use enso_protocol::language_server::FileEventKind;
+use flo_stream::Subscriber;
 use ide_view::graph_editor::data;
 use ide_view::graph_editor::component::visualization::definition;
 use ide_view::graph_editor::component::visualization;
@@ -27,9 +32,37 @@ pub enum VisualizationError {
     NotFound {
         identifier : VisualizationPath
     },
-    #[fail(display = "JavaScript visualization \"{}\" failed to be instantiated.", identifier)]
+    #[fail(display = "JavaScript visualization \"{}\" failed to be parsed: {}", identifier,
+        reason)]
+    ParseError {
+        identifier : VisualizationPath,
+        reason     : String,
+    },
+    #[fail(display = "JavaScript visualization \"{}\" failed to be instantiated: {}", identifier,
+        reason)]
     InstantiationError {
-        identifier : VisualizationPath
+        identifier : VisualizationPath,
+        reason     : String,
+    },
+    #[fail(display = "JavaScript visualization \"{}\" failed to be prepared: {}", identifier,
+        reason)]
+    PreparationError {
+        identifier : VisualizationPath,
+        reason     : String,
+    },
+}
+
+impl VisualizationError {
+    /// Creates the error for the given visualization failing to be turned into a definition.
+    fn from_java_script_error
+    (identifier:VisualizationPath, error:visualization::java_script::definition::Error) -> Self {
+        use visualization::java_script::definition::Error;
+        let reason = error.to_string();
+        match error {
+            Error::InvalidFunction(_) => Self::ParseError         {identifier,reason},
+            Error::EvaluationError(_) => Self::InstantiationError {identifier,reason},
+            Error::InvalidClass(_)    => Self::PreparationError   {identifier,reason},
+        }
     }
 }
 
@@ -50,6 +83,69 @@ pub enum VisualizationPath {
 
 
 
+// ====================
+// === Notification ===
+// ====================
+
+/// Notification about the change of the visualizations available in the project.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum Notification {
+    /// The visualization was added or modified, and should be loaded again.
+    Changed(VisualizationPath),
+}
+
+
+
+// ===================
+// === FileWatcher ===
+// ===================
+
+/// Translates the Language Server's file events in the project's visualization directory into the
+/// visualization controller's notifications.
+///
+/// Unlike `Handle`, it does not keep the Language Server connection alive, so it can be stored in
+/// the Language Server's event handlers.
+#[derive(Clone,CloneRef,Debug)]
+pub struct FileWatcher {
+    directory : Rc<language_server::Path>,
+    notifier  : notification::Publisher<Notification>,
+}
+
+impl FileWatcher {
+    /// Handles the file event, notifying about the change if it concerns a visualization file.
+    ///
+    /// The removed files are ignored, the visualizations defined by them stay registered until the
+    /// IDE is reloaded.
+    pub fn handle_file_event(&self, event:&FileEvent) {
+        let in_directory = event.path.parent().as_ref() == Some(&*self.directory);
+        if in_directory && event.kind != FileEventKind::Removed {
+            let path         = VisualizationPath::File(event.path.clone());
+            let notification = Notification::Changed(path);
+            executor::global::spawn(self.notifier.publish(notification));
+        }
+    }
+}
+
+/// Acquires the capability for receiving the file events from the project's visualization
+/// directory. Nothing is done if the directory does not exist.
+pub async fn acquire_file_updates(language_server:&language_server::Connection)
+-> FallibleResult<()> {
+    let path   = visualization_directory(language_server.content_root());
+    let folder = language_server.file_exists(&path).await?;
+    if folder.exists {
+        let capability = CapabilityRegistration::create_receives_tree_updates(path);
+        let method     = &capability.method;
+        language_server.acquire_capability(method,&capability.register_options).await?;
+    }
+    Ok(())
+}
+
+fn visualization_directory(root_id:Uuid) -> language_server::Path {
+    language_server::Path::new(root_id,&[VISUALIZATION_DIRECTORY])
+}
+
+
+
 // ==============================
 // === EmbeddedVisualizations ===
 // ==============================
@@ -76,22 +172,42 @@ pub struct EmbeddedVisualizations {
 #[derive(Debug,Clone,CloneRef)]
 pub struct Handle {
     language_server_rpc     : Rc<language_server::Connection>,
-    embedded_visualizations : Rc<RefCell<EmbeddedVisualizations>>
+    embedded_visualizations : Rc<RefCell<EmbeddedVisualizations>>,
+    library                 : data::LibraryName,
+    notifier                : notification::Publisher<Notification>,
 }
 
 impl Handle {
-    /// Creates a new visualization controller.
+    /// Creates a new visualization controller. The visualizations from the project's directory are
+    /// defined in the given library, named after the project.
     pub fn new
     ( language_server_rpc     : Rc<language_server::Connection>
-    , embedded_visualizations : EmbeddedVisualizations) -> Self {
+    , embedded_visualizations : EmbeddedVisualizations
+    , library                 : impl Into<data::LibraryName>) -> Self {
         let embedded_visualizations = Rc::new(RefCell::new(embedded_visualizations));
-        Self {language_server_rpc,embedded_visualizations}
+        let library                 = library.into();
+        let notifier                = default();
+        Self {language_server_rpc,embedded_visualizations,library,notifier}
+    }
+
+    /// Subscribe to the notifications about the changes of the project's visualizations.
+    pub fn subscribe(&self) -> Subscriber<Notification> {
+        self.notifier.subscribe()
+    }
+
+    /// Creates a watcher for the file events of the project's visualization directory, which
+    /// publishes this controller's notifications.
+    pub fn file_watcher(&self) -> FileWatcher {
+        let root_id   = self.language_server_rpc.content_root();
+        let directory = Rc::new(visualization_directory(root_id));
+        let notifier  = self.notifier.clone_ref();
+        FileWatcher {directory,notifier}
     }
 
     async fn list_project_specific_visualizations
     (&self) -> FallibleResult<Vec<VisualizationPath>> {
         let root_id   = self.language_server_rpc.content_root();
-        let path      = language_server::Path::new(root_id,&[VISUALIZATION_DIRECTORY]);
+        let path      = visualization_directory(root_id);
         let folder    = self.language_server_rpc.file_exists(&path).await?;
         let file_list = if folder.exists {
             self.language_server_rpc.file_list(&path).await?.paths
@@ -136,11 +252,10 @@ impl Handle {
             VisualizationPath::File(path) => {
                 let js_code    = self.language_server_rpc.read_file(&path).await?.contents;
                 let identifier = visualization.clone();
-                let error      = |_| VisualizationError::InstantiationError {identifier}.into();
-                let module     = data::builtin_library(); // FIXME: provide real library name.
-                // TODO: this is wrong. This is translated to InstantiationError and it is preparation error :
-                let js_class   = visualization::java_script::Definition::new(module,&js_code).map_err(error);
-                js_class.map(|t| t.into())
+                let error      = |e| VisualizationError::from_java_script_error(identifier,e);
+                let library    = self.library.clone_ref();
+                let js_class   = visualization::java_script::Definition::new(library,&js_code);
+                Ok(js_class.map_err(error)?.into())
             }
         }
     }
@@ -156,11 +271,14 @@ impl Handle {
 mod tests {
     use super::*;
 
+    use crate::executor::test_utils::TestWithLocalPoolExecutor;
+
     use enso_protocol::language_server::FileSystemObject;
     use enso_protocol::language_server::Path;
     use ide_view::graph_editor::builtin;
     use ide_view::graph_editor::component::visualization;
     use json_rpc::expect_call;
+    use utils::test::stream::StreamTestExt;
 
     use wasm_bindgen_test::wasm_bindgen_test_configure;
     use wasm_bindgen_test::wasm_bindgen_test;
@@ -212,7 +330,8 @@ mod tests {
         let mut embedded_visualizations = EmbeddedVisualizations::default();
         let embedded_visualization      = builtin::visualization::native::BubbleChart::definition();
         embedded_visualizations.insert("[Demo] Bubble Visualization".to_string(), embedded_visualization.clone());
-        let vis_controller              = Handle::new(language_server,embedded_visualizations);
+        let vis_controller              =
+            Handle::new(language_server,embedded_visualizations,"Test");
 
         let visualizations = vis_controller.list_visualizations().await;
         let visualizations = visualizations.expect("Couldn't list visualizations.");
@@ -222,8 +341,8 @@ mod tests {
         assert_eq!(visualizations[2], VisualizationPath::File(path1));
         assert_eq!(visualizations.len(),3);
 
-        let javascript_vis0 = visualization::java_script::Definition::new("builtin", &file_content0);
-        let javascript_vis1 = visualization::java_script::Definition::new("builtin", &file_content1);
+        let javascript_vis0 = visualization::java_script::Definition::new("Test", &file_content0);
+        let javascript_vis1 = visualization::java_script::Definition::new("Test", &file_content1);
         let javascript_vis0 = javascript_vis0.expect("Couldn't create visualization class.");
         let javascript_vis1 = javascript_vis1.expect("Couldn't create visualization class.");
         let javascript_vis0:visualization::Definition = javascript_vis0.into();
@@ -239,4 +358,63 @@ mod tests {
             assert_eq!(loaded_signature,expected_signature);
         }
     }
+
+    #[wasm_bindgen_test(async)]
+    async fn load_invalid() {
+        let mock_client      = language_server::MockClient::default();
+        let root_id          = uuid::Uuid::default();
+        let invalid_syntax   = Path::new(root_id,&["visualization","syntax.js"]);
+        let throwing         = Path::new(root_id,&["visualization","throwing.js"]);
+        let syntax_content   = "class Vis0 { return Vis0".to_string();
+        let throwing_content = "throw new Error(\"Failed\")".to_string();
+        let syntax_result    = language_server::response::Read{contents:syntax_content};
+        let throwing_result  = language_server::response::Read{contents:throwing_content};
+        expect_call!(mock_client.read_file(path=invalid_syntax.clone()) => Ok(syntax_result));
+        expect_call!(mock_client.read_file(path=throwing.clone())       => Ok(throwing_result));
+
+        let language_server = language_server::Connection::new_mock_rc(mock_client);
+        let vis_controller  = Handle::new(language_server,default(),"Test");
+        let vis_controller  = &vis_controller;
+        let load_error      = |path| async move {
+            let visualization = VisualizationPath::File(path);
+            let result        = vis_controller.load_visualization(&visualization).await;
+            let error         = result.expect_err("Invalid visualization was loaded.");
+            error.downcast::<VisualizationError>().unwrap()
+        };
+        let parse_error = load_error(invalid_syntax).await;
+        assert!(matches!(parse_error, VisualizationError::ParseError{..}));
+        let instantiation_error = load_error(throwing).await;
+        assert!(matches!(instantiation_error, VisualizationError::InstantiationError{..}));
+    }
+
+    #[wasm_bindgen_test]
+    fn notifying_about_changed_files() {
+        let mut test          = TestWithLocalPoolExecutor::set_up();
+        let mock_client       = language_server::MockClient::default();
+        let language_server   = language_server::Connection::new_mock_rc(mock_client);
+        let vis_controller    = Handle::new(language_server,default(),"Test");
+        let watcher           = vis_controller.file_watcher();
+        let mut notifications = vis_controller.subscribe().boxed_local();
+        notifications.expect_pending();
+
+        let root_id       = uuid::Uuid::default();
+        let visualization = Path::new(root_id,&["visualization","histogram.js"]);
+        let module        = Path::new(root_id,&["src","Main.enso"]);
+        let event         = |path:&Path, kind| FileEvent {path:path.clone(),kind};
+        watcher.handle_file_event(&event(&module,FileEventKind::Modified));
+        test.run_until_stalled();
+        notifications.expect_pending();
+
+        watcher.handle_file_event(&event(&visualization,FileEventKind::Removed));
+        test.run_until_stalled();
+        notifications.expect_pending();
+
+        watcher.handle_file_event(&event(&visualization,FileEventKind::Added));
+        watcher.handle_file_event(&event(&visualization,FileEventKind::Modified));
+        test.run_until_stalled();
+        let path = VisualizationPath::File(visualization);
+        assert_eq!(notifications.expect_next(), Notification::Changed(path.clone()));
+        assert_eq!(notifications.expect_next(), Notification::Changed(path));
+        notifications.expect_pending();
+    }
 }
@@ -90,6 +90,12 @@ impl PartialEq<QualifiedName> for QualifiedName {
 
 impl Eq for QualifiedName {}
 
+impl Hash for QualifiedName {
+    fn hash<H:std::hash::Hasher>(&self, state:&mut H) {
+        self.text.hash(state)
+    }
+}
+
 
 // ==================
 // === ImportInfo ===
@@ -167,6 +173,11 @@ pub struct ImportNotFound(pub ImportInfo);
 #[allow(missing_docs)]
 pub struct LineIndexOutOfBounds;
 
+#[derive(Clone,Debug,Fail)]
+#[fail(display="Definition `{}` was not found in the module's root scope.",_0)]
+#[allow(missing_docs)]
+pub struct RootDefinitionNotFound(pub definition::DefinitionName);
+
 
 
 // ============
@@ -239,6 +250,23 @@ impl Info {
         index_to_place_at
     }
 
+    /// Add a new method definition to the module, right after the root definition with given name.
+    /// The definitions are separated with an empty line.
+    ///
+    /// Fails if there is no root definition with given name.
+    pub fn add_method_after
+    (&mut self, method:definition::DefinitionInfo, after:&definition::DefinitionName)
+    -> FallibleResult<()> {
+        let lookup_result = self.ast.shape().enumerate().find(|(_,ast)| {
+            let found = definition::DefinitionInfo::from_root_line_ast(ast);
+            found.contains_if(|found| &*found.name == after)
+        });
+        let (crumb,_) = lookup_result.ok_or_else(|| RootDefinitionNotFound(after.clone()))?;
+        self.add_line(crumb.line_index + 1,None);
+        self.add_line(crumb.line_index + 2,Some(method.ast.into()));
+        Ok(())
+    }
+
     #[cfg(test)]
     pub fn expect_code(&self,expected_code:impl AsRef<str>) {
         assert_eq!(self.ast.repr(),expected_code.as_ref());
@@ -401,6 +429,22 @@ mod tests {
         info.expect_code("import Bar.Gar");
     }
 
+    #[wasm_bindgen_test]
+    fn adding_method() {
+        let parser   = parser::Parser::new_or_panic();
+        let code     = "import Foo\n\nmain = bar\n\nbaz = 2";
+        let ast      = parser.parse_module(code,default()).unwrap();
+        let mut info = Info {ast};
+        let method   = parser.parse_line("foo a = a + 1").unwrap();
+        let method   = definition::DefinitionInfo::from_root_line_ast(&method).unwrap();
+        let main     = DefinitionName::new_plain("main");
+        info.add_method_after(method.clone(),&main).unwrap();
+        info.expect_code("import Foo\n\nmain = bar\n\nfoo a = a + 1\n\nbaz = 2");
+
+        let missing = DefinitionName::new_plain("missing");
+        assert!(info.add_method_after(method,&missing).is_err());
+    }
+
     #[wasm_bindgen_test]
     fn implicit_method_resolution() {
         let parser = parser::Parser::new_or_panic();
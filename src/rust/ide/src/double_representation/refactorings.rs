@@ -0,0 +1,3 @@
+//! Module with refactorings: the operations rearranging the code without changing its behavior.
+
+pub mod collapse;
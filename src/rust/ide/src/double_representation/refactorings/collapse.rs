@@ -0,0 +1,337 @@
+//! Collapsing nodes: moving a set of graph's nodes into a new method definition and replacing them
+//! with a single node calling that method.
+
+use crate::prelude::*;
+
+use crate::double_representation::alias_analysis;
+use crate::double_representation::alias_analysis::LocatedName;
+use crate::double_representation::alias_analysis::NormalizedName;
+use crate::double_representation::definition::DefinitionInfo;
+use crate::double_representation::definition::DefinitionProvider;
+use crate::double_representation::graph::GraphInfo;
+use crate::double_representation::module;
+use crate::double_representation::node;
+use crate::double_representation::node::NodeInfo;
+
+use ast::BlockLine;
+use ast::opr::predefined::ACCESS;
+use ast::opr::predefined::ASSIGNMENT;
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+/// Error raised when collapsing was requested for an empty set of nodes.
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="No nodes were given to be collapsed.")]
+pub struct NoNodesToCollapse;
+
+/// Error raised when more than one identifier introduced by the collapsed nodes is used by the
+/// rest of the graph. The new method can return only a single value.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="Cannot collapse nodes: identifiers {:?} are used outside of the collapsed nodes.",
+_0)]
+pub struct MultipleOutputs(pub Vec<String>);
+
+/// Error raised when a node placed between the collapsed nodes, but not collapsed itself, uses an
+/// identifier introduced by the collapsed nodes. Such node would be placed before the node calling
+/// the new method.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="Cannot collapse nodes: identifier {} is used by a node placed between them.",_0)]
+pub struct UsedBetweenCollapsedNodes(pub String);
+
+/// Error raised when the module already has a method with the name given for the new method.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="Cannot collapse nodes: the module already defines method {}.",_0)]
+pub struct MethodAlreadyDefined(pub String);
+
+/// The AST of the new method could not be interpreted as a definition. Means a bug in the
+/// collapsing code.
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="Internal error: failed to create a new method definition.")]
+pub struct FailedToCreateMethod;
+
+
+
+// ================
+// === Collapse ===
+// ================
+
+/// The result of collapsing nodes.
+#[derive(Clone,Debug)]
+pub struct Collapsed {
+    /// The graph's definition with the collapsed nodes replaced by a single node.
+    pub updated_definition : DefinitionInfo,
+    /// The new method definition, containing the collapsed nodes' lines.
+    pub new_method : DefinitionInfo,
+    /// The id of the node calling the new method.
+    pub collapsed_node : node::Id,
+}
+
+/// Collapses the selected nodes of the graph into a new method named `method_name`.
+///
+/// The new method's body consists of the selected nodes' lines in their original order. The
+/// identifiers used by the selected nodes, but introduced earlier by other nodes or by the graph
+/// definition's arguments, become the method's parameters. The identifier introduced by the
+/// selected nodes and used by the rest of the graph becomes the method's result. If there is no
+/// such identifier, the last one introduced by the selected nodes is returned.
+///
+/// The selected lines are replaced with a single node calling the new method, placed where the last
+/// selected node was. Therefore the nodes placed between the selected ones cannot use identifiers
+/// introduced by the selected nodes. The method is called as a method of the module named
+/// `module_name`, so the new method is expected to be placed in that module's root scope, which
+/// must not define a method with the same name yet.
+pub fn collapse
+( module         : &module::Info
+, graph          : &GraphInfo
+, selected_nodes : impl IntoIterator<Item=node::Id>
+, method_name    : &str
+, module_name    : &str
+) -> FallibleResult<Collapsed> {
+    let selected_nodes = selected_nodes.into_iter().collect::<HashSet<_>>();
+    if selected_nodes.is_empty() {
+        return Err(NoNodesToCollapse.into())
+    }
+    if is_method_defined(module,method_name,module_name) {
+        return Err(MethodAlreadyDefined(method_name.to_owned()).into())
+    }
+    let lines = graph.source.block_lines()?;
+    let nodes = lines.iter().enumerate().filter_map(|(index,line)| {
+        Some((index,NodeInfo::from_block_line(line)?))
+    }).collect_vec();
+    if let Some(id) = selected_nodes.iter().find(|id| !nodes.iter().any(|(_,n)| n.id() == **id)) {
+        return Err(node::IdNotFound {id:*id}.into())
+    }
+    let is_selected    = |node:&NodeInfo| selected_nodes.contains(&node.id());
+    let selected_lines = nodes.iter().filter(|(_,node)| is_selected(node)).map(|(index,_)| *index);
+    // We checked earlier that all the selected nodes are present.
+    let first_selected = selected_lines.clone().min().unwrap();
+    let last_selected  = selected_lines.max().unwrap();
+
+    // === Identifiers Analysis ===
+
+    let definition_args  = graph.source.args.iter();
+    let mut available    = definition_args.filter_map(|arg| {
+        NormalizedName::try_from_ast(&arg.item)
+    }).collect::<HashSet<_>>();
+    let mut used_outside = HashSet::<NormalizedName>::new();
+    let mut parameters   = Vec::<(NormalizedName,String)>::new();
+    let mut introduced   = Vec::<(NormalizedName,String)>::new();
+    for (index,node) in &nodes {
+        let usage         = alias_analysis::analyse_node(node);
+        let is_introduced = |name:&NormalizedName| introduced.iter().any(|(n,_)| n == name);
+        if is_selected(node) {
+            for name in &usage.used {
+                let is_known = parameters.iter().any(|(parameter,_)| parameter == &name.item);
+                let is_new   = !is_known && !is_introduced(&name.item);
+                if is_new && available.contains(&name.item) {
+                    parameters.push((name.item.clone(),identifier_text(node,name)));
+                }
+            }
+            let introduced_names = usage.introduced.iter();
+            introduced.extend(introduced_names.map(|name| {
+                (name.item.clone(),identifier_text(node,name))
+            }));
+        } else {
+            let is_between     = *index > first_selected && *index < last_selected;
+            let used_collapsed = usage.used.iter().find(|name| is_introduced(&name.item));
+            if let Some(name) = used_collapsed.filter(|_| is_between) {
+                return Err(UsedBetweenCollapsedNodes(identifier_text(node,name)).into())
+            }
+            if *index > first_selected {
+                used_outside.extend(usage.used.into_iter().map(|name| name.item));
+            }
+            available.extend(usage.introduced.into_iter().map(|name| name.item));
+        }
+    }
+    let outputs = introduced.iter().filter(|(name,_)| used_outside.contains(name));
+    let outputs = outputs.map(|(_,text)| text.clone()).unique().collect_vec();
+    if outputs.len() > 1 {
+        return Err(MultipleOutputs(outputs).into())
+    }
+    let last_introduced = || introduced.last().map(|(_,text)| text.clone());
+    let output          = outputs.into_iter().next().or_else(last_introduced);
+
+    // === New Method ===
+
+    let parameter_names = || parameters.iter().map(|(_,text)| Ast::var(text.as_str()));
+    let method_lhs      = parameter_names().fold(Ast::var(method_name),Ast::prefix);
+    let opr             = Ast::opr(ASSIGNMENT);
+    let method_ast      = ast::Infix {larg:method_lhs,loff:1,opr,roff:0,rarg:Ast::blank()};
+    let method_ast      = Ast::from(method_ast);
+    let new_method      = DefinitionInfo::from_root_line_ast(&method_ast);
+    let mut new_method  = new_method.ok_or(FailedToCreateMethod)?;
+    let mut body_lines  = lines.iter().filter(|line| {
+        NodeInfo::from_block_line(line).contains_if(is_selected)
+    }).cloned().collect_vec();
+    if let Some(output) = &output {
+        body_lines.push(BlockLine::new(Some(Ast::var(output.as_str()).with_new_id())));
+    }
+    new_method.set_block_lines(body_lines)?;
+
+    // === Updated Definition ===
+
+    let module         = Ast::cons(module_name);
+    let opr            = Ast::opr(ACCESS);
+    let method_ref     = ast::Infix {larg:module,loff:0,opr,roff:0,rarg:Ast::var(method_name)};
+    let collapsed_node = node::Id::new_v4();
+    let call           = parameter_names().fold(Ast::from(method_ref),Ast::prefix);
+    let call           = call.with_id(collapsed_node);
+    let call_line      = match &output {
+        Some(output) => Ast::infix(Ast::var(output.as_str()),ASSIGNMENT,call),
+        None         => call,
+    };
+    let updated_lines  = lines.iter().enumerate().filter_map(|(index,line)| {
+        if index == last_selected {
+            Some(BlockLine {elem:Some(call_line.clone()), off:line.off})
+        } else if NodeInfo::from_block_line(line).contains_if(is_selected) {
+            None
+        } else {
+            Some(line.clone())
+        }
+    }).collect_vec();
+    let mut updated_definition = graph.source.clone();
+    updated_definition.set_block_lines(updated_lines)?;
+    Ok(Collapsed {updated_definition,new_method,collapsed_node})
+}
+
+/// Checks if the module's root scope defines a method named `method_name` on the module itself.
+fn is_method_defined(module:&module::Info, method_name:&str, module_name:&str) -> bool {
+    module.ast.def_iter().any(|definition| {
+        let name = &definition.item.name.item;
+        let name_matches = name.name.item == method_name;
+        let type_matches = match name.extended_target.as_slice() {
+            []         => true,
+            [typename] => typename.item == module_name,
+            _          => false,
+        };
+        name_matches && type_matches
+    })
+}
+
+/// Returns the identifier's text as it is written in the node's code.
+fn identifier_text(node:&NodeInfo, name:&LocatedName) -> String {
+    let ast = node.ast().get_traversing(&name.crumbs).ok();
+    ast.map_or_else(|| name.item.to_string(), |ast| ast.repr())
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::double_representation::definition::DefinitionName;
+    use crate::double_representation::definition::DefinitionProvider;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    struct Case {
+        code                : &'static str,
+        selected_lines      : Vec<usize>,
+        expected_main       : &'static str,
+        expected_new_method : &'static str,
+    }
+
+    impl Case {
+        fn run(&self, parser:&parser::Parser) {
+            let (module,graph) = main_graph(parser,self.code);
+            let nodes          = graph.nodes();
+            let selected       = self.selected_lines.iter().map(|index| nodes[*index].id());
+            let result         = collapse(&module,&graph,selected,"foo","Main").unwrap();
+            assert_eq!(result.updated_definition.ast.repr(), self.expected_main);
+            assert_eq!(result.new_method.ast.repr(), self.expected_new_method);
+
+            let updated_graph = GraphInfo::from_definition(result.updated_definition);
+            assert!(updated_graph.find_node(result.collapsed_node).is_some());
+        }
+    }
+
+    fn main_graph(parser:&parser::Parser, program:impl Str) -> (module::Info,GraphInfo) {
+        let module = parser.parse_module(program.into(), default()).unwrap();
+        let name   = DefinitionName::new_plain("main");
+        let main   = module.def_iter().find_by_name(&name).unwrap();
+        (module::Info {ast:module},GraphInfo::from_definition(main.item))
+    }
+
+    #[wasm_bindgen_test]
+    fn collapsing_nodes() {
+        let parser = parser::Parser::new_or_panic();
+        let cases  = vec![
+            Case {
+                code                : "main =\n    a = 1\n    b = 2\n    c = a + b\n    \
+                                       d = c + 1\n    print d",
+                selected_lines      : vec![2,3],
+                expected_main       : "main =\n    a = 1\n    b = 2\n    d = Main.foo a b\n    \
+                                       print d",
+                expected_new_method : "foo a b =\n    c = a + b\n    d = c + 1\n    d",
+            },
+            Case {
+                code                : "main x =\n    a = x + 1\n    print a",
+                selected_lines      : vec![0],
+                expected_main       : "main x =\n    a = Main.foo x\n    print a",
+                expected_new_method : "foo x =\n    a = x + 1\n    a",
+            },
+            Case {
+                code                : "main =\n    a = 1\n    print a\n    print a",
+                selected_lines      : vec![1,2],
+                expected_main       : "main =\n    a = 1\n    Main.foo a",
+                expected_new_method : "foo a =\n    print a\n    print a",
+            },
+            Case {
+                code                : "main =\n    a = 1\n    x = 5\n    b = a + x\n    print b",
+                selected_lines      : vec![0,2],
+                expected_main       : "main =\n    x = 5\n    b = Main.foo x\n    print b",
+                expected_new_method : "foo x =\n    a = 1\n    b = a + x\n    b",
+            },
+            Case {
+                code                : "main =\n    a = 1\n    b = a + 1\n    print a",
+                selected_lines      : vec![0,1],
+                expected_main       : "main =\n    a = Main.foo\n    print a",
+                expected_new_method : "foo =\n    a = 1\n    b = a + 1\n    a",
+            },
+            Case {
+                code                : "main =\n    b = a + 1\n    a = 2\n    print b",
+                selected_lines      : vec![0],
+                expected_main       : "main =\n    b = Main.foo\n    a = 2\n    print b",
+                expected_new_method : "foo =\n    b = a + 1\n    b",
+            },
+        ];
+        for case in cases {
+            case.run(&parser)
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn collapsing_nodes_failures() {
+        let parser = parser::Parser::new_or_panic();
+        let code   = "main =\n    a = 1\n    b = a + 1\n    print a\n    print b";
+        let (module,graph) = main_graph(&parser,code);
+        let nodes          = graph.nodes();
+
+        assert!(collapse(&module,&graph,vec![],"foo","Main").is_err());
+        assert!(collapse(&module,&graph,vec![node::Id::new_v4()],"foo","Main").is_err());
+        let both_used_outside = vec![nodes[0].id(),nodes[1].id()];
+        assert!(collapse(&module,&graph,both_used_outside,"foo","Main").is_err());
+        let used_between = vec![nodes[0].id(),nodes[3].id()];
+        let result       = collapse(&module,&graph,used_between,"foo","Main");
+        assert!(result.unwrap_err().downcast::<UsedBetweenCollapsedNodes>().is_ok());
+
+        let code           = "main =\n    a = 1\n    print a\n\nfoo = 2\n\nMain.bar = 3";
+        let (module,graph) = main_graph(&parser,code);
+        let selected       = || vec![graph.nodes()[0].id()];
+        let result         = collapse(&module,&graph,selected(),"foo","Main");
+        assert!(result.unwrap_err().downcast::<MethodAlreadyDefined>().is_ok());
+        let result         = collapse(&module,&graph,selected(),"bar","Main");
+        assert!(result.unwrap_err().downcast::<MethodAlreadyDefined>().is_ok());
+        assert!(collapse(&module,&graph,selected(),"baz","Main").is_ok());
+    }
+}
@@ -2,6 +2,8 @@
 
 use crate::prelude::*;
 
+use crate::transport::recording;
+use crate::transport::recording::Recordings;
 use crate::transport::web::ConnectingError;
 use crate::transport::web::WebSocket;
 use crate::view::project::ProjectView;
@@ -48,13 +50,16 @@ pub struct Ide {
 /// The IDE initializer.
 #[derive(Debug)]
 pub struct IdeInitializer {
-    logger : Logger
+    logger     : Logger,
+    /// The recordings of the IDE's connections, if the traffic is recorded.
+    recordings : Option<Recordings>,
 }
 
 impl Default for IdeInitializer {
     fn default() -> Self {
-        let logger = Logger::new("IdeInitializer");
-        Self {logger}
+        let logger     = Logger::new("IdeInitializer");
+        let recordings = None;
+        Self {logger,recordings}
     }
 }
 
@@ -78,29 +83,79 @@ impl IdeInitializer {
         project_manager
     }
 
-    /// Connect to language server.
+    /// Connect to language server. If the `recordings` are given, the traffic with the Language
+    /// Server is recorded, including the connections made when reconnecting.
     pub async fn open_project
     ( logger           : &Logger
     , project_manager  : Rc<dyn project_manager::API>
     , project_metadata : ProjectMetadata
+    , recordings       : Option<Recordings>
     ) -> FallibleResult<model::Project> {
-        let endpoints       = project_manager.open_project(&project_metadata.id).await?;
+        let project_id    = project_metadata.id;
+        let project_name  = project_metadata.name;
+        let client_id     = Uuid::new_v4();
+        let connecting    = Self::connect_to_language_server(logger,&*project_manager,project_id
+            ,recordings.as_ref());
+        let (client_json,client_binary) = connecting.await?;
+        let connection_json   = language_server::Connection::new(client_json,client_id).await?;
+        let connection_binary = binary::Connection::new(client_binary,client_id).await?;
+        let project = model::Project::from_connections(logger,project_manager.clone_ref()
+            ,connection_json,connection_binary,project_id,project_name).await?;
+        let connector = Self::language_server_connector(logger,project_manager,project_id
+            ,recordings);
+        project.set_connector(connector);
+        Ok(project)
+    }
+
+    /// Opens the project and establishes the new connections with its Language Server. The
+    /// returned clients have their runners already scheduled in the global executor.
+    pub async fn connect_to_language_server
+    ( logger          : &Logger
+    , project_manager : &dyn project_manager::API
+    , project_id      : Uuid
+    , recordings      : Option<&Recordings>
+    ) -> FallibleResult<(language_server::Client,binary::Client)> {
+        let endpoints       = project_manager.open_project(&project_id).await?;
         let json_endpoint   = endpoints.language_server_json_address;
         let binary_endpoint = endpoints.language_server_binary_address;
         info!(logger, "Establishing Language Server connection.");
-        let client_id     = Uuid::new_v4();
         let json_ws       = new_opened_ws(logger.clone_ref(), json_endpoint).await?;
         let binary_ws     = new_opened_ws(logger.clone_ref(), binary_endpoint).await?;
-        let client_json   = language_server::Client::new(json_ws);
-        let client_binary = binary::Client::new(logger,binary_ws);
+        let (client_json,client_binary) = match recordings {
+            Some(recordings) => {
+                let json_ws   = recordings.record("language-server-json",json_ws);
+                let binary_ws = recordings.record("language-server-binary",binary_ws);
+                (language_server::Client::new(json_ws),binary::Client::new(logger,binary_ws))
+            }
+            None => (language_server::Client::new(json_ws),binary::Client::new(logger,binary_ws)),
+        };
         crate::executor::global::spawn(client_json.runner());
         crate::executor::global::spawn(client_binary.runner());
-        let connection_json   = language_server::Connection::new(client_json,client_id).await?;
-        let connection_binary = binary::Connection::new(client_binary,client_id).await?;
-        let project_id        = project_metadata.id;
-        let project_name      = project_metadata.name;
-        model::Project::from_connections(logger,project_manager,connection_json,connection_binary
-            ,project_id,project_name).await
+        Ok((client_json,client_binary))
+    }
+
+    /// Creates a function used by the project model to reconnect to its Language Server after the
+    /// connection was lost.
+    pub fn language_server_connector
+    ( logger          : &Logger
+    , project_manager : Rc<dyn project_manager::API>
+    , project_id      : Uuid
+    , recordings      : Option<Recordings>
+    ) -> model::project::Connector {
+        let logger = logger.clone_ref();
+        Rc::new(move || {
+            let logger          = logger.clone_ref();
+            let project_manager = project_manager.clone_ref();
+            let recordings      = recordings.clone();
+            async move {
+                let connecting = Self::connect_to_language_server(&logger,&*project_manager
+                    ,project_id,recordings.as_ref());
+                let (json,binary) = connecting.await?;
+                let json          = Box::new(json);
+                let binary        = Box::new(binary);
+                Ok(model::project::Clients {json,binary})
+            }.boxed_local()
+        })
     }
 
     /// Creates a new project and returns its metadata, so the newly connected project can be
@@ -163,8 +218,26 @@ impl IdeInitializer {
 
     async fn initialize_project_manager
     (&mut self, config:&config::Startup) -> FallibleResult<project_manager::Client> {
-        let transport        = self.connect_to_project_manager(config).await?;
-        Ok(Self::setup_project_manager(transport))
+        let transport = self.connect_to_project_manager(config).await?;
+        match &self.recordings {
+            Some(recordings) => {
+                let transport = recordings.record("project-manager",transport);
+                Ok(Self::setup_project_manager(transport))
+            }
+            None => Ok(Self::setup_project_manager(transport)),
+        }
+    }
+
+    /// Start recording the traffic with the services, and make the recordings downloadable from
+    /// the browser. See `crate::transport::recording`.
+    fn start_recording(&mut self) {
+        let recordings = Recordings::new();
+        if let Err(error) = recordings.expose_in_browser(&self.logger) {
+            error!(self.logger,"Failed to expose the recordings in the browser: {error:?}");
+        }
+        let function = recording::DOWNLOAD_FUNCTION_NAME;
+        info!(self.logger,"Recording the traffic. Call `{function}()` to download it.");
+        self.recordings = Some(recordings);
     }
 
     /// Initialize the project view, including the controller it uses.
@@ -178,7 +251,9 @@ impl IdeInitializer {
         let project_metadata = Self::get_project_or_create_new
             (logger,&project_manager,&project_name).await?;
         let project_manager = Rc::new(project_manager);
-        let project         = Self::open_project(logger,project_manager,project_metadata).await?;
+        let recordings      = self.recordings.clone();
+        let project         = Self::open_project(logger,project_manager,project_metadata
+            ,recordings).await?;
         Ok(ProjectView::new(logger,Rc::new(project)).await?)
     }
 
@@ -188,6 +263,9 @@ impl IdeInitializer {
         let executor = setup_global_executor();
         let config   = config::Startup::new_local();
         info!(self.logger, "Starting IDE with the following config: {config:?}");
+        if config.record_traffic {
+            self.start_recording();
+        }
         executor::global::spawn(async move {
             // TODO [mwu] Once IDE gets some well-defined mechanism of reporting
             //      issues to user, such information should be properly passed
@@ -7,10 +7,14 @@ use crate::notification::Publisher;
 
 use enso_protocol::language_server;
 use enso_protocol::language_server::ExpressionValueUpdate;
+use enso_protocol::language_server::ExpressionValueUpdatePayload;
 use enso_protocol::language_server::ExpressionValuesComputed;
 use enso_protocol::language_server::MethodPointer;
 use enso_protocol::language_server::VisualisationConfiguration;
 use flo_stream::Subscriber;
+use parser::Parser;
+use serde::Deserialize;
+use serde::Serialize;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -28,6 +32,54 @@ pub type ExpressionId = ast::Id;
 
 
 
+// =======================
+// === ExpressionError ===
+// =======================
+
+/// The error which occurred when computing an expression.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum ExpressionError {
+    /// The expression evaluated to a dataflow error.
+    Dataflow {
+        /// The expression which raised the error, if known. It is the first expression of the
+        /// error's trace.
+        origin:Option<ExpressionId>
+    },
+    /// The computation of the expression panicked.
+    Panic {
+        /// The message describing the panic.
+        message:String
+    },
+}
+
+impl ExpressionError {
+    /// Get the error from the expression update payload. Returns `None` if the expression was
+    /// computed to a value.
+    pub fn from_payload(payload:ExpressionValueUpdatePayload) -> Option<Self> {
+        match payload {
+            ExpressionValueUpdatePayload::Value              => None,
+            ExpressionValueUpdatePayload::DataflowError {trace} => {
+                let origin = trace.first().copied();
+                Some(Self::Dataflow {origin})
+            }
+            ExpressionValueUpdatePayload::Panic {message,..} => Some(Self::Panic {message}),
+        }
+    }
+}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        // The origin is an expression id, meaningless for the user. It is resolved to the node's
+        // code by the graph view, where the nodes are known.
+        match self {
+            Self::Dataflow {..}   => write!(f, "Dataflow error."),
+            Self::Panic {message} => write!(f, "Panic: {}", message),
+        }
+    }
+}
+
+
+
 // =========================
 // === ComputedValueInfo ===
 // =========================
@@ -39,15 +91,21 @@ pub type ExpressionId = ast::Id;
 pub struct ComputedValueInfo {
     /// The string representing the typename of the computed value, e.g. "Number" or "Unit".
     pub typename:Option<ImString>,
+    /// A short textual representation of the computed value, e.g. "5" or "[1, 2, 3]".
+    pub short_value:Option<ImString>,
     /// If the expression is a method call (i.e. can be entered), this points to the target method.
     pub method_pointer:Option<Rc<MethodPointer>>,
+    /// The error which occurred when computing the expression, if any.
+    pub error:Option<ExpressionError>,
 }
 
 impl From<ExpressionValueUpdate> for ComputedValueInfo {
     fn from(update:ExpressionValueUpdate) -> Self {
         ComputedValueInfo {
             typename       : update.typename.map(ImString::new),
+            short_value    : update.short_value.map(ImString::new),
             method_pointer : update.method_call.map(Rc::new),
+            error          : ExpressionError::from_payload(update.payload),
         }
     }
 }
@@ -117,6 +175,30 @@ impl ComputedValueInfoRegistry {
 
 
 
+// =======================
+// === ExecutionStatus ===
+// =======================
+
+/// The status of the last execution of the execution context.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum ExecutionStatus {
+    /// The context has not been executed yet or its last execution did not fail.
+    Ok,
+    /// The last execution of the context failed, e.g. because its entry point does not exist.
+    Failed {
+        /// The message describing the failure, as received from the Language Server.
+        message:String
+    },
+}
+
+impl Default for ExecutionStatus {
+    fn default() -> Self {
+        Self::Ok
+    }
+}
+
+
+
 // ===============================
 // === VisualizationUpdateData ===
 // ===============================
@@ -135,6 +217,11 @@ impl VisualizationUpdateData {
     pub fn new(data:Vec<u8>) -> VisualizationUpdateData {
         VisualizationUpdateData(data)
     }
+
+    /// Takes the binary data out of the update, without copying it.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
 }
 
 impl AsRef<[u8]> for VisualizationUpdateData {
@@ -167,6 +254,100 @@ pub struct PopOnEmptyStack();
 #[fail(display="Tried to use incorrect visualization Id: {}.",_0)]
 pub struct InvalidVisualizationId(VisualizationId);
 
+/// Error when the entry point call expression does not call a method by its name.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The expression `{}` is not a method call.",_0)]
+pub struct NotAMethodCall(String);
+
+/// Error when the entry point call expression calls a method which is not an entry point.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="There is no entry point named `{}`.",_0)]
+pub struct NoSuchEntryPoint(String);
+
+
+
+// ==================
+// === EntryPoint ===
+// ==================
+
+/// The root call of the execution context: an explicit call of some method with the given
+/// argument expressions.
+#[derive(Clone,Debug,Eq,PartialEq,Deserialize,Serialize)]
+pub struct EntryPoint {
+    /// A pointer to the called method.
+    pub method                           : MethodPointer,
+    /// An expression evaluated to the `this` argument. If `None`, the module's atom is used.
+    pub this_argument_expression         : Option<String>,
+    /// Expressions evaluated to the positional arguments of the called method.
+    pub positional_arguments_expressions : Vec<String>,
+}
+
+impl EntryPoint {
+    /// Create an entry point calling the given method without any arguments.
+    pub fn new(method:MethodPointer) -> Self {
+        let this_argument_expression         = default();
+        let positional_arguments_expressions = default();
+        EntryPoint {method,this_argument_expression,positional_arguments_expressions}
+    }
+
+    /// Create an entry point calling the given method with the given positional arguments.
+    pub fn with_arguments
+    (method:MethodPointer, arguments:impl IntoIterator<Item:Into<String>>) -> Self {
+        let this_argument_expression         = default();
+        let positional_arguments_expressions = arguments.into_iter().map(Into::into).collect();
+        EntryPoint {method,this_argument_expression,positional_arguments_expressions}
+    }
+
+    /// Create an entry point from a call expression like `foo 1 (bar 2)`, which calls one of the
+    /// given methods by its name. Each argument of the call is a positional argument expression.
+    /// If the method is accessed on some expression, like in `Main.foo 1`, this expression is the
+    /// `this` argument expression.
+    pub fn from_call_expression
+    (parser:&Parser, expression:impl Str, methods:&[MethodPointer]) -> FallibleResult<Self> {
+        let expression  = expression.as_ref();
+        let ast         = parser.parse_line(expression)?;
+        let chain       = ast::prefix::Chain::new_non_strict(&ast);
+        let access      = ast::opr::to_specific_infix(&chain.func,ast::opr::predefined::ACCESS);
+        let (this,func) = match &access {
+            Some(access) => (Some(access.larg.repr()),&access.rarg),
+            None         => (None,&chain.func),
+        };
+        let name        = ast::identifier::name(func);
+        let name        = name.ok_or_else(|| NotAMethodCall(expression.to_owned()))?;
+        let method      = methods.iter().find(|method| method.name == *name);
+        let method      = method.ok_or_else(|| NoSuchEntryPoint(name.clone()))?;
+        let arguments   = chain.args.iter().map(|argument| argument.sast.wrapped.repr());
+        let mut entry_point = Self::with_arguments(method.clone(),arguments);
+        entry_point.this_argument_expression = this;
+        Ok(entry_point)
+    }
+
+    /// The call expression of this entry point, see `from_call_expression`.
+    pub fn call_expression(&self) -> String {
+        let function = match &self.this_argument_expression {
+            Some(this) => format!("{}{}{}",this,ast::opr::predefined::ACCESS,self.method.name),
+            None       => self.method.name.clone(),
+        };
+        let arguments = self.positional_arguments_expressions.iter().cloned();
+        std::iter::once(function).chain(arguments).join(" ")
+    }
+
+    /// The language server's stack item describing this entry point.
+    pub fn explicit_call(&self) -> language_server::ExplicitCall {
+        language_server::ExplicitCall {
+            method_pointer                   : self.method.clone(),
+            this_argument_expression         : self.this_argument_expression.clone(),
+            positional_arguments_expressions : self.positional_arguments_expressions.clone(),
+        }
+    }
+}
+
+impl From<MethodPointer> for EntryPoint {
+    fn from(method:MethodPointer) -> Self {
+        Self::new(method)
+    }
+}
+
 
 
 // =================
@@ -176,7 +357,7 @@ pub struct InvalidVisualizationId(VisualizationId);
 /// A specific function call occurring within another function's definition body.
 ///
 /// This is a single item in ExecutionContext stack.
-#[derive(Clone,Debug,Eq,PartialEq)]
+#[derive(Clone,Debug,Eq,PartialEq,Deserialize,Serialize)]
 pub struct LocalCall {
     /// An expression being a call to a method.
     pub call       : ExpressionId,
@@ -259,33 +440,44 @@ pub struct AttachedVisualization {
 ///
 /// It implements internal mutability pattern, so it may be shared between different
 /// controllers.
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct ExecutionContext {
     logger:Logger,
-    /// A name of definition which is a root call of this context.
-    pub entry_point:MethodPointer,
+    /// The root call of this context.
+    entry_point:RefCell<EntryPoint>,
     /// Local call stack.
     stack:RefCell<Vec<LocalCall>>,
     /// Set of active visualizations.
     visualizations: RefCell<HashMap<VisualizationId,AttachedVisualization>>,
     /// Storage for information about computed values (like their types).
     pub computed_value_info_registry: ComputedValueInfoRegistry,
+    /// The status of the last execution.
+    execution_status:RefCell<ExecutionStatus>,
+    /// A publisher emitting the execution status every time it changes.
+    #[derivative(Debug="ignore")]
+    execution_status_updates:Publisher<ExecutionStatus>,
 }
 
 impl ExecutionContext {
     /// Create new execution context
-    pub fn new(logger:impl Into<Logger>, entry_point:MethodPointer) -> Self {
+    pub fn new(logger:impl Into<Logger>, entry_point:impl Into<EntryPoint>) -> Self {
         let logger                       = logger.into();
+        let entry_point                  = RefCell::new(entry_point.into());
         let stack                        = default();
         let visualizations               = default();
         let computed_value_info_registry = default();
-        Self {logger,entry_point,stack,visualizations, computed_value_info_registry }
+        let execution_status             = default();
+        let execution_status_updates     = default();
+        Self {logger,entry_point,stack,visualizations,computed_value_info_registry,execution_status
+             ,execution_status_updates}
     }
 
     /// Push a new stack item to execution context.
     pub fn push(&self, stack_item:LocalCall) {
         self.stack.borrow_mut().push(stack_item);
         self.computed_value_info_registry.clear();
+        self.set_execution_status(ExecutionStatus::Ok);
     }
 
     /// Pop the last stack item from this context. It returns error when only root call
@@ -293,6 +485,7 @@ impl ExecutionContext {
     pub fn pop(&self) -> FallibleResult<LocalCall> {
         let ret = self.stack.borrow_mut().pop().ok_or_else(PopOnEmptyStack)?;
         self.computed_value_info_registry.clear();
+        self.set_execution_status(ExecutionStatus::Ok);
         Ok(ret)
     }
 
@@ -301,10 +494,25 @@ impl ExecutionContext {
         if let Some(top_frame) = self.stack.borrow().last() {
             top_frame.definition.clone()
         } else {
-            self.entry_point.clone()
+            self.entry_point.borrow().method.clone()
         }
     }
 
+    /// The root call of this context.
+    pub fn entry_point(&self) -> EntryPoint {
+        self.entry_point.borrow().clone()
+    }
+
+    /// Replace the root call of this context. As the whole stack is changed, all the local calls
+    /// are removed and the computed values are cleared.
+    pub fn set_entry_point(&self, entry_point:EntryPoint) {
+        info!(self.logger,"Setting entry point to {entry_point.method.name}.");
+        *self.entry_point.borrow_mut() = entry_point;
+        self.stack.borrow_mut().clear();
+        self.computed_value_info_registry.clear();
+        self.set_execution_status(ExecutionStatus::Ok);
+    }
+
     /// Attach a new visualization for current execution context.
     ///
     /// Returns a stream of visualization update data received from the server.
@@ -367,11 +575,99 @@ impl ExecutionContext {
     pub fn handle_expression_values_computed
     (&self, notification:ExpressionValuesComputed) -> FallibleResult<()> {
         self.computed_value_info_registry.apply_update(notification);
+        self.set_execution_status(ExecutionStatus::Ok);
         Ok(())
     }
 
+    /// Handles the notification about the execution of this context having failed.
+    pub fn handle_execution_failed(&self, message:String) {
+        warning!(self.logger,"Execution failed: {message}");
+        self.set_execution_status(ExecutionStatus::Failed {message});
+    }
+
+    /// The status of the last execution of this context.
+    pub fn execution_status(&self) -> ExecutionStatus {
+        self.execution_status.borrow().clone()
+    }
+
+    /// Subscribe to the changes of the execution status.
+    pub fn subscribe_execution_status(&self) -> Subscriber<ExecutionStatus> {
+        self.execution_status_updates.subscribe()
+    }
+
+    fn set_execution_status(&self, status:ExecutionStatus) {
+        if *self.execution_status.borrow() != status {
+            *self.execution_status.borrow_mut() = status.clone();
+            executor::global::spawn(self.execution_status_updates.publish(status));
+        }
+    }
+
     /// Returns IDs of all active visualizations.
     pub fn active_visualizations(&self) -> Vec<VisualizationId> {
         self.visualizations.borrow().keys().copied().collect_vec()
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn entry_point_from_call_expression() {
+        let parser  = Parser::new_or_panic();
+        let module  = "local.Project.Main";
+        let file    = language_server::Path::new(Uuid::new_v4(),&["src","Main.enso"]);
+        let method  = |name:&str| MethodPointer {
+            file            : file.clone(),
+            defined_on_type : module.to_owned(),
+            name            : name.to_owned(),
+        };
+        let methods = vec![method("main"),method("add")];
+
+        let entry_point = EntryPoint::from_call_expression(&parser,"main",&methods).unwrap();
+        assert_eq!(entry_point, EntryPoint::new(method("main")));
+
+        let expression  = "add 1 (Vector.new 2)";
+        let entry_point = EntryPoint::from_call_expression(&parser,expression,&methods).unwrap();
+        let expected    = EntryPoint::with_arguments(method("add"),vec!["1","(Vector.new 2)"]);
+        assert_eq!(entry_point, expected);
+        assert_eq!(entry_point.call_expression(), expression);
+
+        let expression   = "Main.add 1 2";
+        let entry_point  = EntryPoint::from_call_expression(&parser,expression,&methods).unwrap();
+        let mut expected = EntryPoint::with_arguments(method("add"),vec!["1","2"]);
+        expected.this_argument_expression = Some("Main".to_owned());
+        assert_eq!(entry_point, expected);
+        assert_eq!(entry_point.call_expression(), expression);
+
+        assert!(EntryPoint::from_call_expression(&parser,"sub 1",&methods).is_err());
+        assert!(EntryPoint::from_call_expression(&parser,"1 + 2",&methods).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn expression_error_from_payload() {
+        let origin  = ExpressionId::new_v4();
+        let trace   = vec![origin,ExpressionId::new_v4()];
+        let payload = ExpressionValueUpdatePayload::DataflowError {trace};
+        let error   = ExpressionError::from_payload(payload).unwrap();
+        assert_eq!(error, ExpressionError::Dataflow {origin:Some(origin)});
+        assert_eq!(error.to_string(), "Dataflow error.");
+
+        let payload = ExpressionValueUpdatePayload::DataflowError {trace:vec![]};
+        let error   = ExpressionError::from_payload(payload).unwrap();
+        assert_eq!(error, ExpressionError::Dataflow {origin:None});
+
+        let message = "Boom".to_string();
+        let payload = ExpressionValueUpdatePayload::Panic {message:message.clone(),trace:vec![]};
+        assert_eq!(ExpressionError::from_payload(payload), Some(ExpressionError::Panic {message}));
+        assert_eq!(ExpressionError::from_payload(ExpressionValueUpdatePayload::Value), None);
+    }
+}
@@ -5,6 +5,8 @@ use crate::constants::LANGUAGE_FILE_EXTENSION;
 use crate::constants::SOURCE_DIRECTORY;
 use crate::controller::FilePath;
 use crate::double_representation::definition::DefinitionInfo;
+use crate::model::execution_context::EntryPoint;
+use crate::model::execution_context::LocalCall;
 use crate::notification;
 
 use data::text::TextChange;
@@ -13,6 +15,7 @@ use enso_protocol::language_server::MethodPointer;
 use flo_stream::Subscriber;
 use parser::api::SourceFile;
 use parser::api::ParsedSourceFile;
+use parser::api::METADATA_TAG;
 use parser::Parser;
 use serde::Serialize;
 use serde::Deserialize;
@@ -59,6 +62,22 @@ pub enum ModulePathViolation {
 #[fail(display="No qualified name segments were provided.")]
 pub struct EmptyQualifiedName;
 
+/// Warning about module's metadata which could not be deserialized. The module was loaded with
+/// metadata recovered as much as possible.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The module's metadata are malformed and were recovered only partially: {}", _0)]
+pub struct MalformedMetadata(pub String);
+
+/// Failure for undo requested when there are no recorded edits.
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="There is no module edit to undo.")]
+pub struct NothingToUndo;
+
+/// Failure for redo requested when there are no undone edits.
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="There is no module edit to redo.")]
+pub struct NothingToRedo;
+
 
 
 // ============
@@ -226,6 +245,10 @@ pub enum Notification {
 // == Metadata ==
 // ==============
 
+/// The key of the metadata of other users, under which the metadata which could not be
+/// interpreted at all are kept (see `Metadata::from_raw_lossy`).
+pub const MALFORMED_METADATA_KEY:&str = "malformed";
+
 /// Mapping between ID and metadata.
 #[derive(Debug,Clone,Deserialize,Serialize)]
 pub struct Metadata {
@@ -251,18 +274,72 @@ impl Default for Metadata {
     }
 }
 
+impl Metadata {
+    /// Recover as much of metadata as possible from the raw JSON, which could not be deserialized.
+    ///
+    /// The metadata of other users are preserved. From the ide metadata only the entries of nodes
+    /// still present in the module's `ast` are kept, provided they can be deserialized. The project
+    /// metadata are kept if they can be deserialized. If the raw JSON is not an object at all, it
+    /// is kept under `MALFORMED_METADATA_KEY`, so it is not lost when the module is saved.
+    pub fn from_raw_lossy(raw:serde_json::Value, ast:&ast::known::Module) -> Self {
+        let mut rest  = match raw {
+            serde_json::Value::Object(fields) => fields,
+            other => std::iter::once((MALFORMED_METADATA_KEY.to_owned(),other)).collect(),
+        };
+        let ide       = rest.remove("ide");
+        let nodes     = ide.as_ref().and_then(|ide| ide.get("node")).and_then(|n| n.as_object());
+        let known_ids = ast.id_map().vec.into_iter().map(|(_,id)| id).collect::<HashSet<_>>();
+        let node      = nodes.iter().flat_map(|nodes| nodes.iter()).filter_map(|(id,data)| {
+            let id       = id.parse::<ast::Id>().ok()?;
+            let metadata = serde_json::from_value(data.clone()).ok()?;
+            known_ids.contains(&id).and_option(Some((id,metadata)))
+        }).collect();
+        let project = ide.as_ref().and_then(|ide| ide.get("project")).and_then(|project| {
+            serde_json::from_value(project.clone()).ok()
+        }).unwrap_or_default();
+        let ide  = IdeMetadata {node,project};
+        let rest = serde_json::Value::Object(rest);
+        Metadata {ide,rest}
+    }
+}
+
 /// Metadata that belongs to ide.
 #[derive(Debug,Clone,Default,Deserialize,Serialize)]
 pub struct IdeMetadata {
     /// Metadata that belongs to nodes.
-    node : HashMap<ast::Id,NodeMetadata>
+    node    : HashMap<ast::Id,NodeMetadata>,
+    /// Metadata of the project, stored in the module containing the project's entry point.
+    #[serde(default)]
+    project : ProjectMetadata,
+}
+
+/// Project-wide metadata, describing the state of the project's view.
+#[derive(Debug,Clone,Default,Deserialize,Serialize,PartialEq)]
+pub struct ProjectMetadata {
+    /// The execution contexts opened in the view, keyed by the names given by the view.
+    #[serde(default)]
+    pub execution_contexts : HashMap<String,StoredExecutionContext>,
+}
+
+/// The state of an execution context, which is restored when the project is opened again.
+#[derive(Debug,Clone,Deserialize,Serialize,PartialEq)]
+pub struct StoredExecutionContext {
+    /// The root call of the execution context.
+    pub entry_point : EntryPoint,
+    /// The stack of the local calls entered from the entry point.
+    pub call_stack  : Vec<LocalCall>,
 }
 
 /// Metadata of specific node.
-#[derive(Debug,Clone,Copy,Default,Serialize,Deserialize,Shrinkwrap)]
+#[derive(Debug,Clone,Default,Serialize,Deserialize,Shrinkwrap)]
 pub struct NodeMetadata {
     /// Position in x,y coordinates.
-    pub position: Option<Position>
+    #[shrinkwrap(main_field)]
+    pub position: Option<Position>,
+    /// The visualization chosen for the node. It is stored in the format defined by the view, the
+    /// model does not interpret it.
+    #[serde(default)]
+    pub visualization: Option<serde_json::Value>,
 }
 
 /// Used for storing node position.
@@ -282,22 +359,170 @@ impl Position {
 
 
 
-// ==============
-// === Module ===
-// ==============
+// ===============
+// === History ===
+// ===============
+
+/// The maximum number of transactions which can be undone.
+const MAX_UNDO_HISTORY : usize = 100;
 
 /// A type describing content of the module: the ast and metadata.
 pub type Content = ParsedSourceFile<Metadata>;
 
+/// Deserialize the module's content from its file's text.
+///
+/// Malformed metadata does not prevent loading the module: the metadata are recovered as much as
+/// possible (see `Metadata::from_raw_lossy`), or replaced with default if they are not a valid
+/// JSON. In such case the warning describing the problem is returned along with the content. Only
+/// the errors in module's code make this function fail.
+pub fn deserialize_content
+(parser:&Parser, text:String) -> FallibleResult<(Content,Option<MalformedMetadata>)> {
+    let error = match parser.parse_with_metadata(text.clone()) {
+        Ok(content) => return Ok((content,None)),
+        Err(error)  => error,
+    };
+    let warning = MalformedMetadata(error.to_string());
+    let content = match parser.parse_with_metadata::<serde_json::Value>(text.clone()) {
+        Ok(raw) => {
+            let metadata = Metadata::from_raw_lossy(raw.metadata,&raw.ast);
+            ParsedSourceFile {ast:raw.ast,metadata}
+        }
+        // The sections are broken so badly, that the parser cannot split them.
+        Err(_) => {
+            let (code,footer)   = text.split_at(text.find(METADATA_TAG).unwrap_or(text.len()));
+            let mut footer      = footer.trim_start_matches(METADATA_TAG).lines();
+            let id_map          = footer.next().and_then(|line| serde_json::from_str(line).ok());
+            let metadata_text   = footer.collect_vec().join("\n");
+            let ast             = parser.parse_module(code,id_map.unwrap_or_default())?;
+            let metadata        = if metadata_text.is_empty() { default() } else {
+                // The text which is not a valid JSON is kept as a JSON string.
+                let raw = serde_json::from_str(&metadata_text);
+                let raw = raw.unwrap_or_else(|_| serde_json::Value::String(metadata_text));
+                Metadata::from_raw_lossy(raw,&ast)
+            };
+            ParsedSourceFile {ast,metadata}
+        }
+    };
+    Ok((content,Some(warning)))
+}
+
+/// The transaction which is currently open.
+#[derive(Clone,Debug)]
+struct OpenTransaction {
+    id : usize,
+    /// If the module content from before the transaction was already put on undo stack.
+    recorded : bool,
+    /// The guard shared by all the `Transaction` handles of this transaction.
+    guard : Weak<TransactionGuard>,
+}
+
+/// The undo-redo history of module. Both stacks contain the module contents from before the
+/// transactions, with the most recent ones at the end.
+#[derive(Debug,Default)]
+struct History {
+    undo             : Vec<Content>,
+    redo             : Vec<Content>,
+    open_transaction : Option<OpenTransaction>,
+    next_id          : usize,
+}
+
+impl History {
+    /// Record the module content from before some edit. If the edit is a part of a transaction
+    /// which has been already recorded, nothing is stored.
+    fn record(&mut self, content:Content) {
+        let already_recorded = self.open_transaction.contains_if(|t| t.recorded);
+        if !already_recorded {
+            if let Some(transaction) = &mut self.open_transaction {
+                transaction.recorded = true;
+            }
+            self.undo.push(content);
+            if self.undo.len() > MAX_UNDO_HISTORY {
+                self.undo.remove(0);
+            }
+            self.redo.clear();
+        }
+    }
+
+    /// Take the content to be restored by undo, putting the current content on redo stack.
+    fn undo(&mut self, current:Content) -> Option<Content> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.close_recorded_transaction();
+        Some(previous)
+    }
+
+    /// Take the content to be restored by redo, putting the current content on undo stack.
+    fn redo(&mut self, current:Content) -> Option<Content> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.close_recorded_transaction();
+        Some(next)
+    }
+
+    /// Forget all the recorded contents, e.g. when they no longer describe the module's file.
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.close_recorded_transaction();
+    }
+
+    /// The edits done after undo or redo must not be merged with the transaction recorded before,
+    /// so it will be recorded again.
+    fn close_recorded_transaction(&mut self) {
+        if let Some(transaction) = &mut self.open_transaction {
+            transaction.recorded = false;
+        }
+    }
+}
+
+/// A handle of module's transaction: all the module edits done when the transaction is open are
+/// undone and redone together. The transaction is closed when all its handles are dropped.
+///
+/// If the transaction was already open when the handle was created, the handle joins it, keeping
+/// it open as long as the handle is alive.
+#[derive(Clone,Debug)]
+pub struct Transaction {
+    guard : Rc<TransactionGuard>,
+}
+
+/// Closes the transaction on drop.
+#[derive(Debug)]
+struct TransactionGuard {
+    history : Weak<RefCell<History>>,
+    id      : usize,
+}
+
+impl Drop for TransactionGuard {
+    fn drop(&mut self) {
+        if let Some(history) = self.history.upgrade() {
+            let mut history = history.borrow_mut();
+            if history.open_transaction.contains_if(|t| t.id == self.id) {
+                history.open_transaction = None;
+            }
+        }
+    }
+}
+
+
+
+// ==============
+// === Module ===
+// ==============
+
 /// A structure describing the module.
 ///
 /// It implements internal mutability pattern, so the state may be shared between different
 /// controllers. Each change in module will emit notification for each module representation
 /// (text and graph).
+///
+/// All the changes are recorded in the module's history, so they can be undone and redone. The
+/// changes may be grouped using transactions (see `open_transaction`).
 #[derive(Debug)]
 pub struct Module {
-    content       : RefCell<Content>,
-    notifications : notification::Publisher<Notification>,
+    content          : RefCell<Content>,
+    history          : Rc<RefCell<History>>,
+    metadata_warning : RefCell<Option<MalformedMetadata>>,
+    notifications    : notification::Publisher<Notification>,
 }
 
 impl Default for Module {
@@ -311,8 +536,10 @@ impl Module {
     /// Create state with given content.
     pub fn new(ast:ast::known::Module, metadata:Metadata) -> Self {
         Module {
-            content       : RefCell::new(ParsedSourceFile{ast,metadata}),
-            notifications : default(),
+            content          : RefCell::new(ParsedSourceFile{ast,metadata}),
+            history          : default(),
+            metadata_warning : default(),
+            notifications    : default(),
         }
     }
 
@@ -352,6 +579,17 @@ impl Module {
         double_representation::module::get_definition(&ast, id)
     }
 
+    /// Returns the warning about the module's metadata, which were malformed when the module's
+    /// content was loaded.
+    pub fn metadata_warning(&self) -> Option<MalformedMetadata> {
+        self.metadata_warning.borrow().clone()
+    }
+
+    /// Returns the project metadata stored in this module.
+    pub fn project_metadata(&self) -> ProjectMetadata {
+        self.content.borrow().metadata.ide.project.clone()
+    }
+
     /// Returns metadata for given node, if present.
     pub fn node_metadata(&self, id:ast::Id) -> FallibleResult<NodeMetadata> {
         let data = self.content.borrow().metadata.ide.node.get(&id).cloned();
@@ -364,14 +602,19 @@ impl Module {
 
 impl Module {
 
-    /// Update whole content of the module.
+    /// Update whole content of the module, e.g. after the module's file was changed externally.
+    ///
+    /// This change cannot be undone, and it clears the module's history, because the recorded
+    /// contents describe the file from before the change.
     pub fn update_whole(&self, content:Content) {
+        self.history.borrow_mut().clear();
         *self.content.borrow_mut() = content;
         self.notify(Notification::Invalidate);
     }
 
     /// Update ast in module controller.
     pub fn update_ast(&self, ast:ast::known::Module) {
+        self.record_history();
         self.content.borrow_mut().ast  = ast;
         self.notify(Notification::Invalidate);
     }
@@ -386,6 +629,7 @@ impl Module {
         let replaced_location = TextLocation::convert_range(&code,&change.replaced);
         change.apply(&mut code);
         let new_ast = parser.parse(code,new_id_map)?.try_into()?;
+        self.record_history();
         self.content.borrow_mut().ast = new_ast;
         self.notify(Notification::CodeChanged {change,replaced_location});
         Ok(())
@@ -393,14 +637,17 @@ impl Module {
 
     /// Sets metadata for given node.
     pub fn set_node_metadata(&self, id:ast::Id, data:NodeMetadata) {
+        self.record_history();
         self.content.borrow_mut().metadata.ide.node.insert(id, data);
         self.notify(Notification::MetadataChanged);
     }
 
     /// Removes metadata of given node and returns them.
     pub fn remove_node_metadata(&self, id:ast::Id) -> FallibleResult<NodeMetadata> {
+        let before = self.content.borrow().clone();
         let lookup = self.content.borrow_mut().metadata.ide.node.remove(&id);
         let data   = lookup.ok_or_else(|| NodeMetadataNotFound(id))?;
+        self.history.borrow_mut().record(before);
         self.notify(Notification::MetadataChanged);
         Ok(data)
     }
@@ -411,6 +658,7 @@ impl Module {
     /// should use only the data passed as argument; don't use functions of this controller for
     /// getting and setting metadata for the same node.
     pub fn with_node_metadata(&self, id:ast::Id, fun:impl FnOnce(&mut NodeMetadata)) {
+        self.record_history();
         let lookup   = self.content.borrow_mut().metadata.ide.node.remove(&id);
         let mut data = lookup.unwrap_or_default();
         fun(&mut data);
@@ -418,6 +666,75 @@ impl Module {
         self.notify(Notification::MetadataChanged);
     }
 
+    /// Modify the project metadata stored in this module.
+    ///
+    /// The project metadata describe the state of the view rather than the module's content, so
+    /// this change is not recorded in the module's history.
+    pub fn with_project_metadata(&self, fun:impl FnOnce(&mut ProjectMetadata)) {
+        fun(&mut self.content.borrow_mut().metadata.ide.project);
+        self.notify(Notification::MetadataChanged);
+    }
+
+    /// Set the warning about the module's metadata, which were malformed when the module's content
+    /// was loaded (see `deserialize_content`). `None` means the metadata were loaded properly.
+    ///
+    /// No notification is emitted, so the warning should be set before updating the module with
+    /// the loaded content (see `update_whole`).
+    pub fn set_metadata_warning(&self, warning:Option<MalformedMetadata>) {
+        *self.metadata_warning.borrow_mut() = warning;
+    }
+
+    /// Open a new transaction. All the edits done until all the handles of the transaction are
+    /// dropped will be undone and redone together. If some transaction is already open, the
+    /// returned handle joins it instead.
+    pub fn open_transaction(&self) -> Transaction {
+        let mut history = self.history.borrow_mut();
+        let open_guard  = history.open_transaction.as_ref().and_then(|t| t.guard.upgrade());
+        let guard       = open_guard.unwrap_or_else(|| {
+            let id           = history.next_id;
+            let weak_history = Rc::downgrade(&self.history);
+            let guard        = Rc::new(TransactionGuard {history:weak_history,id});
+            let transaction  = OpenTransaction {id,recorded:false,guard:Rc::downgrade(&guard)};
+            history.next_id         += 1;
+            history.open_transaction = Some(transaction);
+            guard
+        });
+        Transaction {guard}
+    }
+
+    /// Restore the module content from before the last recorded transaction. The project
+    /// metadata are not a part of the history, so the current ones are kept.
+    pub fn undo(&self) -> FallibleResult<()> {
+        let current  = self.content.borrow().clone();
+        let previous = self.history.borrow_mut().undo(current).ok_or(NothingToUndo)?;
+        self.restore_from_history(previous);
+        Ok(())
+    }
+
+    /// Restore the module content from before the last undo. The project metadata are not a part
+    /// of the history, so the current ones are kept.
+    pub fn redo(&self) -> FallibleResult<()> {
+        let current = self.content.borrow().clone();
+        let next    = self.history.borrow_mut().redo(current).ok_or(NothingToRedo)?;
+        self.restore_from_history(next);
+        Ok(())
+    }
+
+    fn restore_from_history(&self, mut content:Content) {
+        let mut current = self.content.borrow_mut();
+        let project     = std::mem::take(&mut current.metadata.ide.project);
+        content.metadata.ide.project = project;
+        *current = content;
+        drop(current);
+        self.notify(Notification::Invalidate);
+    }
+
+    /// Put the current content to the undo history. Should be called before each edit.
+    fn record_history(&self) {
+        let content = self.content.borrow().clone();
+        self.history.borrow_mut().record(content);
+    }
+
     fn notify(&self, notification:Notification) {
         let notify  = self.notifications.publish(notification);
         executor::global::spawn(notify);
@@ -480,7 +797,8 @@ mod test {
 
             // Metadata update
             let id            = Uuid::new_v4();
-            let node_metadata = NodeMetadata {position:Some(Position::new(1.0, 2.0))};
+            let position      = Some(Position::new(1.0, 2.0));
+            let node_metadata = NodeMetadata {position,..default()};
             module.set_node_metadata(id.clone(),node_metadata.clone());
             assert_eq!(Some(Notification::MetadataChanged), subscription.next().await);
             module.remove_node_metadata(id.clone()).unwrap();
@@ -510,7 +828,8 @@ mod test {
             let initial_md = module.node_metadata(id.clone());
             assert!(initial_md.is_err());
 
-            let md_to_set = NodeMetadata {position:Some(Position::new(1.0, 2.0))};
+            let position  = Some(Position::new(1.0, 2.0));
+            let md_to_set = NodeMetadata {position,..default()};
             module.set_node_metadata(id.clone(),md_to_set.clone());
             assert_eq!(md_to_set.position, module.node_metadata(id.clone()).unwrap().position);
 
@@ -523,6 +842,180 @@ mod test {
         });
     }
 
+    #[wasm_bindgen_test]
+    fn handling_project_metadata() {
+        let parser = Parser::new_or_panic();
+        let module = Module::from_code_or_panic("main = 1",default(),default());
+        assert_eq!(module.project_metadata(), ProjectMetadata::default());
+
+        let method  = Path::from_mock_module_name("Test").method_pointer("foo");
+        let call    = LocalCall {call:Uuid::new_v4(), definition:method.clone()};
+        let context = StoredExecutionContext {
+            entry_point : EntryPoint::new(method),
+            call_stack  : vec![call],
+        };
+        module.with_project_metadata(|md| {
+            md.execution_contexts.insert("main".to_string(),context.clone());
+        });
+        assert_eq!(module.project_metadata().execution_contexts["main"], context);
+        // Changing the project metadata is not an edit which could be undone.
+        assert!(module.undo().is_err());
+
+        // Undoing and redoing the edits keeps the current project metadata.
+        let edit_position = NodeMetadata {position:Some(Position::new(1.0,2.0)),..default()};
+        module.set_node_metadata(Uuid::new_v4(),edit_position);
+        module.with_project_metadata(|md| md.execution_contexts.clear());
+        module.undo().unwrap();
+        assert!(module.project_metadata().execution_contexts.is_empty());
+        module.with_project_metadata(|md| {
+            md.execution_contexts.insert("main".to_string(),context.clone());
+        });
+        module.redo().unwrap();
+        assert_eq!(module.project_metadata().execution_contexts["main"], context);
+
+        let content    = module.serialized_content().unwrap();
+        let (loaded,w) = deserialize_content(&parser,content.content).unwrap();
+        assert!(w.is_none());
+        assert_eq!(loaded.metadata.ide.project.execution_contexts["main"], context);
+    }
+
+    #[wasm_bindgen_test]
+    fn deserializing_malformed_metadata() {
+        let parser  = Parser::new_or_panic();
+        let module  = Module::from_code_or_panic("main = 1\nfoo = 2",default(),default());
+        let ids     = module.ast().id_map().vec;
+        let node_id = ids.first().unwrap().1;
+        let lost_id = Uuid::new_v4();
+        let position = |x,y| NodeMetadata {position:Some(Position::new(x,y)),..default()};
+        module.set_node_metadata(node_id,position(1.0,2.0));
+        module.set_node_metadata(lost_id,position(3.0,4.0));
+        let content = module.serialized_content().unwrap();
+        let id_map  = content.id_map_slice();
+        let code    = content.code_slice();
+
+        // Metadata not matching the expected structure.
+        let mut metadata = serde_json::from_str::<serde_json::Value>(content.metadata_slice());
+        let metadata     = metadata.as_mut().unwrap();
+        let broken_id    = ids.last().unwrap().1.to_string();
+        metadata["ide"]["node"][broken_id] = serde_json::json!({"position":"nowhere"});
+        metadata["other"]                  = serde_json::json!({"x":1});
+        let text       = iformat!("{code}{METADATA_TAG}{id_map}\n{metadata}");
+        let (loaded,w) = deserialize_content(&parser,text).unwrap();
+        assert!(w.is_some());
+        assert_eq!(loaded.ast.repr(), "main = 1\nfoo = 2");
+        assert_eq!(loaded.metadata.ide.node.len(), 1);
+        assert_eq!(loaded.metadata.ide.node[&node_id].position, Some(Position::new(1.0,2.0)));
+        assert_eq!(loaded.metadata.rest["other"]["x"], 1);
+
+        // Metadata which are not a valid JSON, e.g. after unresolved merge conflict.
+        let metadata   = "<<<<<<< HEAD\n{}\n=======\n{}\n>>>>>>> branch";
+        let text       = iformat!("{code}{METADATA_TAG}{id_map}\n{metadata}");
+        let (loaded,w) = deserialize_content(&parser,text).unwrap();
+        assert!(w.is_some());
+        assert_eq!(loaded.ast.repr(), "main = 1\nfoo = 2");
+        assert_eq!(loaded.ast.id_map(), module.ast().id_map());
+        assert!(loaded.metadata.ide.node.is_empty());
+        assert_eq!(loaded.metadata.rest[MALFORMED_METADATA_KEY], metadata);
+
+        // Proper metadata.
+        let (loaded,w) = deserialize_content(&parser,content.content).unwrap();
+        assert!(w.is_none());
+        assert_eq!(loaded.metadata.ide.node.len(), 2);
+    }
+
+    #[test]
+    fn undoing_and_redoing() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async {
+            let parser = Parser::new_or_panic();
+            let module = Module::from_code_or_panic("2 + 2",default(),default());
+            let id     = Uuid::new_v4();
+            let change = |inserted:&str| TextChange {
+                replaced: text::Index::new(0)..text::Index::new(1),
+                inserted: inserted.to_string(),
+            };
+            let position = || module.node_metadata(id).ok().and_then(|md| md.position);
+            assert!(module.undo().is_err());
+
+            module.apply_code_change(change("3"),&parser,default()).unwrap();
+            {
+                let _transaction = module.open_transaction();
+                let _joined      = module.open_transaction();
+                module.apply_code_change(change("4"),&parser,default()).unwrap();
+                let metadata = NodeMetadata {position:Some(Position::new(1.0,2.0)),..default()};
+                module.set_node_metadata(id,metadata);
+            }
+            assert_eq!(module.ast().repr(), "4 + 2");
+            assert_eq!(position(), Some(Position::new(1.0,2.0)));
+
+            module.undo().unwrap();
+            assert_eq!(module.ast().repr(), "3 + 2");
+            assert_eq!(position(), None);
+            module.undo().unwrap();
+            assert_eq!(module.ast().repr(), "2 + 2");
+            assert!(module.undo().is_err());
+
+            module.redo().unwrap();
+            assert_eq!(module.ast().repr(), "3 + 2");
+            module.redo().unwrap();
+            assert_eq!(module.ast().repr(), "4 + 2");
+            assert_eq!(position(), Some(Position::new(1.0,2.0)));
+            assert!(module.redo().is_err());
+
+            // A new edit discards the undone changes.
+            module.undo().unwrap();
+            module.apply_code_change(change("5"),&parser,default()).unwrap();
+            assert!(module.redo().is_err());
+            module.undo().unwrap();
+            assert_eq!(module.ast().repr(), "3 + 2");
+        });
+    }
+
+    #[test]
+    fn keeping_transaction_open_while_any_handle_is_alive() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async {
+            let parser = Parser::new_or_panic();
+            let module = Module::from_code_or_panic("2 + 2",default(),default());
+            let change = |inserted:&str| TextChange {
+                replaced: text::Index::new(0)..text::Index::new(1),
+                inserted: inserted.to_string(),
+            };
+
+            let transaction = module.open_transaction();
+            let joined      = module.open_transaction();
+            module.apply_code_change(change("3"),&parser,default()).unwrap();
+            drop(transaction);
+            module.apply_code_change(change("4"),&parser,default()).unwrap();
+            drop(joined);
+            module.apply_code_change(change("5"),&parser,default()).unwrap();
+
+            module.undo().unwrap();
+            assert_eq!(module.ast().repr(), "4 + 2");
+            module.undo().unwrap();
+            assert_eq!(module.ast().repr(), "2 + 2");
+        });
+    }
+
+    #[test]
+    fn updating_whole_content_clears_history() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async {
+            let parser = Parser::new_or_panic();
+            let module = Module::from_code_or_panic("2 + 2",default(),default());
+            let change = TextChange {
+                replaced: text::Index::new(0)..text::Index::new(1),
+                inserted: "3".to_string(),
+            };
+            module.apply_code_change(change,&parser,default()).unwrap();
+
+            let reloaded = parser.parse_module("4 + 2",default()).unwrap();
+            module.update_whole(ParsedSourceFile {ast:reloaded,metadata:default()});
+            assert!(module.undo().is_err());
+            assert_eq!(module.ast().repr(), "4 + 2");
+        });
+    }
+
     #[test]
     fn module_path_conversion() {
         let path = FilePath::new(default(), &["src","Main.enso"]);
@@ -5,7 +5,10 @@
 
 use crate::prelude::*;
 
+use crate::constants::SOURCE_DIRECTORY;
 use crate::controller::Visualization;
+use crate::double_representation::definition::DefinitionProvider;
+use crate::model::execution_context::EntryPoint;
 use crate::model::execution_context::VisualizationUpdateData;
 use crate::model::module::QualifiedName as ModuleQualifiedName;
 use crate::model::module::Path          as ModulePath;
@@ -18,7 +21,10 @@ use enso_protocol::language_server;
 use enso_protocol::language_server::CapabilityRegistration;
 use enso_protocol::project_manager;
 use enso_protocol::project_manager::ProjectName;
+use ensogl_system_web::sleep;
+use futures::future::LocalBoxFuture;
 use parser::Parser;
+use std::time::Duration;
 use uuid::Uuid;
 
 
@@ -86,11 +92,317 @@ impl ExecutionContextsRegistry {
         })
     }
 
+    /// Handles the notification about the execution of the context having failed.
+    pub fn handle_execution_failed
+    (&self, notification:language_server::ExecutionFailed) -> FallibleResult<()> {
+        self.with_context(notification.context_id, |ctx| {
+            ctx.handle_execution_failed(notification);
+            Ok(())
+        })
+    }
+
     /// Registers a new ExecutionContext. It will be eligible for receiving future updates routed
     /// through `dispatch_visualization_update`.
     pub fn insert(&self, context:Rc<ExecutionContext>) {
         self.0.borrow_mut().insert(context.id(),context);
     }
+
+    /// Restore all the registered execution contexts on the Language Server, e.g. after
+    /// reconnecting. The contexts are re-registered under their new ids.
+    ///
+    /// All the contexts are restored, even if some of them fail; the first error is returned.
+    pub async fn restore_all(&self) -> FallibleResult<()> {
+        let contexts   = self.0.borrow().values().collect_vec();
+        let mut result = Ok(());
+        self.0.borrow_mut().clear();
+        for context in contexts {
+            let restored = context.restore().await;
+            self.insert(context);
+            result = result.and(restored);
+        }
+        result
+    }
+}
+
+
+
+// ====================
+// === Reconnection ===
+// ====================
+
+/// The delays between the consecutive attempts of reconnecting to the Language Server. The last
+/// delay is repeated until the connection is restored.
+const RECONNECTION_DELAYS:&[Duration] = &[
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+];
+
+/// The clients of a newly established connection with the Language Server. The clients' runners
+/// are expected to be already scheduled.
+#[allow(missing_docs)]
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Clients {
+    #[derivative(Debug="ignore")]
+    pub json   : Box<dyn language_server::API>,
+    #[derivative(Debug="ignore")]
+    pub binary : Box<dyn binary::API>,
+}
+
+/// A function establishing a new connection with the Language Server of the project.
+pub type Connector = Rc<dyn Fn() -> LocalBoxFuture<'static,FallibleResult<Clients>>>;
+
+/// Error raised when the connection was lost, but the project has no connector set.
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="No connector was set for reconnecting to the Language Server.")]
+pub struct NoConnector;
+
+/// The state of reconnecting to the Language Server.
+#[derive(Derivative,Default)]
+#[derivative(Debug)]
+struct Reconnection {
+    #[derivative(Debug="ignore")]
+    connector   : RefCell<Option<Connector>>,
+    in_progress : Cell<bool>,
+}
+
+/// The weak handles to the project's parts involved in handling the Language Server events and
+/// in restoring the session after the connection is lost.
+#[derive(Clone,Debug)]
+struct Session {
+    logger              : Logger,
+    language_server_rpc : Weak<language_server::Connection>,
+    language_server_bin : Weak<binary::Connection>,
+    module_registry     : Weak<model::registry::Registry<ModulePath,model::synchronized::Module>>,
+    execution_contexts  : Weak<ExecutionContextsRegistry>,
+    suggestion_db       : Weak<SuggestionDatabase>,
+    visualizations      : controller::visualization::FileWatcher,
+    reconnection        : Weak<Reconnection>,
+}
+
+impl Session {
+    /// See `Project::binary_event_handler`.
+    fn binary_event_handler
+    (&self) -> impl Fn(enso_protocol::binary::Event) -> futures::future::Ready<()> {
+        let session = self.clone();
+        let logger  = self.logger.clone_ref();
+        move |event| {
+            debug!(logger, "Received an event from the binary protocol: {event:?}");
+            use enso_protocol::binary::client::Event;
+            use enso_protocol::binary::Notification;
+            match event {
+                Event::Notification(Notification::VisualizationUpdate {context,data}) => {
+                    let data = VisualizationUpdateData::new(data);
+                    if let Some(execution_contexts) = session.execution_contexts.upgrade() {
+                        let result = execution_contexts.dispatch_visualization_update(context,data);
+                        if let Err(error) = result {
+                            error!(logger,"Failed to handle the visualization update: {error}.");
+                        }
+                    } else {
+                        error!(logger,"Received a visualization update despite project being \
+                        already dropped.");
+                    }
+                }
+                Event::Closed => {
+                    error!(logger,"Lost binary connection with the Language Server!");
+                    session.reconnect();
+                }
+                Event::Error(error) => {
+                    error!(logger,"Error emitted by the binary data connection: {error}.");
+                }
+            }
+            futures::future::ready(())
+        }
+    }
+
+    /// See `Project::json_event_handler`.
+    fn json_event_handler
+    (&self) -> impl Fn(enso_protocol::language_server::Event) -> futures::future::Ready<()> {
+    // TODO [mwu]
+    //  This handler for JSON-RPC notifications is very similar to the function above that handles
+    //  binary protocol notifications. However, it is not practical to generalize them, as the
+    //  underlying RPC handlers and their types are separate.
+    //  This generalization should be reconsidered once the old JSON-RPC handler is phased out.
+    //  See: https://github.com/luna/ide/issues/587
+        let session = self.clone();
+        let logger  = self.logger.clone_ref();
+        move |event| {
+            debug!(logger, "Received an event from the json-rpc protocol: {event:?}");
+            use enso_protocol::language_server::Event;
+            use enso_protocol::language_server::Notification;
+            match event {
+                Event::Notification(Notification::ExpressionValuesComputed(update)) => {
+                    if let Some(execution_contexts) = session.execution_contexts.upgrade() {
+                        let result = execution_contexts.handle_expression_values_computed(update);
+                        if let Err(error) = result {
+                            error!(logger,"Failed to handle the expression values computed update: \
+                            {error}.");
+                        }
+                    } else {
+                        error!(logger,"Received a `ExpressionValuesComputed` update despite \
+                        execution context being already dropped.");
+                    }
+                }
+                Event::Notification(Notification::ExecutionFailed(update)) => {
+                    if let Some(execution_contexts) = session.execution_contexts.upgrade() {
+                        let result = execution_contexts.handle_execution_failed(update);
+                        if let Err(error) = result {
+                            error!(logger,"Failed to handle the execution failure: {error}.");
+                        }
+                    } else {
+                        error!(logger,"Received a `ExecutionFailed` update despite execution \
+                        context being already dropped.");
+                    }
+                }
+                Event::Notification(Notification::FileEvent(event)) => {
+                    session.visualizations.handle_file_event(&event);
+                    // Only the opened modules are interested in the changes of their files.
+                    let path     = ModulePath::from_file_path(event.path).ok();
+                    let registry = session.module_registry.upgrade();
+                    if let (Some(path),Some(registry)) = (path,registry) {
+                        if let Some(module) = registry.get_loaded(&path) {
+                            module.handle_file_event(event.kind);
+                        }
+                    }
+                }
+                Event::Notification(Notification::SuggestionDatabaseUpdate(update)) => {
+                    if let Some(suggestion_db) = session.suggestion_db.upgrade() {
+                        if let Err(error) = suggestion_db.apply_update_event(update) {
+                            warning!(logger,"{error} Resynchronizing the suggestion database.");
+                            let logger          = logger.clone_ref();
+                            let language_server = session.language_server_rpc.upgrade();
+                            executor::global::spawn(async move {
+                                if let Some(language_server) = language_server {
+                                    let result = suggestion_db.resynchronize(&language_server);
+                                    if let Err(error) = result.await {
+                                        error!(logger,"Failed to resynchronize the suggestion \
+                                        database: {error}");
+                                    }
+                                }
+                            });
+                        }
+                    }
+                }
+                Event::Closed => {
+                    error!(logger,"Lost JSON-RPC connection with the Language Server!");
+                    session.reconnect();
+                }
+                Event::Error(error) => {
+                    error!(logger,"Error emitted by the binary data connection: {error}.");
+                }
+                _ => {}
+            }
+            futures::future::ready(())
+        }
+    }
+
+    /// Schedule reconnecting to the Language Server, unless it is already in progress. The
+    /// attempts are repeated with increasing delays until the session is restored or the project
+    /// is dropped.
+    fn reconnect(&self) {
+        let reconnection = match self.reconnection.upgrade() {
+            Some(reconnection) => reconnection,
+            None               => return,
+        };
+        if reconnection.in_progress.replace(true) {
+            return
+        }
+        // The task holds only the weak handle, so dropping the project stops the attempts.
+        let session = self.clone();
+        executor::global::spawn(async move {
+            let last_delay = RECONNECTION_DELAYS.last().into_iter().cycle();
+            let delays     = RECONNECTION_DELAYS.iter().chain(last_delay);
+            for delay in delays {
+                sleep(*delay).await;
+                if session.reconnection.upgrade().is_none() {
+                    break
+                }
+                info!(session.logger,"Reconnecting to the Language Server.");
+                match session.restore().await {
+                    Ok(()) => {
+                        info!(session.logger,"Restored the session with the Language Server.");
+                        break
+                    }
+                    Err(error) => {
+                        warning!(session.logger,"Failed to reconnect to the Language Server: \
+                        {error}");
+                    }
+                }
+            }
+            if let Some(reconnection) = session.reconnection.upgrade() {
+                reconnection.in_progress.set(false);
+            }
+        });
+    }
+
+    /// Acquire the file updates of the project's visualization directory. Failing to do so is not
+    /// fatal, the visualizations will just not be reloaded after being edited.
+    async fn acquire_visualization_updates(&self, language_server:&language_server::Connection) {
+        let result = controller::visualization::acquire_file_updates(language_server).await;
+        if let Err(error) = result {
+            warning!(self.logger,"Failed to watch the visualization directory: {error}");
+        }
+    }
+
+    /// Establish a new connection with the Language Server and restore the session: the
+    /// capabilities, the opened modules and the execution contexts.
+    async fn restore(&self) -> FallibleResult<()> {
+        let rpc = self.language_server_rpc.upgrade();
+        let bin = self.language_server_bin.upgrade();
+        let (rpc,bin) = match (rpc,bin) {
+            (Some(rpc),Some(bin)) => (rpc,bin),
+            _                     => return Ok(()),
+        };
+        let reconnection = self.reconnection.upgrade();
+        let connector    = reconnection.and_then(|r| r.connector.borrow().clone());
+        let clients      = (connector.ok_or(NoConnector)?)().await?;
+        rpc.reconnect(clients.json).await?;
+        bin.reconnect(clients.binary).await?;
+        executor::global::spawn(rpc.events().for_each(self.json_event_handler()));
+        executor::global::spawn(bin.event_stream().for_each(self.binary_event_handler()));
+        acquire_capabilities(&self.logger,&rpc).await?;
+        self.acquire_visualization_updates(&rpc).await;
+        if let Some(suggestion_db) = self.suggestion_db.upgrade() {
+            suggestion_db.resynchronize(&rpc).await?;
+        }
+        if let Some(module_registry) = self.module_registry.upgrade() {
+            for module in module_registry.loaded() {
+                module.handle_reconnection();
+            }
+        }
+        if let Some(execution_contexts) = self.execution_contexts.upgrade() {
+            execution_contexts.restore_all().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Acquire the suggestion database and file tree updates capabilities. Both requests are sent in
+/// a single batch. Each failure is logged, and the first one is returned.
+async fn acquire_capabilities
+(logger:&Logger, language_server:&language_server::Connection) -> json_rpc::Result<()> {
+    let content_root = language_server.content_root();
+    let sources      = language_server::Path::new(content_root,&[SOURCE_DIRECTORY]);
+    let suggestions  = CapabilityRegistration::create_receives_suggestions_database_updates();
+    let file_tree    = CapabilityRegistration::create_receives_tree_updates(sources);
+    let capabilities = vec![suggestions,file_tree];
+    let results      = language_server.batch_all(|client| {
+        capabilities.iter().map(|capability| {
+            client.acquire_capability(&capability.method,&capability.register_options)
+        }).collect()
+    }).await;
+    let mut first_error = None;
+    for (capability,result) in capabilities.iter().zip(results) {
+        if let Err(error) = result {
+            warning!(logger,"Failed to acquire capability {capability.method}: {error}");
+            first_error.get_or_insert(error);
+        }
+    }
+    first_error.map_or(Ok(()),Err)
 }
 
 
@@ -140,6 +452,7 @@ pub struct Project {
     pub suggestion_db       : Rc<SuggestionDatabase>,
     pub parser              : Parser,
     pub logger              : Logger,
+    reconnection            : Rc<Reconnection>,
 }
 
 impl Project {
@@ -158,7 +471,9 @@ impl Project {
         let binary_protocol_events  = language_server_bin.event_stream();
         let embedded_visualizations = default();
         let language_server         = language_server_rpc.clone();
-        let visualization           = Visualization::new(language_server,embedded_visualizations);
+        let library                 = project_name.deref();
+        let visualization           = Visualization::new(language_server,embedded_visualizations
+                                                        ,library);
         let project_data            = ProjectData{id:project_id,name:RefCell::new(project_name)};
         let project_data            = Rc::new(project_data);
         let module_registry         = default();
@@ -167,9 +482,11 @@ impl Project {
         let language_server         = &*language_server_rpc;
         let suggestion_db           = SuggestionDatabase::create_synchronized(language_server);
         let suggestion_db           = Rc::new(suggestion_db.await?);
+        let reconnection            = default();
 
         let ret = Project {project_data,project_manager,module_registry,execution_contexts,parser,
-            language_server_rpc,language_server_bin,logger,visualization,suggestion_db};
+            language_server_rpc,language_server_bin,logger,visualization,suggestion_db,
+            reconnection};
 
         let binary_handler = ret.binary_event_handler();
         crate::executor::global::spawn(binary_protocol_events.for_each(binary_handler));
@@ -177,7 +494,8 @@ impl Project {
         let json_rpc_handler = ret.json_event_handler();
         crate::executor::global::spawn(json_rpc_events.for_each(json_rpc_handler));
 
-        ret.acquire_suggestion_db_updates_capability().await?;
+        acquire_capabilities(&ret.logger,&ret.language_server_rpc).await?;
+        ret.session().acquire_visualization_updates(&ret.language_server_rpc).await;
         Ok(ret)
     }
 
@@ -201,42 +519,18 @@ impl Project {
         self.language_server_rpc.content_root()
     }
 
+    /// Set the function used to establish a new connection with the Language Server when the
+    /// current one is lost. Without it, the project will not reconnect.
+    pub fn set_connector(&self, connector:Connector) {
+        *self.reconnection.connector.borrow_mut() = Some(connector);
+    }
+
     /// Returns a handling function capable of processing updates from the binary protocol.
     /// Such function will be then typically used to process events stream from the binary
     /// connection handler.
     pub fn binary_event_handler
         (&self) -> impl Fn(enso_protocol::binary::Event) -> futures::future::Ready<()> {
-        let logger                  = self.logger.clone_ref();
-        let weak_execution_contexts = Rc::downgrade(&self.execution_contexts);
-        move |event| {
-            debug!(logger, "Received an event from the binary protocol: {event:?}");
-            use enso_protocol::binary::client::Event;
-            use enso_protocol::binary::Notification;
-            match event {
-                Event::Notification(Notification::VisualizationUpdate {context,data}) => {
-                    let data = VisualizationUpdateData::new(data);
-                    if let Some(execution_contexts) = weak_execution_contexts.upgrade() {
-                        let result = execution_contexts.dispatch_visualization_update(context,data);
-                        if let Err(error) = result {
-                            error!(logger,"Failed to handle the visualization update: {error}.");
-                        }
-                    } else {
-                        error!(logger,"Received a visualization update despite project being \
-                        already dropped.");
-                    }
-                }
-                Event::Closed => {
-                    error!(logger,"Lost binary connection with the Language Server!");
-                    // TODO [wmu]
-                    //  The problem should be reported to the user and the connection should be
-                    //  reestablished, see https://github.com/luna/ide/issues/145
-                }
-                Event::Error(error) => {
-                    error!(logger,"Error emitted by the binary data connection: {error}.");
-                }
-            }
-            futures::future::ready(())
-        }
+        self.session().binary_event_handler()
     }
 
     /// Returns a handling function capable of processing updates from the json-rpc protocol.
@@ -244,59 +538,20 @@ impl Project {
     /// connection handler.
     pub fn json_event_handler
     (&self) -> impl Fn(enso_protocol::language_server::Event) -> futures::future::Ready<()> {
-    // TODO [mwu]
-    //  This handler for JSON-RPC notifications is very similar to the function above that handles
-    //  binary protocol notifications. However, it is not practical to generalize them, as the
-    //  underlying RPC handlers and their types are separate.
-    //  This generalization should be reconsidered once the old JSON-RPC handler is phased out.
-    //  See: https://github.com/luna/ide/issues/587
-        let logger                  = self.logger.clone_ref();
-        let weak_execution_contexts = Rc::downgrade(&self.execution_contexts);
-        let weak_suggestion_db      = Rc::downgrade(&self.suggestion_db);
-        move |event| {
-            debug!(logger, "Received an event from the json-rpc protocol: {event:?}");
-            use enso_protocol::language_server::Event;
-            use enso_protocol::language_server::Notification;
-            match event {
-                Event::Notification(Notification::ExpressionValuesComputed(update)) => {
-                    if let Some(execution_contexts) = weak_execution_contexts.upgrade() {
-                        let result = execution_contexts.handle_expression_values_computed(update);
-                        if let Err(error) = result {
-                            error!(logger,"Failed to handle the expression values computed update: \
-                            {error}.");
-                        }
-                    } else {
-                        error!(logger,"Received a `ExpressionValuesComputed` update despite \
-                        execution context being already dropped.");
-                    }
-                }
-                Event::Notification(Notification::ExecutionFailed(update)) => {
-                    error!(logger,"Execution failed in context {update.context_id}. Error: \
-                    {update.message}.");
-                }
-                Event::Notification(Notification::SuggestionDatabaseUpdate(update)) => {
-                    if let Some(suggestion_db) = weak_suggestion_db.upgrade() {
-                        suggestion_db.apply_update_event(update);
-                    }
-                }
-                Event::Closed => {
-                    error!(logger,"Lost JSON-RPC connection with the Language Server!");
-                    // TODO [wmu]
-                    //  The problem should be reported to the user and the connection should be
-                    //  reestablished, see https://github.com/luna/ide/issues/145
-                }
-                Event::Error(error) => {
-                    error!(logger,"Error emitted by the binary data connection: {error}.");
-                }
-                _ => {}
-            }
-            futures::future::ready(())
-        }
+        self.session().json_event_handler()
     }
 
-    fn acquire_suggestion_db_updates_capability(&self) -> impl Future<Output=json_rpc::Result<()>> {
-        let capability = CapabilityRegistration::create_receives_suggestions_database_updates();
-        self.language_server_rpc.acquire_capability(&capability.method,&capability.register_options)
+    fn session(&self) -> Session {
+        Session {
+            logger              : self.logger.clone_ref(),
+            language_server_rpc : Rc::downgrade(&self.language_server_rpc),
+            language_server_bin : Rc::downgrade(&self.language_server_bin),
+            module_registry     : Rc::downgrade(&self.module_registry),
+            execution_contexts  : Rc::downgrade(&self.execution_contexts),
+            suggestion_db       : Rc::downgrade(&self.suggestion_db),
+            visualizations      : self.visualization.file_watcher(),
+            reconnection        : Rc::downgrade(&self.reconnection),
+        }
     }
 
     /// Returns a model of module opened from file. The returned model will synchronize its state
@@ -328,13 +583,25 @@ impl Project {
         model::synchronized::Module::open(path,language_server,parser)
     }
 
-    /// Creates a new execution context with given definition as a root; and registers the context
+    /// Lists the methods of the given module which can be used as entry points of the execution
+    /// contexts, i.e. all the module's top-level definitions which are not extension methods.
+    pub async fn entry_points(&self, path:ModulePath)
+    -> FallibleResult<Vec<language_server::MethodPointer>> {
+        let module      = self.module(path.clone()).await?;
+        let definitions = module.ast().def_iter().infos_vec();
+        let methods     = definitions.into_iter().filter_map(|definition| {
+            let name = definition.name.item;
+            name.extended_target.is_empty().as_some_from(|| path.method_pointer(name.name.item))
+        });
+        Ok(methods.collect())
+    }
+
+    /// Creates a new execution context with given entry point as a root; and registers the context
     /// for receiving update.
     pub async fn create_execution_context
-    (&self, root_definition:language_server::MethodPointer)
-    -> FallibleResult<Rc<ExecutionContext>> {
+    (&self, entry_point:impl Into<EntryPoint>) -> FallibleResult<Rc<ExecutionContext>> {
         let ls_rpc  = self.language_server_rpc.clone_ref();
-        let context = ExecutionContext::create(&self.logger,ls_rpc,root_definition);
+        let context = ExecutionContext::create(&self.logger,ls_rpc,entry_point);
         let context = context.await?;
         let context = Rc::new(context);
         self.register_execution_context(&context);
@@ -373,6 +640,7 @@ pub mod test {
 
     use crate::controller::FilePath;
     use crate::constants::DEFAULT_PROJECT_NAME;
+    use crate::constants::VISUALIZATION_DIRECTORY;
     use crate::executor::test_utils::TestWithLocalPoolExecutor;
 
     use json_rpc::expect_call;
@@ -402,16 +670,7 @@ pub mod test {
         binary_client.expect_event_stream().return_once(|| {
             futures::stream::empty().boxed_local()
         });
-        let initial_suggestions_db = language_server::response::GetSuggestionDatabase {
-            entries: vec![],
-            current_version: 0
-        };
-        expect_call!(json_client.get_suggestions_database() => Ok(initial_suggestions_db));
-        let capability_reg = CapabilityRegistration::create_receives_suggestions_database_updates();
-        let method         = capability_reg.method;
-        let options        = capability_reg.register_options;
-        expect_call!(json_client.acquire_capability(method,options) => Ok(()));
-
+        mock_calls_for_starting_session(&json_client);
         setup_mock_json(&mut json_client);
         setup_mock_binary(&mut binary_client);
 
@@ -489,6 +748,23 @@ pub mod test {
         });
     }
 
+    #[wasm_bindgen_test]
+    fn listing_entry_points() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            let module_path = ModulePath::from_mock_module_name("Test");
+            let file_path   = module_path.file_path().clone();
+            let code        = "main = 5\nhelper x = x + 1\nNumber.twice = this * 2";
+            let project     = setup_mock_project(|mock_json_client| {
+                mock_calls_for_opening_text_file(mock_json_client,file_path,code);
+            }, |_| {});
+            let entry_points = project.entry_points(module_path.clone()).await.unwrap();
+            let expected     = vec!
+                [module_path.method_pointer("main"),module_path.method_pointer("helper")];
+            assert_eq!(entry_points, expected);
+        });
+    }
+
     /// This tests checks mainly if:
     /// * project controller correctly creates execution context
     /// * created execution context appears in the registry
@@ -537,7 +813,71 @@ pub mod test {
         assert_eq!(value_info.method_pointer, value_update.method_call.clone().map(Rc::new));
     }
 
-    fn mock_calls_for_opening_text_file
+    /// Set up the expectations for the calls made when the session with the Language Server is
+    /// started or restored: getting the suggestion database and acquiring the capabilities.
+    fn mock_calls_for_starting_session(client:&language_server::MockClient) {
+        let initial_suggestions_db = language_server::response::GetSuggestionDatabase {
+            entries: vec![],
+            current_version: 0
+        };
+        expect_call!(client.get_suggestions_database() => Ok(initial_suggestions_db));
+        let capability_reg = CapabilityRegistration::create_receives_suggestions_database_updates();
+        let method         = capability_reg.method;
+        let options        = capability_reg.register_options;
+        expect_call!(client.acquire_capability(method,options) => Ok(()));
+        let sources        = language_server::Path::new(default(),&[SOURCE_DIRECTORY]);
+        let capability_reg = CapabilityRegistration::create_receives_tree_updates(sources);
+        let method         = capability_reg.method;
+        let options        = capability_reg.register_options;
+        expect_call!(client.acquire_capability(method,options) => Ok(()));
+        let visualizations = language_server::Path::new(default(),&[VISUALIZATION_DIRECTORY]);
+        let exists         = response::FileExists {exists:false};
+        expect_call!(client.file_exists(path=visualizations) => Ok(exists));
+    }
+
+    #[wasm_bindgen_test]
+    fn restoring_session_with_new_connection() {
+        let mut test    = TestWithLocalPoolExecutor::set_up();
+        let project     = setup_mock_project(|_| {}, |_| {});
+        let json_client = language_server::MockClient::default();
+        let init        = response::InitProtocolConnection {content_roots:vec![default()]};
+        expect_call!(json_client.init_protocol_connection(client_id=default()) => Ok(init));
+        mock_calls_for_starting_session(&json_client);
+        json_client.require_all_calls();
+        let mut binary_client = enso_protocol::binary::MockClient::default();
+        binary_client.expect_init().times(1).return_once(|_| futures::future::ok(()).boxed_local());
+        binary_client.expect_event_stream().return_once(|| {
+            futures::stream::empty().boxed_local()
+        });
+        let json    = Box::new(json_client);
+        let binary  = Box::new(binary_client);
+        let clients = RefCell::new(Some(Clients{json,binary}));
+        project.set_connector(Rc::new(move || {
+            let clients = clients.borrow_mut().take().expect("Connected more than once.");
+            futures::future::ok(clients).boxed_local()
+        }));
+
+        test.run_task(async move {
+            project.session().restore().await.unwrap();
+            // Dropping the project checks that all the expected calls were made with the new
+            // client.
+            drop(project);
+        });
+    }
+
+    #[wasm_bindgen_test]
+    fn restoring_session_without_connector() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let project  = setup_mock_project(|_| {}, |_| {});
+        test.run_task(async move {
+            let error = project.session().restore().await.unwrap_err();
+            assert!(error.downcast_ref::<NoConnector>().is_some());
+        });
+    }
+
+    /// Sets up the mock client expectations for opening the text file with the given content,
+    /// editing it and closing it.
+    pub fn mock_calls_for_opening_text_file
     (client:&language_server::MockClient, path:language_server::Path, content:&str) {
         let content          = content.to_string();
         let current_version  = Sha3_224::new(content.as_bytes());
@@ -111,6 +111,22 @@ where K : Clone + Eq + Hash {
         }
     }
 
+    /// Get item under the key if it is already loaded. Does not wait for items being loaded.
+    pub fn get_loaded(&self, key:&K) -> Option<Rc<V>> {
+        match self.registry.borrow_mut().get(key) {
+            Some(Entry::Loaded(state)) => Some(state),
+            _                          => None,
+        }
+    }
+
+    /// Get all the items which are already loaded.
+    pub fn loaded(&self) -> Vec<Rc<V>> {
+        self.registry.borrow().values().filter_map(|entry| match entry {
+            Entry::Loaded(state) => Some(state),
+            Entry::Loading(_)    => None,
+        }).collect()
+    }
+
     async fn get(&self, key:&K) -> Result<Option<Rc<V>>,LoadingError> {
         loop {
             let entry = self.registry.borrow_mut().get(&key);
@@ -3,10 +3,13 @@
 use crate::prelude::*;
 
 use crate::double_representation::module::QualifiedName;
+use crate::notification;
 
 use enso_protocol::language_server;
+use flo_stream::Subscriber;
 use language_server::types::SuggestionsDatabaseVersion;
 use language_server::types::SuggestionDatabaseUpdateEvent;
+use std::collections::BTreeSet;
 
 pub use language_server::types::SuggestionEntryArgument as Argument;
 pub use language_server::types::SuggestionEntryId as EntryId;
@@ -18,7 +21,7 @@ pub use language_server::types::SuggestionsDatabaseUpdate as Update;
 // =============
 
 /// A type of suggestion entry.
-#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+#[derive(Copy,Clone,Debug,Eq,Hash,PartialEq)]
 #[allow(missing_docs)]
 pub enum EntryKind {
     Atom,Function,Local,Method
@@ -86,14 +89,31 @@ impl Entry {
 
     /// Returns the code which should be inserted to Searcher input when suggestion is picked.
     pub fn code_to_insert(&self) -> String {
-        let module = self.module.name();
-        if self.self_type.as_ref().contains(&module) {
+        if self.is_module_method() {
+            let module = self.module.name();
             iformat!("{module}.{self.name}")
         } else {
             self.name.clone()
         }
     }
 
+    /// Returns the arguments which remain to be filled after inserting `code_to_insert` to the
+    /// Searcher input. For methods of the module the `this` argument is already provided by the
+    /// inserted code.
+    pub fn arguments_to_fill(&self) -> &[Argument] {
+        if self.is_module_method() {
+            self.arguments.get(1..).unwrap_or_default()
+        } else {
+            &self.arguments
+        }
+    }
+
+    /// Check if this entry is a method defined for the module it belongs to.
+    pub fn is_module_method(&self) -> bool {
+        let module = self.module.name();
+        self.self_type.as_ref().contains(&module)
+    }
+
     /// Returns entry with the changed name.
     pub fn with_name(self, name:impl Into<String>) -> Self {
         Self {name:name.into(),..self}
@@ -113,17 +133,117 @@ impl TryFrom<language_server::types::SuggestionEntry> for Entry {
 // === Database ===
 // ================
 
+// === Constants ===
+
+/// How many times the database content is fetched during a single resynchronization, if there
+/// are still version gaps between the content and the updates received in the meantime.
+pub const RESYNC_ATTEMPTS:usize = 3;
+
+
+// === Errors ===
+
+#[allow(missing_docs)]
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="Received suggestion database update of version {} while the database has version \
+{}. Some updates were missed.", received, current)]
+pub struct VersionGap {
+    pub current  : SuggestionsDatabaseVersion,
+    pub received : SuggestionsDatabaseVersion,
+}
+
+
+// === Notification ===
+
+/// Notification about change in the suggestion database.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Notification {
+    /// Some entries were added, modified or removed by the update event.
+    Updated,
+    /// The whole database content was replaced by the one fetched from the Language Server.
+    Resynchronized,
+}
+
+
+// === Indexes ===
+
+/// The set of entry ids. Ordered, so the query results are deterministic.
+type EntryIds = BTreeSet<EntryId>;
+
+/// The secondary indexes of the database entries, allowing lookups without scanning all entries.
+#[derive(Clone,Debug,Default)]
+struct Indexes {
+    by_module      : HashMap<QualifiedName,EntryIds>,
+    by_self_type   : HashMap<String,EntryIds>,
+    by_return_type : HashMap<String,EntryIds>,
+    by_kind        : HashMap<EntryKind,EntryIds>,
+}
+
+impl Indexes {
+    /// Create indexes of the given entries.
+    fn new(entries:&HashMap<EntryId,Rc<Entry>>) -> Self {
+        let mut indexes = Self::default();
+        for (id,entry) in entries {
+            indexes.insert(*id,entry);
+        }
+        indexes
+    }
+
+    fn insert(&mut self, id:EntryId, entry:&Entry) {
+        self.by_module.entry(entry.module.clone()).or_default().insert(id);
+        self.by_return_type.entry(entry.return_type.clone()).or_default().insert(id);
+        self.by_kind.entry(entry.kind).or_default().insert(id);
+        if let Some(self_type) = &entry.self_type {
+            self.by_self_type.entry(self_type.clone()).or_default().insert(id);
+        }
+    }
+
+    fn remove(&mut self, id:EntryId, entry:&Entry) {
+        Self::remove_from(&mut self.by_module,&entry.module,id);
+        Self::remove_from(&mut self.by_return_type,&entry.return_type,id);
+        Self::remove_from(&mut self.by_kind,&entry.kind,id);
+        if let Some(self_type) = &entry.self_type {
+            Self::remove_from(&mut self.by_self_type,self_type,id);
+        }
+    }
+
+    fn remove_from<K:Eq+Hash>(index:&mut HashMap<K,EntryIds>, key:&K, id:EntryId) {
+        if let Some(ids) = index.get_mut(key) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+
+    fn get<K,Q>(index:&HashMap<K,EntryIds>, key:&Q) -> EntryIds
+    where K:Eq+Hash+std::borrow::Borrow<Q>, Q:Eq+Hash+?Sized {
+        index.get(key).cloned().unwrap_or_default()
+    }
+}
+
+
+// === SuggestionDatabase ===
+
 /// The Suggestion Database
 ///
 /// This is database of possible suggestions in Searcher. To achieve best performance, some
 /// often-called Language Server methods returns the list of keys of this database instead of the
 /// whole entries. Additionally the suggestions contains information about functions and their
 /// argument names and types.
+///
+/// The database is kept in sync with the Language Server by applying update events. Each event
+/// is expected to increase the database version by one; if some version is skipped, the database
+/// should be resynchronized (see `resynchronize` method).
 #[derive(Clone,Debug,Default)]
 pub struct SuggestionDatabase {
-    logger  : Logger,
-    entries : RefCell<HashMap<EntryId,Rc<Entry>>>,
-    version : Cell<SuggestionsDatabaseVersion>,
+    logger             : Logger,
+    entries            : RefCell<HashMap<EntryId,Rc<Entry>>>,
+    indexes            : RefCell<Indexes>,
+    version            : Cell<SuggestionsDatabaseVersion>,
+    resync_in_progress : Cell<bool>,
+    /// Update events received during resynchronization, to be applied once it is finished.
+    pending_updates    : RefCell<Vec<SuggestionDatabaseUpdateEvent>>,
+    notifications      : notification::Publisher<Notification>,
 }
 
 impl SuggestionDatabase {
@@ -136,20 +256,35 @@ impl SuggestionDatabase {
 
     /// Create a new database model from response received from the Language Server.
     fn from_ls_response(response:language_server::response::GetSuggestionDatabase) -> Self {
-        let logger      = Logger::new("SuggestionDatabase");
+        let logger  = Logger::new("SuggestionDatabase");
+        let entries = Self::entries_from_ls_response(&logger,response.entries);
+        let indexes = Indexes::new(&entries);
+        Self {
+            logger,
+            entries : RefCell::new(entries),
+            indexes : RefCell::new(indexes),
+            version : Cell::new(response.current_version),
+            ..default()
+        }
+    }
+
+    fn entries_from_ls_response
+    (logger:&Logger, ls_entries:Vec<language_server::SuggestionsDatabaseEntry>)
+    -> HashMap<EntryId,Rc<Entry>> {
         let mut entries = HashMap::new();
-        for ls_entry in response.entries {
+        for ls_entry in ls_entries {
             let id = ls_entry.id;
             match Entry::from_ls_entry(ls_entry.suggestion) {
                 Ok(entry) => { entries.insert(id, Rc::new(entry)); },
                 Err(err)  => { error!(logger,"Discarded invalid entry {id}: {err}"); },
             }
         }
-        Self {
-            logger,
-            entries : RefCell::new(entries),
-            version : Cell::new(response.current_version),
-        }
+        entries
+    }
+
+    /// Subscribe for notifications about changes in the database.
+    pub fn subscribe(&self) -> Subscriber<Notification> {
+        self.notifications.subscribe()
     }
 
     /// Get suggestion entry by id.
@@ -157,26 +292,192 @@ impl SuggestionDatabase {
         self.entries.borrow().get(&id).cloned()
     }
 
+    /// Get all entries defined in the given module.
+    pub fn entries_in_module(&self, module:&QualifiedName) -> Vec<(EntryId,Rc<Entry>)> {
+        let ids = Indexes::get(&self.indexes.borrow().by_module,module);
+        self.entries_with_ids(ids)
+    }
+
+    /// Get all methods of the given type.
+    pub fn methods_of_type(&self, self_type:&str) -> Vec<(EntryId,Rc<Entry>)> {
+        let ids = Indexes::get(&self.indexes.borrow().by_self_type,self_type);
+        self.entries_with_ids(ids)
+    }
+
+    /// Get all entries returning the given type.
+    pub fn entries_returning(&self, return_type:&str) -> Vec<(EntryId,Rc<Entry>)> {
+        let ids = Indexes::get(&self.indexes.borrow().by_return_type,return_type);
+        self.entries_with_ids(ids)
+    }
+
+    /// Get all entries of the given kind.
+    pub fn entries_of_kind(&self, kind:EntryKind) -> Vec<(EntryId,Rc<Entry>)> {
+        let ids = Indexes::get(&self.indexes.borrow().by_kind,&kind);
+        self.entries_with_ids(ids)
+    }
+
+    /// Get all atoms defined in the given module.
+    pub fn atoms_in_module(&self, module:&QualifiedName) -> Vec<(EntryId,Rc<Entry>)> {
+        let indexes   = self.indexes.borrow();
+        let in_module = Indexes::get(&indexes.by_module,module);
+        let atoms     = Indexes::get(&indexes.by_kind,&EntryKind::Atom);
+        drop(indexes);
+        self.entries_with_ids(in_module.intersection(&atoms).copied())
+    }
+
+    fn entries_with_ids
+    (&self, ids:impl IntoIterator<Item=EntryId>) -> Vec<(EntryId,Rc<Entry>)> {
+        let entries = self.entries.borrow();
+        ids.into_iter().filter_map(|id| Some((id,entries.get(&id)?.clone_ref()))).collect()
+    }
+
+    /// Get the current version of the database.
+    pub fn version(&self) -> SuggestionsDatabaseVersion {
+        self.version.get()
+    }
+
     /// Apply the update event to the database.
-    pub fn apply_update_event(&self, event:SuggestionDatabaseUpdateEvent) {
-        for update in event.updates {
-            let mut entries = self.entries.borrow_mut();
+    ///
+    /// The events older than the database version are ignored. If the event is newer than the
+    /// next expected version, the `VersionGap` error is returned and the database should be
+    /// resynchronized. During resynchronization the events are stored and applied once it is
+    /// finished.
+    pub fn apply_update_event
+    (&self, event:SuggestionDatabaseUpdateEvent) -> Result<(),VersionGap> {
+        if self.resync_in_progress.get() {
+            self.pending_updates.borrow_mut().push(event);
+            Ok(())
+        } else {
+            self.apply_update_event_now(event)
+        }
+    }
+
+    fn apply_update_event_now
+    (&self, event:SuggestionDatabaseUpdateEvent) -> Result<(),VersionGap> {
+        let current  = self.version.get();
+        let received = event.current_version;
+        if received <= current {
+            debug!(self.logger,"Skipping outdated update of version {received}.");
+            Ok(())
+        } else if received > current + 1 {
+            Err(VersionGap {current,received})
+        } else {
+            self.apply_updates(event.updates);
+            self.version.set(received);
+            self.notify(Notification::Updated);
+            Ok(())
+        }
+    }
+
+    fn apply_updates(&self, updates:Vec<Update>) {
+        for update in updates {
             match update {
                 Update::Add {id,entry} => match entry.try_into() {
-                    Ok(entry) => { entries.insert(id,Rc::new(entry));                       },
+                    Ok(entry) => { self.insert_entry(id,entry);                             },
                     Err(err)  => { error!(self.logger, "Discarding update for {id}: {err}") },
                 },
-                Update::Remove {id} => { entries.remove(&id); },
+                // The old entry is kept if the new one is invalid.
+                Update::Modify {id,entry} => match entry.try_into() {
+                    Ok(entry) => {
+                        if !self.entries.borrow().contains_key(&id) {
+                            warning!(self.logger,"Modifying missing entry {id}; adding it.");
+                        }
+                        self.insert_entry(id,entry);
+                    },
+                    Err(err) => { error!(self.logger, "Discarding update for {id}: {err}") },
+                },
+                Update::Remove {id} => {
+                    if self.remove_entry(id).is_none() {
+                        warning!(self.logger,"Removing missing entry {id}.");
+                    }
+                },
             };
         }
-        self.version.set(event.current_version);
+    }
+
+    fn insert_entry(&self, id:EntryId, entry:Entry) {
+        let entry       = Rc::new(entry);
+        let mut indexes = self.indexes.borrow_mut();
+        if let Some(old_entry) = self.entries.borrow_mut().insert(id,entry.clone_ref()) {
+            indexes.remove(id,&old_entry);
+        }
+        indexes.insert(id,&entry);
+    }
+
+    fn remove_entry(&self, id:EntryId) -> Option<Rc<Entry>> {
+        let removed = self.entries.borrow_mut().remove(&id);
+        if let Some(entry) = &removed {
+            self.indexes.borrow_mut().remove(id,entry);
+        }
+        removed
+    }
+
+    /// Replace the database content with the one fetched from the Language Server.
+    ///
+    /// This should be called when the update events cannot be applied, e.g. because some of them
+    /// were missed. The update events received in the meantime are applied after fetching the
+    /// content. If there is a version gap between them, the content is fetched again, up to
+    /// `RESYNC_ATTEMPTS` times. If fetching fails, the pending events are discarded.
+    pub async fn resynchronize
+    (&self, language_server:&language_server::Connection) -> FallibleResult<()> {
+        if self.resync_in_progress.replace(true) {
+            return Ok(())
+        }
+        let result = self.resynchronize_until_consistent(language_server).await;
+        self.resync_in_progress.set(false);
+        if result.is_err() {
+            self.pending_updates.borrow_mut().clear();
+        }
+        result
+    }
+
+    async fn resynchronize_until_consistent
+    (&self, language_server:&language_server::Connection) -> FallibleResult<()> {
+        let mut attempt = 1;
+        loop {
+            info!(self.logger,"Resynchronizing the suggestion database, attempt {attempt}.");
+            let response = language_server.client.get_suggestions_database().await?;
+            let entries  = Self::entries_from_ls_response(&self.logger,response.entries);
+            *self.indexes.borrow_mut() = Indexes::new(&entries);
+            *self.entries.borrow_mut() = entries;
+            self.version.set(response.current_version);
+            self.notify(Notification::Resynchronized);
+            match self.apply_pending_updates() {
+                Ok(())                                  => return Ok(()),
+                Err(gap) if attempt >= RESYNC_ATTEMPTS => return Err(gap.into()),
+                Err(gap)                                => {
+                    warning!(self.logger,"Version gap after resynchronization: {gap}.");
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Apply the updates received during resynchronization. If there is a version gap, the event
+    /// and all the following ones are put back to pending updates.
+    fn apply_pending_updates(&self) -> Result<(),VersionGap> {
+        let mut pending_updates = std::mem::take(&mut *self.pending_updates.borrow_mut());
+        while !pending_updates.is_empty() {
+            let event = pending_updates.remove(0);
+            if let Err(gap) = self.apply_update_event_now(event.clone()) {
+                pending_updates.insert(0,event);
+                *self.pending_updates.borrow_mut() = pending_updates;
+                return Err(gap)
+            }
+        }
+        Ok(())
+    }
+
+    fn notify(&self, notification:Notification) {
+        let notify = self.notifications.publish(notification);
+        executor::global::spawn(notify);
     }
 
     /// Put the entry to the database. Using this function likely break the synchronization between
     /// Language Server and IDE, and should be used only in tests.
     #[cfg(test)]
     pub fn put_entry(&self, id:EntryId, entry:Entry) {
-        self.entries.borrow_mut().insert(id,Rc::new(entry));
+        self.insert_entry(id,entry);
     }
 }
 
@@ -196,7 +497,11 @@ impl From<language_server::response::GetSuggestionDatabase> for SuggestionDataba
 mod test {
     use super::*;
 
+    use crate::executor::test_utils::TestWithLocalPoolExecutor;
+
     use enso_protocol::language_server::SuggestionsDatabaseEntry;
+    use json_rpc::error::RpcError;
+    use utils::test::traits::*;
 
 
 
@@ -259,8 +564,19 @@ mod test {
         assert_eq!(db.version.get(), 456);
     }
 
+    fn atom_entry(name:&str) -> language_server::types::SuggestionEntry {
+        language_server::types::SuggestionEntry::SuggestionEntryAtom {
+            name          : name.to_string(),
+            module        : "TestProject.TestModule".to_string(),
+            arguments     : vec![],
+            return_type   : "TestAtom".to_string(),
+            documentation : None
+        }
+    }
+
     #[test]
     fn applying_update() {
+        let _test = TestWithLocalPoolExecutor::set_up();
         let entry1 = language_server::types::SuggestionEntry::SuggestionEntryAtom {
             name          : "Entry1".to_string(),
             module        : "TestProject.TestModule".to_string(),
@@ -297,7 +613,7 @@ mod test {
             updates         : vec![remove_update],
             current_version : 2
         };
-        db.apply_update_event(update);
+        db.apply_update_event(update).unwrap();
         assert_eq!(db.get(2),        None);
         assert_eq!(db.version.get(), 2   );
 
@@ -307,8 +623,280 @@ mod test {
             updates         : vec![add_update],
             current_version : 3,
         };
-        db.apply_update_event(update);
+        db.apply_update_event(update).unwrap();
         assert_eq!(db.get(2).unwrap().name, "NewEntry2");
         assert_eq!(db.version.get(),        3          );
+
+        // Modify
+        let modify_update = Update::Modify {id:1, entry:atom_entry("NewEntry1")};
+        let update        = SuggestionDatabaseUpdateEvent {
+            updates         : vec![modify_update],
+            current_version : 4,
+        };
+        db.apply_update_event(update).unwrap();
+        assert_eq!(db.get(1).unwrap().name, "NewEntry1");
+        assert_eq!(db.version.get(),        4          );
+    }
+
+    #[test]
+    fn keeping_entry_on_invalid_modification() {
+        let _test    = TestWithLocalPoolExecutor::set_up();
+        let db_entry = SuggestionsDatabaseEntry {id:1, suggestion:atom_entry("Entry1")};
+        let response = language_server::response::GetSuggestionDatabase {
+            entries         : vec![db_entry],
+            current_version : 1,
+        };
+        let db      = SuggestionDatabase::from_ls_response(response);
+        let invalid = language_server::types::SuggestionEntry::SuggestionEntryAtom {
+            name          : "NewEntry1".to_string(),
+            module        : "ModuleWithoutProject".to_string(),
+            arguments     : vec![],
+            return_type   : "TestAtom".to_string(),
+            documentation : None
+        };
+        let update = SuggestionDatabaseUpdateEvent {
+            updates         : vec![Update::Modify {id:1, entry:invalid}],
+            current_version : 2,
+        };
+        db.apply_update_event(update).unwrap();
+        let module:QualifiedName = "TestProject.TestModule".to_string().try_into().unwrap();
+        assert_eq!(db.get(1).unwrap().name, "Entry1");
+        assert_eq!(db.version.get(),        2       );
+        assert_eq!(db.entries_in_module(&module).len(), 1);
+    }
+
+    #[test]
+    fn handling_update_versions() {
+        let mut test     = TestWithLocalPoolExecutor::set_up();
+        let db_entry     = SuggestionsDatabaseEntry {id:1, suggestion:atom_entry("Entry1")};
+        let response     = language_server::response::GetSuggestionDatabase {
+            entries         : vec![db_entry],
+            current_version : 5,
+        };
+        let db             = SuggestionDatabase::from_ls_response(response);
+        let mut subscriber = db.subscribe();
+
+        // Outdated update is ignored.
+        let update = SuggestionDatabaseUpdateEvent {
+            updates         : vec![Update::Remove {id:1}],
+            current_version : 5,
+        };
+        db.apply_update_event(update).unwrap();
+        assert!(db.get(1).is_some());
+        assert_eq!(db.version(), 5);
+        test.run_until_stalled();
+        subscriber.next().boxed_local().expect_pending();
+
+        // Update after gap is not applied.
+        let update = SuggestionDatabaseUpdateEvent {
+            updates         : vec![Update::Remove {id:1}],
+            current_version : 7,
+        };
+        let gap = db.apply_update_event(update).unwrap_err();
+        assert_eq!((gap.current,gap.received), (5,7));
+        assert!(db.get(1).is_some());
+        assert_eq!(db.version(), 5);
+
+        // The next update is applied and notified.
+        let update = SuggestionDatabaseUpdateEvent {
+            updates         : vec![Update::Remove {id:1}],
+            current_version : 6,
+        };
+        db.apply_update_event(update).unwrap();
+        assert!(db.get(1).is_none());
+        assert_eq!(db.version(), 6);
+        test.run_until_stalled();
+        let notification = subscriber.next().boxed_local().expect_ready();
+        assert_eq!(notification, Some(Notification::Updated));
+    }
+
+    #[test]
+    fn querying_entries() {
+        let _test  = TestWithLocalPoolExecutor::set_up();
+        let module = |name:&str| -> QualifiedName { name.to_string().try_into().unwrap() };
+        let atom   = Entry {
+            name          : "Atom".to_string(),
+            kind          : EntryKind::Atom,
+            module        : module("Project.Main"),
+            arguments     : vec![],
+            return_type   : "Atom".to_string(),
+            documentation : None,
+            self_type     : None,
+        };
+        let method = Entry {
+            name        : "method".to_string(),
+            kind        : EntryKind::Method,
+            return_type : "Number".to_string(),
+            self_type   : Some("Atom".to_string()),
+            ..atom.clone()
+        };
+        let other_atom = Entry {
+            name   : "OtherAtom".to_string(),
+            module : module("Project.Other"),
+            ..atom.clone()
+        };
+        let db = SuggestionDatabase::default();
+        db.put_entry(1,atom);
+        db.put_entry(2,method);
+        db.put_entry(3,other_atom);
+        let ids = |entries:Vec<(EntryId,Rc<Entry>)>| {
+            entries.into_iter().map(|(id,_)| id).collect_vec()
+        };
+
+        assert_eq!(ids(db.entries_in_module(&module("Project.Main"))), vec![1,2]);
+        assert_eq!(ids(db.entries_in_module(&module("Project.Other"))), vec![3]);
+        assert_eq!(ids(db.methods_of_type("Atom")), vec![2]);
+        assert_eq!(ids(db.entries_returning("Atom")), vec![1,3]);
+        assert_eq!(ids(db.entries_of_kind(EntryKind::Method)), vec![2]);
+        assert_eq!(ids(db.atoms_in_module(&module("Project.Main"))), vec![1]);
+
+        // Indexes are updated with entries.
+        let update = SuggestionDatabaseUpdateEvent {
+            updates : vec![
+                Update::Remove {id:1},
+                Update::Modify {id:3, entry:atom_entry("Entry3")},
+            ],
+            current_version : 1,
+        };
+        db.apply_update_event(update).unwrap();
+        assert_eq!(ids(db.entries_in_module(&module("Project.Main"))), vec![2]);
+        assert_eq!(ids(db.entries_in_module(&module("Project.Other"))), Vec::<EntryId>::new());
+        assert_eq!(ids(db.entries_in_module(&module("TestProject.TestModule"))), vec![3]);
+        assert_eq!(ids(db.entries_returning("Atom")), Vec::<EntryId>::new());
+        assert_eq!(ids(db.entries_returning("TestAtom")), vec![3]);
+        assert_eq!(ids(db.atoms_in_module(&module("Project.Main"))), Vec::<EntryId>::new());
+    }
+
+    #[test]
+    fn resynchronizing() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let db       = Rc::new(SuggestionDatabase::from_ls_response(
+            language_server::response::GetSuggestionDatabase {
+                entries         : vec![],
+                current_version : 1,
+            }
+        ));
+        let mut subscriber = db.subscribe();
+        let client         = language_server::MockClient::default();
+        let db_clone       = db.clone_ref();
+        client.expect.get_suggestions_database(move || {
+            // Updates received during resynchronization should be applied after it.
+            let outdated_update = SuggestionDatabaseUpdateEvent {
+                updates         : vec![Update::Remove {id:1}],
+                current_version : 10,
+            };
+            let new_update = SuggestionDatabaseUpdateEvent {
+                updates         : vec![Update::Add {id:2, entry:atom_entry("Entry2")}],
+                current_version : 11,
+            };
+            db_clone.apply_update_event(outdated_update).unwrap();
+            db_clone.apply_update_event(new_update).unwrap();
+            assert!(db_clone.get(2).is_none());
+
+            let db_entry = SuggestionsDatabaseEntry {id:1, suggestion:atom_entry("Entry1")};
+            Ok(language_server::response::GetSuggestionDatabase {
+                entries         : vec![db_entry],
+                current_version : 10,
+            })
+        });
+        let connection = language_server::Connection::new_mock(client);
+
+        let db_clone = db.clone_ref();
+        test.run_task(async move {
+            db_clone.resynchronize(&connection).await.unwrap();
+        });
+        test.expect_finished();
+
+        assert_eq!(db.get(1).unwrap().name, "Entry1");
+        assert_eq!(db.get(2).unwrap().name, "Entry2");
+        assert_eq!(db.version(), 11);
+        let notification = subscriber.next().boxed_local().expect_ready();
+        assert_eq!(notification, Some(Notification::Resynchronized));
+        let notification = subscriber.next().boxed_local().expect_ready();
+        assert_eq!(notification, Some(Notification::Updated));
+    }
+
+    #[test]
+    fn resynchronizing_again_on_version_gap() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let db       = Rc::new(SuggestionDatabase::from_ls_response(
+            language_server::response::GetSuggestionDatabase {
+                entries         : vec![],
+                current_version : 1,
+            }
+        ));
+        let client   = language_server::MockClient::default();
+        let db_clone = db.clone_ref();
+        client.expect.get_suggestions_database(move || {
+            // This update does not follow the fetched version, so the content is fetched again.
+            let gapped_update = SuggestionDatabaseUpdateEvent {
+                updates         : vec![Update::Add {id:2, entry:atom_entry("Entry2")}],
+                current_version : 12,
+            };
+            db_clone.apply_update_event(gapped_update).unwrap();
+            Ok(language_server::response::GetSuggestionDatabase {
+                entries         : vec![],
+                current_version : 10,
+            })
+        });
+        client.expect.get_suggestions_database(|| {
+            let db_entry = SuggestionsDatabaseEntry {id:1, suggestion:atom_entry("Entry1")};
+            Ok(language_server::response::GetSuggestionDatabase {
+                entries         : vec![db_entry],
+                current_version : 11,
+            })
+        });
+        let connection = language_server::Connection::new_mock(client);
+
+        let db_clone = db.clone_ref();
+        test.run_task(async move {
+            db_clone.resynchronize(&connection).await.unwrap();
+        });
+        test.expect_finished();
+
+        assert_eq!(db.get(1).unwrap().name, "Entry1");
+        assert_eq!(db.get(2).unwrap().name, "Entry2");
+        assert_eq!(db.version(), 12);
+        assert!(db.pending_updates.borrow().is_empty());
+    }
+
+    #[test]
+    fn dropping_pending_updates_on_failed_resynchronization() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let db       = Rc::new(SuggestionDatabase::from_ls_response(
+            language_server::response::GetSuggestionDatabase {
+                entries         : vec![],
+                current_version : 1,
+            }
+        ));
+        let client   = language_server::MockClient::default();
+        let db_clone = db.clone_ref();
+        client.expect.get_suggestions_database(move || {
+            let update = SuggestionDatabaseUpdateEvent {
+                updates         : vec![Update::Add {id:2, entry:atom_entry("Entry2")}],
+                current_version : 2,
+            };
+            db_clone.apply_update_event(update).unwrap();
+            Err(RpcError::LostConnection)
+        });
+        let connection = language_server::Connection::new_mock(client);
+
+        let db_clone = db.clone_ref();
+        test.run_task(async move {
+            assert!(db_clone.resynchronize(&connection).await.is_err());
+        });
+        test.expect_finished();
+
+        assert!(db.pending_updates.borrow().is_empty());
+        assert!(db.get(2).is_none());
+        assert_eq!(db.version(), 1);
+
+        // The database is no longer resynchronizing, so next updates are applied immediately.
+        let update = SuggestionDatabaseUpdateEvent {
+            updates         : vec![Update::Add {id:2, entry:atom_entry("Entry2")}],
+            current_version : 2,
+        };
+        db.apply_update_event(update).unwrap();
+        assert_eq!(db.get(2).unwrap().name, "Entry2");
     }
 }
@@ -3,13 +3,15 @@
 use crate::prelude::*;
 
 use crate::model::execution_context::ComputedValueInfoRegistry;
+use crate::model::execution_context::EntryPoint;
+use crate::model::execution_context::ExecutionStatus;
 use crate::model::execution_context::LocalCall;
 use crate::model::execution_context::Visualization;
 use crate::model::execution_context::VisualizationUpdateData;
 use crate::model::execution_context::VisualizationId;
 
 use enso_protocol::language_server;
-use json_rpc::error::RpcError;
+use flo_stream::Subscriber;
 
 
 // ==========================
@@ -20,7 +22,7 @@ use json_rpc::error::RpcError;
 /// from LS once dropped.
 #[derive(Debug)]
 pub struct ExecutionContext {
-    id              : model::execution_context::Id,
+    id              : Cell<model::execution_context::Id>,
     model           : model::ExecutionContext,
     language_server : Rc<language_server::Connection>,
     logger          : Logger,
@@ -29,26 +31,28 @@ pub struct ExecutionContext {
 impl ExecutionContext {
     /// The unique identifier of this execution context.
     pub fn id(&self) -> model::execution_context::Id {
-        self.id
+        self.id.get()
     }
 
     /// Create new ExecutionContext. It will be created in LanguageServer and the ExplicitCall
-    /// stack frame will be pushed.
+    /// stack frame of the given entry point will be pushed.
     ///
     /// NOTE: By itself this execution context will not be able to receive any updates from the
     /// language server.
     pub fn create
 	( parent          : impl AnyLogger
     , language_server : Rc<language_server::Connection>
-    , root_definition : language_server::MethodPointer
+    , entry_point     : impl Into<EntryPoint>
     ) -> impl Future<Output=FallibleResult<Self>> {
-        let logger = Logger::sub(&parent,"ExecutionContext");
+        let logger      = Logger::sub(&parent,"ExecutionContext");
+        let entry_point = entry_point.into();
         async move {
             info!(logger, "Creating.");
             let id     = language_server.client.create_execution_context().await?.context_id;
             let logger = Logger::sub(&parent,iformat!{"ExecutionContext {id}"});
-            let model  = model::ExecutionContext::new(&logger,root_definition);
+            let model  = model::ExecutionContext::new(&logger,entry_point);
             info!(logger, "Created. Id: {id}.");
+            let id   = Cell::new(id);
             let this = Self {id,model,language_server,logger };
             this.push_root_frame().await?;
             info!(this.logger, "Pushed root frame.");
@@ -61,32 +65,74 @@ impl ExecutionContext {
         self.model.current_method()
     }
 
-    fn push_root_frame(&self) -> impl Future<Output=FallibleResult<()>> {
-        let method_pointer                   = self.model.entry_point.clone();
-        let this_argument_expression         = default();
-        let positional_arguments_expressions = default();
+    /// Get an iterator over the local calls on the stack, see `stack_items` in the model.
+    pub fn stack_items<'a>(&'a self) -> impl Iterator<Item=LocalCall> + 'a {
+        self.model.stack_items()
+    }
+
+    /// The root call of this context.
+    pub fn entry_point(&self) -> EntryPoint {
+        self.model.entry_point()
+    }
+
+    /// Replace the root call of this context.
+    ///
+    /// All the frames, including the root one, are popped from the Language Server's stack and the
+    /// new root frame is pushed. The attached visualizations are kept.
+    ///
+    /// The model follows the Language Server's stack: if some pop fails, the local calls popped
+    /// so far are removed from the model. If the new root frame cannot be pushed, the previous one
+    /// is pushed back.
+    pub async fn set_entry_point(&self, entry_point:EntryPoint) -> FallibleResult<()> {
+        while self.model.stack_items().next().is_some() {
+            self.pop().await?;
+        }
+        self.language_server.pop_from_execution_context(&self.id()).await?;
+        let previous_entry_point = self.model.entry_point();
+        self.model.set_entry_point(entry_point);
+        if let Err(err) = self.push_root_frame().await {
+            warning!(self.logger, "Failed to push root frame of the new entry point, restoring the \
+                previous one: {err}");
+            self.model.set_entry_point(previous_entry_point);
+            self.push_root_frame().await?;
+            return Err(err)
+        }
+        info!(self.logger, "Pushed root frame of the new entry point.");
+        Ok(())
+    }
 
-        let call = language_server::ExplicitCall {method_pointer,this_argument_expression,
-            positional_arguments_expressions};
+    fn push_root_frame(&self) -> impl Future<Output=FallibleResult<()>> {
+        let call   = self.model.entry_point().explicit_call();
         let frame  = language_server::StackItem::ExplicitCall(call);
-        let result = self.language_server.push_to_execution_context(&self.id,&frame);
+        let result = self.language_server.push_to_execution_context(&self.id(),&frame);
         result.map(|res| res.map_err(|err| err.into()))
     }
 
     /// Push a new stack item to execution context.
-    pub fn push(&self, stack_item: LocalCall) -> impl Future<Output=Result<(),RpcError>> {
+    ///
+    /// If the Language Server fails to push the frame, it is removed from the model as well.
+    pub async fn push(&self, stack_item: LocalCall) -> FallibleResult<()> {
         let expression_id = stack_item.call;
         let call          = language_server::LocalCall{expression_id};
         let frame         = language_server::StackItem::LocalCall(call);
         self.model.push(stack_item);
-        self.language_server.push_to_execution_context(&self.id,&frame)
+        if let Err(err) = self.language_server.push_to_execution_context(&self.id(),&frame).await {
+            self.model.pop()?;
+            return Err(err.into())
+        }
+        Ok(())
     }
 
     /// Pop the last stack item from this context. It returns error when only root call
     /// remains. The root frame cannot be popped.
+    ///
+    /// If the Language Server fails to pop the frame, it is kept in the model as well.
     pub async fn pop(&self) -> FallibleResult<LocalCall> {
         let ret = self.model.pop()?;
-        self.language_server.pop_from_execution_context(&self.id).await?;
+        if let Err(err) = self.language_server.pop_from_execution_context(&self.id()).await {
+            self.model.push(ret);
+            return Err(err.into())
+        }
         Ok(ret)
     }
 
@@ -99,7 +145,7 @@ impl ExecutionContext {
         //  We must register our visualization in the model first, because Language server can send
         //  us visualization updates through the binary socket before confirming that visualization
         //  has been successfully attached.
-        let config = vis.config(self.id);
+        let config = vis.config(self.id());
         let stream = self.model.attach_visualization(vis.clone());
         let result = self.language_server.attach_visualisation(&vis.id, &vis.ast_id, &config).await;
         if let Err(e) = result {
@@ -125,7 +171,7 @@ impl ExecutionContext {
     pub async fn detach_visualization_inner
     (&self, vis:Visualization) -> FallibleResult<Visualization> {
         let vis_id = vis.id;
-        let exe_id = self.id;
+        let exe_id = self.id();
         let ast_id = vis.ast_id;
         let ls     = self.language_server.clone_ref();
         let logger = self.logger.clone_ref();
@@ -163,11 +209,64 @@ impl ExecutionContext {
         self.model.handle_expression_values_computed(notification)
     }
 
+    /// Handles the notification about the execution of this context having failed.
+    pub fn handle_execution_failed(&self, notification:language_server::ExecutionFailed) {
+        self.model.handle_execution_failed(notification.message)
+    }
+
     /// Access the registry of computed values information, like types or called method pointers.
     pub fn computed_value_info_registry(&self) -> &ComputedValueInfoRegistry {
         &self.model.computed_value_info_registry
     }
 
+    /// The status of the last execution of this context.
+    pub fn execution_status(&self) -> ExecutionStatus {
+        self.model.execution_status()
+    }
+
+    /// Subscribe to the changes of the execution status.
+    pub fn subscribe_execution_status(&self) -> Subscriber<ExecutionStatus> {
+        self.model.subscribe_execution_status()
+    }
+
+
+    /// Recreate this context in the Language Server, e.g. after reconnecting, when the previous
+    /// context is no longer available. The context gets a new id, its call stack is pushed again
+    /// and all its visualizations are attached again.
+    ///
+    /// The frames are pushed one after another, as each must be on the stack before the next one,
+    /// and failing to push any of them fails the restoration. Then the visualizations are attached
+    /// in a single batch, and those which failed to attach are only reported.
+    pub async fn restore(&self) -> FallibleResult<()> {
+        info!(self.logger, "Restoring.");
+        let id             = self.language_server.client.create_execution_context().await?;
+        let id             = id.context_id;
+        self.id.set(id);
+        let root_call      = self.model.entry_point().explicit_call();
+        let root_frame     = language_server::StackItem::ExplicitCall(root_call);
+        let local_frames   = self.model.stack_items().map(|local_call| {
+            let expression_id = local_call.call;
+            language_server::StackItem::LocalCall(language_server::LocalCall{expression_id})
+        });
+        let frames         = std::iter::once(root_frame).chain(local_frames).collect_vec();
+        for frame in &frames {
+            self.language_server.push_to_execution_context(&id,frame).await?;
+        }
+        let visualizations = self.model.all_visualizations_info();
+        let results        = self.language_server.batch_all(|client| {
+            let attach = |vis:&Visualization| {
+                client.attach_visualisation(&vis.id,&vis.ast_id,&vis.config(id))
+            };
+            visualizations.iter().map(attach).collect()
+        }).await;
+        for (vis,result) in visualizations.iter().zip(results) {
+            if let Err(error) = result {
+                warning!(self.logger, "Failed to attach again visualization {vis.id}: {error}");
+            }
+        }
+        info!(self.logger, "Restored with new id {id}.");
+        Ok(())
+    }
 
     /// Create a mock which does no call on `language_server` during construction.
     #[cfg(test)]
@@ -177,13 +276,14 @@ impl ExecutionContext {
     , language_server : Rc<language_server::Connection>
     ) -> Self {
         let logger          = Logger::new("ExecuctionContext mock");
+        let id              = Cell::new(id);
         ExecutionContext {id,model,language_server,logger}
     }
 }
 
 impl Drop for ExecutionContext {
     fn drop(&mut self) {
-        let id     = self.id;
+        let id     = self.id();
         let ls     = self.language_server.clone_ref();
         let logger = self.logger.clone_ref();
         executor::global::spawn(async move {
@@ -211,6 +311,7 @@ pub mod tests {
 
     use enso_protocol::language_server::CapabilityRegistration;
     use enso_protocol::language_server::response::CreateExecutionContext;
+    use json_rpc::error::RpcError;
     use json_rpc::expect_call;
     use utils::test::ExpectTuple;
     use utils::test::stream::StreamTestExt;
@@ -284,8 +385,9 @@ pub mod tests {
             language_server::ExpressionValueUpdate {
                 id          : model::execution_context::ExpressionId::new_v4(),
                 typename    : Some("typename".into()),
-                short_value : None,
+                short_value : Some("5".into()),
                 method_call : None,
+                payload     : default(),
             }
         }
 
@@ -367,8 +469,8 @@ pub mod tests {
             let path    = Rc::new(mock_data.module_path);
             let context = ExecutionContext::create(logger,connection,method);
             let context = context.await.unwrap();
-            assert_eq!(context_id             , context.id);
-            assert_eq!(*path                  , context.model.entry_point.file);
+            assert_eq!(context_id             , context.id());
+            assert_eq!(*path                  , context.model.entry_point().method.file);
             assert_eq!(Vec::<LocalCall>::new(), context.model.stack_items().collect_vec());
         })
     }
@@ -421,6 +523,123 @@ pub mod tests {
         })
     }
 
+    #[test]
+    fn setting_entry_point() {
+        let mock_data = MockData {
+            customize_model : Rc::new(|model,data| {
+                let item = LocalCall {
+                    call       : model::execution_context::ExpressionId::new_v4(),
+                    definition : data.main_method_pointer(),
+                };
+                model.push(item);
+            }),
+            ..default()
+        };
+        let mut method = mock_data.main_method_pointer();
+        method.name    = "helper".to_string();
+        let arguments  = vec!["5","\"text\""];
+        let entry      = EntryPoint::with_arguments(method.clone(),arguments);
+        let root_frame = language_server::StackItem::ExplicitCall(entry.explicit_call());
+
+        let ls = language_server::MockClient::default();
+        let id = mock_data.context_id;
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+        expect_call!(ls.push_to_execution_context(id,root_frame) => Ok(()));
+        let context = mock_data.create_context(ls);
+
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            context.set_entry_point(entry.clone()).await.unwrap();
+            assert_eq!(context.entry_point(), entry);
+            assert_eq!(context.current_method(), method);
+            assert_eq!(Vec::<LocalCall>::new(), context.model.stack_items().collect_vec());
+        })
+    }
+
+    #[test]
+    fn setting_entry_point_with_failed_pop() {
+        let mock_data = MockData {
+            customize_model : Rc::new(|model,data| {
+                for _ in 0..2 {
+                    let item = LocalCall {
+                        call       : model::execution_context::ExpressionId::new_v4(),
+                        definition : data.main_method_pointer(),
+                    };
+                    model.push(item);
+                }
+            }),
+            ..default()
+        };
+        let mut method = mock_data.main_method_pointer();
+        method.name    = "helper".to_string();
+        let entry      = EntryPoint::new(method);
+
+        let ls = language_server::MockClient::default();
+        let id = mock_data.context_id;
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+        expect_call!(ls.pop_from_execution_context(id) => Err(RpcError::LostConnection));
+        let context  = mock_data.create_context(ls);
+        let previous = context.entry_point();
+
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            assert!(context.set_entry_point(entry).await.is_err());
+            assert_eq!(context.entry_point(), previous);
+            assert_eq!(context.model.stack_items().count(), 1);
+        })
+    }
+
+    #[test]
+    fn setting_entry_point_with_failed_push() {
+        let mock_data      = MockData::new();
+        let previous       = EntryPoint::new(mock_data.main_method_pointer());
+        let mut method     = mock_data.main_method_pointer();
+        method.name        = "helper".to_string();
+        let entry          = EntryPoint::new(method);
+        let old_root_frame = language_server::StackItem::ExplicitCall(previous.explicit_call());
+        let new_root_frame = language_server::StackItem::ExplicitCall(entry.explicit_call());
+        let timeout        = RpcError::TimeoutError {millis:1000};
+
+        let ls = language_server::MockClient::default();
+        let id = mock_data.context_id;
+        expect_call!(ls.pop_from_execution_context(id) => Ok(()));
+        expect_call!(ls.push_to_execution_context(id,new_root_frame) => Err(timeout));
+        expect_call!(ls.push_to_execution_context(id,old_root_frame) => Ok(()));
+        let context = mock_data.create_context(ls);
+
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            assert!(context.set_entry_point(entry).await.is_err());
+            assert_eq!(context.entry_point(), previous);
+        })
+    }
+
+    #[test]
+    fn handling_execution_failure() {
+        let mut test     = TestWithLocalPoolExecutor::set_up();
+        let mock_data    = MockData::new();
+        let context      = mock_data.create_context(language_server::MockClient::default());
+        let mut statuses = context.subscribe_execution_status().boxed_local();
+        assert_eq!(context.execution_status(), ExecutionStatus::Ok);
+
+        let context_id = mock_data.context_id;
+        let message    = "Object Main does not define method main.".to_string();
+        let failure    = language_server::ExecutionFailed {context_id,message:message.clone()};
+        context.handle_execution_failed(failure);
+        test.run_until_stalled();
+        let expected = ExecutionStatus::Failed {message};
+        assert_eq!(context.execution_status(), expected);
+        assert_eq!(statuses.expect_next(), expected);
+
+        // The new values computed mean that the context was successfully executed again.
+        context.handle_expression_values_computed(mock_data.mock_values_computed_update()).unwrap();
+        test.run_until_stalled();
+        assert_eq!(context.execution_status(), ExecutionStatus::Ok);
+        assert_eq!(statuses.expect_next(), ExecutionStatus::Ok);
+        statuses.expect_pending();
+    }
+
     #[test]
     fn attaching_visualizations_and_notifying() {
         let mock_data = MockData::new();
@@ -506,4 +725,108 @@ pub mod tests {
             context.detach_all_visualizations().await;
         });
     }
+
+    #[test]
+    fn restoring_context() {
+        let mock_data     = MockData::new();
+        let ls            = language_server::MockClient::default();
+        let new_data      = MockData::new();
+        let new_id        = new_data.context_id;
+        let expression_id = model::execution_context::ExpressionId::new_v4();
+        let vis           = Visualization {
+            id                   : model::execution_context::VisualizationId::new_v4(),
+            ast_id               : model::execution_context::ExpressionId::new_v4(),
+            expression           : "".to_string(),
+            visualisation_module : mock_data.module_qualified_name(),
+        };
+        let vis_id     = vis.id;
+        let ast_id     = vis.ast_id;
+        let config     = vis.config(new_id);
+        let root_frame = language_server::StackItem::ExplicitCall(language_server::ExplicitCall {
+            method_pointer                   : mock_data.main_method_pointer(),
+            this_argument_expression         : None,
+            positional_arguments_expressions : vec![],
+        });
+        let local_frame = language_server::StackItem::LocalCall(language_server::LocalCall {
+            expression_id
+        });
+        expect_call!(ls.create_execution_context() => Ok(new_data.expected_creation_response()));
+        expect_call!(ls.push_to_execution_context(new_id,root_frame)  => Ok(()));
+        expect_call!(ls.push_to_execution_context(new_id,local_frame) => Ok(()));
+        expect_call!(ls.attach_visualisation(vis_id,ast_id,config)    => Ok(()));
+        // The context is dropped with the new id.
+        expect_call!(ls.destroy_execution_context(new_id) => Ok(()));
+        let model      = mock_data.create_model();
+        let definition = mock_data.main_method_pointer();
+        model.push(LocalCall {call:expression_id,definition});
+        let _          = model.attach_visualization(vis);
+        let connection = language_server::Connection::new_mock_rc(ls);
+        let context    = ExecutionContext::new_mock(mock_data.context_id,model,connection);
+
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            context.restore().await.unwrap();
+            assert_eq!(context.id(), new_id);
+        });
+    }
+
+    #[test]
+    fn restoring_context_with_replies_out_of_order() {
+        use json_rpc::messages::Message;
+        use json_rpc::messages::RequestMessage;
+        use json_rpc::test_util::transport::mock::MockTransport;
+        use serde_json::Value;
+
+        let mock_data     = MockData::new();
+        let new_data      = MockData::new();
+        let new_id        = new_data.context_id;
+        let mut transport = MockTransport::new();
+        let client        = language_server::Client::new(transport.clone_ref());
+        let mut test      = TestWithLocalPoolExecutor::set_up();
+        executor::global::spawn(client.runner());
+        let model         = mock_data.create_model();
+        let definition    = mock_data.main_method_pointer();
+        model.push(LocalCall {call:model::execution_context::ExpressionId::new_v4(),definition});
+        let visualization = || Visualization {
+            id                   : model::execution_context::VisualizationId::new_v4(),
+            ast_id               : model::execution_context::ExpressionId::new_v4(),
+            expression           : "".to_string(),
+            visualisation_module : mock_data.module_qualified_name(),
+        };
+        let _ = model.attach_visualization(visualization());
+        let _ = model.attach_visualization(visualization());
+        test.run_task(async move {
+            let connection = language_server::Connection::new(client,default()).await.unwrap();
+            let connection = Rc::new(connection);
+            let context    = ExecutionContext::new_mock(mock_data.context_id,model,connection);
+            context.restore().await.unwrap();
+            assert_eq!(context.id(), new_id);
+        });
+
+        let mut reply = |result:Value| {
+            test.run_until_stalled();
+            let request = transport.expect_json_message::<RequestMessage<Value>>();
+            // Each request must be answered before the next one is sent.
+            transport.with_mut_data(|data| assert!(data.sent_text_msgs.is_empty()));
+            transport.mock_peer_json_message(Message::new_success(request.id,result));
+            request
+        };
+        reply(serde_json::json!({"contentRoots":[uuid::Uuid::new_v4()]}));
+        reply(serde_json::to_value(new_data.expected_creation_response()).unwrap());
+        let root_push  = reply(Value::Null);
+        let local_push = reply(Value::Null);
+        assert_eq!(root_push.method, "executionContext/push");
+        assert_eq!(local_push.method, "executionContext/push");
+
+        // The visualizations are attached in a single batch, answered in the reversed order.
+        test.run_until_stalled();
+        let attaches = transport.expect_json_message::<Vec<RequestMessage<Value>>>();
+        assert_eq!(attaches.len(), 2);
+        let replies = attaches.iter().rev().map(|request| {
+            assert_eq!(request.method, "executionContext/attachVisualisation");
+            Message::new_success(request.id,Value::Null)
+        }).collect_vec();
+        transport.mock_peer_json_message(replies);
+        test.expect_finished();
+    }
 }
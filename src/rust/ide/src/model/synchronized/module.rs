@@ -3,13 +3,16 @@
 use crate::prelude::*;
 
 use crate::model::module::Notification;
+use crate::notification;
 
 use enso_protocol::types::Sha3_224;
 use enso_protocol::language_server;
+use enso_protocol::language_server::FileEventKind;
 use data::text::TextLocation;
 use parser::api::SourceFile;
 use parser::Parser;
 use enso_protocol::language_server::TextEdit;
+use flo_stream::Subscriber;
 
 
 
@@ -59,7 +62,8 @@ enum LanguageServerContent {
     /// The content is synchronized with our module state after last fully handled notification.
     Synchronized(ParsedContentSummary),
     /// The content is not synchronized with our module state after last fully handled notificaiton,
-    /// probably due to connection error when sending update.
+    /// probably due to connection error when sending update. The next handled event will reopen the
+    /// file to learn the actual Language Server state and synchronize it again.
     Desynchronized(ContentSummary)
 }
 
@@ -74,6 +78,61 @@ impl LanguageServerContent {
 
 
 
+// =======================
+// === External Change ===
+// =======================
+
+/// A notification about the module's file being modified by someone else than this module, e.g.
+/// another user or a script.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum ExternalChange {
+    /// The module had no unsaved changes, so it was reloaded with the new file content.
+    Reloaded,
+    /// The module has unsaved changes, so the new file content was not loaded. Saving the module
+    /// will overwrite the external changes, unless the module is reloaded first (see
+    /// `Module::reload`).
+    Conflict {
+        /// The new content of the module's file.
+        file_content : String,
+    },
+}
+
+/// The information about the module's file, used to detect its external modifications.
+#[derive(Clone,Debug)]
+struct FileState {
+    /// The digest of the last known file content.
+    file_digest  : Sha3_224,
+    /// The digest of the module content corresponding to the last known file content. It may
+    /// differ from `file_digest`, because e.g. the metadata may be serialized differently.
+    saved_digest : Sha3_224,
+}
+
+impl FileState {
+    /// Create the state for the module which content was just loaded from the file.
+    fn new(file_digest:Sha3_224, model:&model::Module) -> Self {
+        let saved_digest = content_digest(model).unwrap_or_else(|_| file_digest.clone());
+        FileState {file_digest,saved_digest}
+    }
+}
+
+/// The digest of the module's serialized content.
+fn content_digest(model:&model::Module) -> FallibleResult<Sha3_224> {
+    Ok(Sha3_224::new(model.serialized_content()?.content.as_bytes()))
+}
+
+/// The events handled by the task synchronizing the module with Language Server.
+#[derive(Clone,Debug)]
+enum Event {
+    /// Our module state has changed.
+    ModelChanged(Notification),
+    /// The module's file has changed in the filesystem.
+    FileChanged(FileEventKind),
+    /// The connection with Language Server was restored, so the module must be opened again.
+    Reconnected,
+}
+
+
+
 // ===========================
 // === Synchronized Module ===
 // ===========================
@@ -83,16 +142,27 @@ impl LanguageServerContent {
 /// This struct owns  `model::Module`, load the state during creation and updates LS about all
 /// changes done to it. On drop the module is closed in Language Server.
 ///
+/// The module also reacts on external modifications of its file, reported with
+/// `handle_file_event`. If there are no unsaved changes, the module is reloaded with the new
+/// file content, otherwise the conflict is reported through `subscribe_external_changes`.
+///
+/// When the connection with Language Server is restored after being lost, the module should be
+/// informed with `handle_reconnection`, so it reopens its file in the new session.
+///
 /// See also (enso protocol documentation)
 /// [https://github.com/luna/enso/blob/main/docs/language-server/protocol-language-server.md].
 #[derive(Debug)]
 pub struct Module {
     /// Path to the module file.
-    pub path        : model::module::Path,
+    pub path         : model::module::Path,
     /// The module handle.
-    pub model       : model::Module,
-    language_server : Rc<language_server::Connection>,
-    logger          : Logger,
+    pub model        : model::Module,
+    language_server  : Rc<language_server::Connection>,
+    parser           : Parser,
+    file_state       : RefCell<FileState>,
+    external_events  : notification::Publisher<Event>,
+    external_changes : notification::Publisher<ExternalChange>,
+    logger           : Logger,
 }
 
 
@@ -114,13 +184,19 @@ impl Module {
         let opened = language_server.client.open_text_file(&file_path).await?;
         info!(logger, "Read content of the module {path}, digest is {opened.current_version:?}");
         let end_of_file = TextLocation::at_document_end(&opened.content);
-        // TODO[ao] We should not fail here when metadata are malformed, but discard them and set
-        //  default instead.
-        let source  = parser.parse_with_metadata(opened.content)?;
-        let digest  = opened.current_version;
-        let summary = ContentSummary {digest,end_of_file};
-        let model   = model::Module::new(source.ast,source.metadata);
-        let this    = Rc::new(Module {path,model,language_server,logger});
+        let (source,warning) = model::module::deserialize_content(&parser,opened.content)?;
+        if let Some(warning) = &warning {
+            warning!(logger,"{warning}");
+        }
+        let digest           = opened.current_version;
+        let model            = model::Module::new(source.ast,source.metadata);
+        model.set_metadata_warning(warning);
+        let file_state       = RefCell::new(FileState::new(digest.clone(),&model));
+        let summary          = ContentSummary {digest,end_of_file};
+        let external_events  = default();
+        let external_changes = default();
+        let this             = Rc::new(Module {path,model,language_server,parser,file_state,
+            external_events,external_changes,logger});
         executor::global::spawn(Self::runner(this.clone_ref(),summary));
         Ok(this)
     }
@@ -131,8 +207,41 @@ impl Module {
         let client = language_server::MockClient::default();
         client.expect.close_text_file(|_| Ok(()));
         // We don't expect any other call, because we don't execute `runner()`.
-        let language_server = language_server::Connection::new_mock_rc(client);
-        Rc::new(Module{path,model,language_server,logger})
+        let language_server  = language_server::Connection::new_mock_rc(client);
+        let parser           = Parser::new_or_panic();
+        let digest           = content_digest(&model).expect("Mocked module must be serializable.");
+        let file_state       = RefCell::new(FileState::new(digest,&model));
+        let external_events  = default();
+        let external_changes = default();
+        Rc::new(Module{path,model,language_server,parser,file_state,external_events,
+            external_changes,logger})
+    }
+
+    /// Handle the filesystem event concerning the module's file, e.g. received from the Language
+    /// Server. The event is processed asynchronously: when the file was modified externally, the
+    /// module is reloaded or the conflict is reported (see `ExternalChange`).
+    pub fn handle_file_event(&self, kind:FileEventKind) {
+        executor::global::spawn(self.external_events.publish(Event::FileChanged(kind)));
+    }
+
+    /// Handle the restored connection with Language Server. The module's file is reopened in the
+    /// new session and, if its content differs from the module's, replaced with the module content.
+    pub fn handle_reconnection(&self) {
+        executor::global::spawn(self.external_events.publish(Event::Reconnected));
+    }
+
+    /// Subscribe for notifications about external modifications of the module's file.
+    pub fn subscribe_external_changes(&self) -> Subscriber<ExternalChange> {
+        self.external_changes.subscribe()
+    }
+
+    /// Replace the module's content with the current content of its file, discarding any unsaved
+    /// changes. May be used to resolve the `ExternalChange::Conflict`.
+    pub async fn reload(&self) -> FallibleResult<()> {
+        let file_path    = self.path.file_path();
+        let file_content = self.language_server.client.read_file(file_path).await?.contents;
+        let file_digest  = Sha3_224::new(file_content.as_bytes());
+        self.load_content(file_digest,file_content)
     }
 }
 
@@ -143,21 +252,35 @@ impl Module {
     /// The asynchronous task scheduled during struct creation which listens for all module changes
     /// and send proper updates to Language Server.
     async fn runner(self:Rc<Self>, initial_ls_content: ContentSummary) {
+        // We subscribe first, so no event emitted during the first invalidation is missed.
+        let model_events       = self.model.subscribe().map(Event::ModelChanged);
+        let external_events    = self.external_events.subscribe();
+        let mut events         = futures::stream::select(model_events,external_events);
         let first_invalidation = self.full_invalidation(&initial_ls_content).await;
         let mut ls_content     = self.new_ls_content_info(initial_ls_content, first_invalidation);
-        let mut subscriber     = self.model.subscribe();
         let weak               = Rc::downgrade(&self);
         drop(self);
 
         loop {
-            let notification = subscriber.next().await;
-            let this = weak.upgrade();
-            match (notification,this) {
-                (Some(notification),Some(this)) => {
+            let event = events.next().await;
+            let this  = weak.upgrade();
+            match (event,this) {
+                (Some(Event::ModelChanged(notification)),Some(this)) => {
                     debug!(this.logger,"Processing a notification: {notification:?}");
                     let result = this.handle_notification(&ls_content,notification).await;
                     ls_content = this.new_ls_content_info(ls_content.summary().clone(),result)
                 }
+                (Some(Event::FileChanged(kind)),Some(this)) => {
+                    debug!(this.logger,"Processing a file event: {kind:?}");
+                    if let Err(err) = this.handle_file_change(kind).await {
+                        error!(this.logger,"Error during handling the module file change: {err}");
+                    }
+                }
+                (Some(Event::Reconnected),Some(this)) => {
+                    debug!(this.logger,"Reopening the module after reconnection.");
+                    let result = this.reopen().await;
+                    ls_content = this.new_ls_content_info(ls_content.summary().clone(),result)
+                }
                 _ => break,
             }
         }
@@ -186,7 +309,7 @@ impl Module {
     -> FallibleResult<ParsedContentSummary> {
         debug!(self.logger,"Handling notification: {content:?}.");
         match content {
-            LanguageServerContent::Desynchronized(summary) => self.full_invalidation(summary).await,
+            LanguageServerContent::Desynchronized(_)     => self.recover_synchronization().await,
             LanguageServerContent::Synchronized(summary)   => match notification {
                 Notification::Invalidate => self.full_invalidation(&summary.summary).await,
                 Notification::CodeChanged{change,replaced_location} =>
@@ -223,6 +346,40 @@ impl Module {
         }]).await
     }
 
+    /// Reopen the module's file in Language Server to learn the actual state of its content and
+    /// then replace it with the entire module content. Used after failed updates, when we cannot be
+    /// sure which of them were applied by Language Server. Returns the new content summary of
+    /// Language Server state.
+    async fn recover_synchronization(&self) -> FallibleResult<ParsedContentSummary> {
+        info!(self.logger,"Recovering synchronization with Language Server.");
+        let file_path = self.path.file_path();
+        // The file may be already closed if the previous recovery attempt failed.
+        if let Err(err) = self.language_server.client.close_text_file(file_path).await {
+            warning!(self.logger,"Error when closing module file before reopening: {err}");
+        }
+        self.reopen().await
+    }
+
+    /// Open the module's file in Language Server and, if its content differs from the module's,
+    /// replace it with the entire module content. Returns the new content summary of Language
+    /// Server state.
+    async fn reopen(&self) -> FallibleResult<ParsedContentSummary> {
+        let file_path = self.path.file_path();
+        let opened    = self.language_server.client.open_text_file(file_path).await?;
+        let content   = self.model.serialized_content()?;
+        let summary   = ParsedContentSummary::from_source(&content);
+        if opened.current_version == summary.digest {
+            debug!(self.logger,"The module content in Language Server is up to date.");
+            Ok(summary)
+        } else {
+            let ls_content = ContentSummary {
+                digest      : opened.current_version,
+                end_of_file : TextLocation::at_document_end(&opened.content),
+            };
+            self.full_invalidation(&ls_content).await
+        }
+    }
+
     /// This is a helper function with all common logic regarding sending the update to
     /// Language Server. Returns the new summary of Language Server state.
     async fn notify_language_server
@@ -244,6 +401,59 @@ impl Module {
     }
 }
 
+
+
+// === Handling External Changes ===
+
+impl Module {
+    /// Check if the module's file was changed externally and react accordingly: reload the module
+    /// if there were no unsaved changes, or report the conflict otherwise.
+    async fn handle_file_change(&self, kind:FileEventKind) -> FallibleResult<()> {
+        if kind == FileEventKind::Removed {
+            warning!(self.logger,"The module file was removed.");
+            return Ok(())
+        }
+        let file_path     = self.path.file_path();
+        let file_content  = self.language_server.client.read_file(file_path).await?.contents;
+        let file_digest   = Sha3_224::new(file_content.as_bytes());
+        let module_digest = content_digest(&self.model)?;
+        let known_state   = self.file_state.borrow().clone();
+        let is_unsaved    = module_digest != known_state.saved_digest;
+        if file_digest == module_digest {
+            debug!(self.logger,"The module file was saved.");
+            *self.file_state.borrow_mut() = FileState {file_digest,saved_digest:module_digest};
+        } else if file_digest == known_state.file_digest {
+            debug!(self.logger,"The module file content has not changed.");
+        } else if is_unsaved {
+            warning!(self.logger,"The module file was changed externally, but the module has \
+                unsaved changes.");
+            self.file_state.borrow_mut().file_digest = file_digest;
+            self.notify_external_change(ExternalChange::Conflict {file_content});
+        } else {
+            info!(self.logger,"The module file was changed externally, reloading.");
+            self.load_content(file_digest,file_content)?;
+            self.notify_external_change(ExternalChange::Reloaded);
+        }
+        Ok(())
+    }
+
+    /// Replace the module's content with the given content of its file.
+    fn load_content(&self, file_digest:Sha3_224, file_content:String) -> FallibleResult<()> {
+        let (source,warning) = model::module::deserialize_content(&self.parser,file_content)?;
+        if let Some(warning) = &warning {
+            warning!(self.logger,"{warning}");
+        }
+        self.model.set_metadata_warning(warning);
+        self.model.update_whole(source);
+        *self.file_state.borrow_mut() = FileState::new(file_digest,&self.model);
+        Ok(())
+    }
+
+    fn notify_external_change(&self, change:ExternalChange) {
+        executor::global::spawn(self.external_changes.publish(change));
+    }
+}
+
 impl Drop for Module {
     fn drop(&mut self) {
         let file_path       = self.path.file_path().clone();
@@ -284,6 +494,7 @@ pub mod test {
     use json_rpc::error::RpcError;
     use json_rpc::expect_call;
     use utils::test::ExpectTuple;
+    use utils::test::stream::StreamTestExt;
     use wasm_bindgen_test::wasm_bindgen_test;
 
 
@@ -340,9 +551,10 @@ pub mod test {
         }
 
         fn expect_invalidate(&self, result:json_rpc::Result<()>) {
-            let end_of_file = TextLocation::at_document_end(self.current_ls_code.get());
+            let ls_code = self.current_ls_code.clone_ref();
             self.expect_edit(result, move |edits| {
-                let (edit,)      = edits.iter().expect_tuple();
+                let end_of_file    = TextLocation::at_document_end(ls_code.get());
+                let (edit,)        = edits.iter().expect_tuple();
                 let expected_range = language_server::types::TextRange {
                     start : language_server::types::Position { line:0,character:0  },
                     end   : end_of_file.into(),
@@ -352,6 +564,31 @@ pub mod test {
             });
         }
 
+        fn expect_open(&self) {
+            let client     = &self.client;
+            let path       = self.file_path.clone();
+            let capability = CapabilityRegistration::create_can_edit_text_file(path);
+            let open_resp  = language_server::response::OpenTextFile {
+                write_capability : Some(capability),
+                content          : self.current_ls_code.get(),
+                current_version  : self.current_ls_version.get(),
+            };
+            expect_call!(client.open_text_file(path=self.file_path.clone()) => Ok(open_resp));
+        }
+
+        fn expect_reopen(&self) {
+            let client = &self.client;
+            expect_call!(client.close_text_file(path=self.file_path.clone()) => Ok(()));
+            self.expect_open();
+        }
+
+        fn expect_read(&self, file_content:impl Str) {
+            let client   = &self.client;
+            let contents = file_content.into();
+            let response = language_server::response::Read {contents};
+            expect_call!(client.read_file(path=self.file_path.clone()) => Ok(response));
+        }
+
         fn finish(self) -> Rc<language_server::Connection> {
             let client = self.client;
             expect_call!(client.close_text_file(path=self.file_path) => Ok(()));
@@ -409,6 +646,7 @@ pub mod test {
 
         let setup           = LsClientSetup::new(path.file_path().clone(),initial_content);
         setup.expect_invalidate(Err(RpcError::LostConnection));
+        setup.expect_reopen();
         setup.expect_invalidate(Ok(()));
         let connection                             = setup.finish();
         let mut test                               = TestWithLocalPoolExecutor::set_up();
@@ -430,4 +668,94 @@ pub mod test {
         });
         test.when_stalled(move || *module.borrow_mut() = None);
     }
+
+    #[wasm_bindgen_test]
+    fn handling_external_changes() {
+        let path            = model::module::Path::from_mock_module_name("TestModule");
+        let parser          = Parser::new_or_panic();
+        let initial_content = "main =\n    println \"Hello World!\"";
+        let external_code   = "main =\n    println \"External\"";
+        let conflicting     = "main =\n    println \"External 2\"";
+        let local_code      = "main =\n    println \"Local\"";
+
+        let setup = LsClientSetup::new(path.file_path().clone(),initial_content);
+        setup.expect_invalidate(Ok(()));
+        setup.expect_read(external_code);
+        setup.expect_invalidate(Ok(()));
+        setup.expect_invalidate(Ok(()));
+        setup.expect_read(conflicting);
+        let connection                             = setup.finish();
+        let mut test                               = TestWithLocalPoolExecutor::set_up();
+        let module:Rc<RefCell<Option<Rc<Module>>>> = default();
+        let changes:Rc<RefCell<Option<Subscriber<ExternalChange>>>> = default();
+        let (module_ref1,changes_ref1)             = (module.clone(),changes.clone());
+        let module_ref2                            = module.clone();
+        let (module_ref3,changes_ref3)             = (module.clone(),changes.clone());
+        let module_ref4                            = module.clone();
+        let module_ref5                            = module.clone();
+        test.run_task(async move {
+            let module = Module::open(path,connection,Parser::new_or_panic()).await.unwrap();
+            *changes_ref1.borrow_mut() = Some(module.subscribe_external_changes());
+            *module_ref1.borrow_mut()  = Some(module);
+        });
+        // The module has no unsaved changes, so it should be reloaded.
+        test.when_stalled(move || {
+            let module_ref = module_ref2.borrow();
+            module_ref.as_ref().unwrap().handle_file_event(FileEventKind::Modified);
+        });
+        test.when_stalled(move || {
+            let module_ref = module_ref3.borrow();
+            let module     = module_ref.as_ref().unwrap();
+            let change     = changes_ref3.borrow_mut().as_mut().unwrap().expect_next();
+            assert_eq!(change, ExternalChange::Reloaded);
+            assert_eq!(module.ast().repr(), external_code);
+            module.update_ast(parser.parse_module(local_code,default()).unwrap());
+        });
+        // Now the module has unsaved changes, so the conflict should be reported.
+        test.when_stalled(move || {
+            let module_ref = module_ref4.borrow();
+            module_ref.as_ref().unwrap().handle_file_event(FileEventKind::Modified);
+        });
+        test.when_stalled(move || {
+            let change       = changes.borrow_mut().as_mut().unwrap().expect_next();
+            let file_content = conflicting.to_string();
+            assert_eq!(change, ExternalChange::Conflict {file_content});
+            let module_ref = module_ref5.borrow();
+            assert_eq!(module_ref.as_ref().unwrap().ast().repr(), local_code);
+        });
+        test.when_stalled(move || *module.borrow_mut() = None);
+    }
+
+    #[wasm_bindgen_test]
+    fn handling_reconnection() {
+        let path            = model::module::Path::from_mock_module_name("TestModule");
+        let parser          = Parser::new_or_panic();
+        let initial_content = "main =\n    println \"Hello World!\"";
+        let new_content     = "main =\n    println \"Test\"";
+
+        let setup = LsClientSetup::new(path.file_path().clone(),initial_content);
+        setup.expect_invalidate(Ok(()));
+        // The content in Language Server is up to date, so no edit is expected after reopening.
+        setup.expect_open();
+        setup.expect_invalidate(Ok(()));
+        let connection                             = setup.finish();
+        let mut test                               = TestWithLocalPoolExecutor::set_up();
+        let module:Rc<RefCell<Option<Rc<Module>>>> = default();
+        let module_ref1                            = module.clone();
+        let module_ref2                            = module.clone();
+        let module_ref3                            = module.clone();
+        test.run_task(async move {
+            let module = Module::open(path,connection,Parser::new_or_panic()).await.unwrap();
+            *module_ref1.borrow_mut() = Some(module);
+        });
+        test.when_stalled(move || {
+            module_ref2.borrow().as_ref().unwrap().handle_reconnection();
+        });
+        test.when_stalled(move || {
+            let module_ref = module_ref3.borrow();
+            let module     = module_ref.as_ref().unwrap();
+            module.update_ast(parser.parse_module(new_content,default()).unwrap());
+        });
+        test.when_stalled(move || *module.borrow_mut() = None);
+    }
 }
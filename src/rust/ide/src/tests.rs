@@ -23,7 +23,7 @@ async fn failure_to_open_most_recent_project_is_reported() {
         let project_metadata = IdeInitializer::get_most_recent_project_or_create_new
             (&logger,&client,name).await.expect("Couldn't get most recent or create new project.");
         let client  = Rc::new(client);
-        let project = IdeInitializer::open_project(&logger,client,project_metadata);
+        let project = IdeInitializer::open_project(&logger,client,project_metadata,None);
         let project = project.await;
         project.expect_err("error should have been reported");
     });
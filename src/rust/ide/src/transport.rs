@@ -1,6 +1,10 @@
 //! Transport implementations used by the IDE.
 
+pub mod recording;
 pub mod web;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod native;
+
 #[cfg(test)]
 pub mod test_utils;
@@ -0,0 +1,221 @@
+//! Native (non-browser) WebSocket-based `Transport` implementation.
+//!
+//! Allows using the protocol clients outside the wasm environment, e.g. in command-line tools or
+//! integration tests running against a local server.
+
+use crate::prelude::*;
+
+use failure::Error;
+use futures::channel::mpsc;
+use json_rpc::Transport;
+use json_rpc::TransportEvent;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use websocket::ClientBuilder;
+use websocket::OwnedMessage;
+use websocket::sync::Reader;
+use websocket::sync::Writer;
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+/// Errors that may happen when trying to establish WebSocket connection.
+#[derive(Debug,Fail)]
+pub enum ConnectingError {
+    /// The given URL could not be parsed.
+    #[fail(display = "Invalid websocket URL: {}.", _0)]
+    InvalidUrl(#[cause] websocket::client::ParseError),
+    /// Failed to establish connection, e.g. due to the server being down.
+    #[fail(display = "Failed to establish connection: {}.", _0)]
+    FailedToConnect(#[cause] websocket::WebSocketError),
+}
+
+/// Error that may occur when attempting to send the data over WebSocket transport.
+#[derive(Debug,Fail)]
+pub enum SendingError {
+    /// The connection has been already closed.
+    #[fail(display = "Failed to send message because the connection is closed.")]
+    NotOpen,
+    /// Writing the message to the socket has failed.
+    #[fail(display = "Failed to send message: {}.", _0)]
+    FailedToSend(#[cause] websocket::WebSocketError),
+}
+
+
+
+// =================
+// === WebSocket ===
+// =================
+
+type SharedWriter = Arc<Mutex<Writer<TcpStream>>>;
+
+/// WebSocket connection over TCP, implementing `Transport`.
+///
+/// The incoming messages are read by a separate thread, started when the event transmitter is set.
+/// They are then passed to the transmitter as `TransportEvent`s, just like in the browser's
+/// WebSocket implementation.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct WebSocket {
+    #[allow(missing_docs)]
+    pub logger : Logger,
+    #[derivative(Debug="ignore")]
+    writer     : SharedWriter,
+    /// The reading half of the socket, until it is moved to the reading thread.
+    #[derivative(Debug="ignore")]
+    reader     : Option<Reader<TcpStream>>,
+    is_closed  : Arc<AtomicBool>,
+}
+
+impl WebSocket {
+    /// Establish connection with endpoint defined by the given URL and wrap it.
+    ///
+    /// Unlike the browser's implementation, the connection is established synchronously, so the
+    /// returned future blocks the thread until the handshake is done.
+    pub async fn new_opened(parent:Logger, url:impl Str) -> Result<WebSocket,ConnectingError> {
+        let logger          = Logger::sub(&parent,url.as_ref());
+        let builder         = ClientBuilder::new(url.as_ref());
+        let mut builder     = builder.map_err(ConnectingError::InvalidUrl)?;
+        let client          = builder.connect_insecure();
+        let client          = client.map_err(ConnectingError::FailedToConnect)?;
+        let split           = client.split();
+        let (reader,writer) = split.map_err(|e| ConnectingError::FailedToConnect(e.into()))?;
+        info!(logger, "Connection opened.");
+        let writer          = Arc::new(Mutex::new(writer));
+        let reader          = Some(reader);
+        let is_closed       = default();
+        Ok(WebSocket {logger,writer,reader,is_closed})
+    }
+
+    /// Send the message through the socket.
+    ///
+    /// Fails if the connection was already closed or if writing the message failed.
+    pub fn send_message(&mut self, message:&OwnedMessage) -> Result<(),Error> {
+        if self.is_closed.load(Ordering::SeqCst) {
+            Err(SendingError::NotOpen.into())
+        } else {
+            let mut writer = self.writer.lock().unwrap();
+            writer.send_message(message).map_err(|e| SendingError::FailedToSend(e).into())
+        }
+    }
+
+    /// The loop run by the reading thread. Ends when the connection is closed or broken.
+    fn read_messages
+    ( mut reader  : Reader<TcpStream>
+    , writer      : SharedWriter
+    , is_closed   : Arc<AtomicBool>
+    , transmitter : mpsc::UnboundedSender<TransportEvent>
+    ) {
+        let emit = |event| transmitter.unbounded_send(event).is_ok();
+        loop {
+            let keep_reading = match reader.recv_message() {
+                Ok(OwnedMessage::Text(text))        => emit(TransportEvent::TextMessage(text)),
+                Ok(OwnedMessage::Binary(data))      => emit(TransportEvent::BinaryMessage(data)),
+                Ok(OwnedMessage::Ping(data))        => {
+                    let pong = OwnedMessage::Pong(data);
+                    writer.lock().unwrap().send_message(&pong).is_ok()
+                }
+                Ok(OwnedMessage::Pong(_))           => true,
+                Ok(OwnedMessage::Close(_)) | Err(_) => false,
+            };
+            if !keep_reading {
+                break
+            }
+        }
+        is_closed.store(true,Ordering::SeqCst);
+        emit(TransportEvent::Closed);
+    }
+}
+
+impl Transport for WebSocket {
+    fn send_text(&mut self, message:&str) -> Result<(), Error> {
+        info!(self.logger, "Sending text message of length {message.len()}");
+        debug!(self.logger, "Message contents: {message}");
+        self.send_message(&OwnedMessage::Text(message.to_string()))
+    }
+
+    fn send_binary(&mut self, message:&[u8]) -> Result<(), Error> {
+        info!(self.logger, "Sending binary message of length {message.len()}");
+        self.logger.debug(|| format!("Message contents: {:x?}", message));
+        self.send_message(&OwnedMessage::Binary(message.to_vec()))
+    }
+
+    fn set_event_transmitter(&mut self, transmitter:mpsc::UnboundedSender<TransportEvent>) {
+        info!(self.logger,"Setting event transmitter.");
+        if let Some(reader) = self.reader.take() {
+            let writer    = self.writer.clone();
+            let is_closed = self.is_closed.clone();
+            std::thread::spawn(move || Self::read_messages(reader,writer,is_closed,transmitter));
+        } else {
+            error!(self.logger,"The event transmitter can be set only once.");
+        }
+    }
+}
+
+impl Drop for WebSocket {
+    fn drop(&mut self) {
+        // Shutting down the socket also ends the reading thread.
+        if let Err(err) = self.writer.lock().unwrap().shutdown_all() {
+            debug!(self.logger,"Failed to shut down the socket: {err}");
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use websocket::sync::Server;
+
+    #[test]
+    fn exchanging_messages() {
+        let server  = Server::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        let server  = std::thread::spawn(move || {
+            let mut server = server;
+            let connection = server.accept().ok().unwrap();
+            let mut client = connection.accept().ok().unwrap();
+            // Echo the two messages and close the connection.
+            for _ in 0..2 {
+                let message = client.recv_message().unwrap();
+                client.send_message(&message).unwrap();
+            }
+            client.send_message(&OwnedMessage::Close(None)).unwrap();
+        });
+
+        let url        = format!("ws://{}", address);
+        let connecting = WebSocket::new_opened(Logger::default(),url);
+        let mut ws     = futures::executor::block_on(connecting).unwrap();
+        let mut events = ws.establish_event_stream();
+        ws.send_text("text").unwrap();
+        ws.send_binary(&[1,2,3]).unwrap();
+
+        let mut next_event = || futures::executor::block_on(events.next());
+        match next_event() {
+            Some(TransportEvent::TextMessage(text)) => assert_eq!(text, "text"),
+            event                                   => panic!("Unexpected event: {:?}", event),
+        }
+        match next_event() {
+            Some(TransportEvent::BinaryMessage(data)) => assert_eq!(data, vec![1,2,3]),
+            event                                     => panic!("Unexpected event: {:?}", event),
+        }
+        match next_event() {
+            Some(TransportEvent::Closed) => {}
+            event                        => panic!("Unexpected event: {:?}", event),
+        }
+        server.join().unwrap();
+        assert!(ws.send_text("text").is_err());
+    }
+}
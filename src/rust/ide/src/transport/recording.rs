@@ -0,0 +1,86 @@
+//! Recording of the IDE's traffic with the Project Manager and the Language Server.
+//!
+//! When the IDE is started with the `record` argument, all its connections are recorded, and the
+//! recordings may be downloaded from the browser by calling the global `DOWNLOAD_FUNCTION_NAME`
+//! function, e.g. from the developer console. The downloaded files can be replayed with
+//! `json_rpc::transport::recording::ReplayTransport`.
+
+use crate::prelude::*;
+
+use ensogl_system_web as web;
+use json_rpc::Transport;
+use json_rpc::transport::recording::Recording;
+use json_rpc::transport::recording::RecordingTransport;
+use wasm_bindgen::JsCast;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The name of the global JS function downloading all the recordings.
+pub const DOWNLOAD_FUNCTION_NAME:&str = "downloadEnsoRecordings";
+
+
+
+// ==================
+// === Recordings ===
+// ==================
+
+/// The recordings of all the connections made by the IDE, each named after the connected service.
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct Recordings {
+    list : Rc<RefCell<Vec<(String,Recording)>>>,
+}
+
+impl Recordings {
+    /// Create an empty set of recordings.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Wrap the transport, so its traffic is recorded in a new recording with the given name. The
+    /// runner of the returned transport is scheduled in the global executor.
+    pub fn record<T:Transport>(&self, name:impl Str, transport:T) -> RecordingTransport<T> {
+        let recording     = Recording::new();
+        let mut transport = RecordingTransport::new(transport,recording.clone_ref());
+        executor::global::spawn(transport.runner());
+        self.list.borrow_mut().push((name.into(),recording));
+        transport
+    }
+
+    /// Start downloading each recording as a separate JSON lines file. The files are numbered in
+    /// the order of connecting, as there may be many connections to the same service.
+    pub fn download(&self) -> Result<(),JsValue> {
+        for (index,(name,recording)) in self.list.borrow().iter().enumerate() {
+            let file_name = format!("{}-{}.jsonl",index,name);
+            let contents  = js_sys::encode_uri_component(&recording.to_json_lines());
+            let contents  = String::from(contents);
+            let href      = format!("data:application/json;charset=utf-8,{}",contents);
+            let link      = web::create_element("a");
+            link.set_attribute("href",&href)?;
+            link.set_attribute("download",&file_name)?;
+            link.unchecked_into::<web::HtmlElement>().click();
+        }
+        Ok(())
+    }
+
+    /// Make the `download` method available in the browser as the global function named
+    /// `DOWNLOAD_FUNCTION_NAME`.
+    pub fn expose_in_browser(&self, logger:&Logger) -> Result<(),JsValue> {
+        let recordings = self.clone_ref();
+        let logger     = logger.clone_ref();
+        let download   = move || {
+            if let Err(error) = recordings.download() {
+                error!(logger,"Failed to download the recordings: {error:?}");
+            }
+        };
+        let closure = Closure::wrap(Box::new(download) as Box<dyn Fn()>);
+        let window  = web::window();
+        js_sys::Reflect::set(&window,&DOWNLOAD_FUNCTION_NAME.into(),closure.as_ref())?;
+        // The function is available for the whole IDE's lifetime.
+        closure.forget();
+        Ok(())
+    }
+}
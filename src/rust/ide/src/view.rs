@@ -1,6 +1,7 @@
 //! A module containing view components.
 
 pub mod node_editor;
+pub mod entry_point_picker;
 pub mod layout;
 pub mod node_searcher;
 pub mod project;
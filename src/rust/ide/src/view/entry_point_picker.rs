@@ -0,0 +1,184 @@
+//! The picker of the entry point executed in a node editor.
+//!
+//! The picker lists the entry points of the module displayed in the node editor and lets the user
+//! type a call expression like `foo 1 (bar 2)`, calling one of them with the given arguments.
+
+use crate::prelude::*;
+
+use crate::model::execution_context::EntryPoint;
+use crate::view::node_editor::NodeEditor;
+
+use enso_protocol::language_server::MethodPointer;
+use ensogl::data::color;
+use ensogl::display;
+use ensogl::display::Scene;
+use ensogl::display::shape::text::glyph::font;
+use ensogl::display::shape::text::text_field::FocusManager;
+use ensogl::display::shape::text::text_field::TextField;
+use ensogl::display::shape::text::text_field::TextFieldProperties;
+use ensogl::traits::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const TEXT_SIZE : f32 = 16.0;
+
+
+
+// ========================
+// === EntryPointPicker ===
+// ========================
+
+/// A text field for the call expression of the entry point, with the list of the available entry
+/// points displayed above it.
+#[derive(Clone,Debug,CloneRef)]
+pub struct EntryPointPicker {
+    display_object : display::object::Instance,
+    project        : Rc<model::Project>,
+    /// The node editor which entry point is picked. Set when the picker is shown.
+    node_editor    : Rc<CloneCell<Option<NodeEditor>>>,
+    entry_points   : Rc<RefCell<Vec<MethodPointer>>>,
+    list           : TextField,
+    text_field     : TextField,
+    logger         : Logger,
+}
+
+impl EntryPointPicker {
+    /// Constructor.
+    pub fn new<'t,S:Into<&'t Scene>>
+    ( scene         : S
+    , logger        : impl AnyLogger
+    , fonts         : &mut font::Registry
+    , focus_manager : &FocusManager
+    , project       : Rc<model::Project>)
+    -> Self {
+        let scene          = scene.into();
+        let screen         = scene.camera().screen();
+        let logger         = Logger::sub(logger,"EntryPointPicker");
+        let display_object = display::object::Instance::new(&logger);
+        let font           = fonts.get_or_load_embedded_font("DejaVuSansMono").unwrap();
+        let properties     = |alpha| TextFieldProperties {
+            font       : font.clone(),
+            text_size  : TEXT_SIZE,
+            base_color : color::Rgba::new(1.0, 1.0, 1.0, alpha),
+            size       : Vector2::new(screen.width,TEXT_SIZE),
+        };
+        let mut list     = TextField::new(scene,properties(0.5),focus_manager);
+        let text_field   = TextField::new(scene,properties(0.7),focus_manager);
+        let node_editor  = default();
+        let entry_points = default();
+        list.set_position(Vector3::new(0.0,TEXT_SIZE * 1.5,0.0));
+        let picker = EntryPointPicker {display_object,project,node_editor,entry_points,list,
+            text_field,logger};
+        picker.initialize()
+    }
+
+    fn initialize(self) -> Self {
+        let mut picker = self.clone_ref();
+        self.text_field.set_text_edit_callback(move |change| {
+            // If the text edit callback is called, the TextEdit must be still alive.
+            let field_content = picker.text_field.get_content();
+            let expression    = field_content.split('\n').next().unwrap().to_string();
+            if change.inserted == "\n" {
+                picker.pick(&expression);
+            } else {
+                // Keep only one line.
+                picker.text_field.set_content(&expression);
+            }
+        });
+        self
+    }
+
+    /// Show the picker for the given node editor, with its current entry point's call expression
+    /// in the text field.
+    pub fn show(&mut self, node_editor:NodeEditor) {
+        if !self.is_shown() {
+            //FIXME:Use add_child(&text_field) when replaced by TextField 2.0
+            self.display_object.add_child(&self.list.display_object());
+            self.display_object.add_child(&self.text_field.display_object());
+            let entry_point = node_editor.graph.controller().entry_point();
+            self.text_field.set_content(&entry_point.call_expression());
+            self.text_field.set_focus();
+            self.list.set_content("Loading entry points...");
+            self.node_editor.set(Some(node_editor));
+            self.load_entry_points(entry_point);
+        }
+    }
+
+    /// Hide the picker if it is visible.
+    pub fn hide(&mut self) {
+        if self.is_shown() {
+            self.text_field.clear_content();
+            self.list.clear_content();
+            self.node_editor.set(None);
+            self.entry_points.borrow_mut().clear();
+            //FIXME:Use remove_child(&text_field) when replaced by TextField 2.0
+            self.display_object.remove_child(&self.list.display_object());
+            self.display_object.remove_child(&self.text_field.display_object());
+        }
+    }
+
+    /// Check if the picker is visible.
+    pub fn is_shown(&self) -> bool {
+        self.text_field.display_object().has_parent()
+    }
+
+    fn load_entry_points(&self, current:EntryPoint) {
+        let project      = self.project.clone_ref();
+        let entry_points = self.entry_points.clone_ref();
+        let mut list     = self.list.clone_ref();
+        let logger       = self.logger.clone_ref();
+        executor::global::spawn(async move {
+            let file    = current.method.file;
+            let methods = match model::module::Path::from_file_path(file) {
+                Ok(path) => project.entry_points(path).await,
+                Err(err) => Err(err.into()),
+            };
+            match methods {
+                Ok(methods) => {
+                    let names = methods.iter().map(|method| method.name.as_str()).join(", ");
+                    list.set_content(&iformat!("Entry points: {names}"));
+                    *entry_points.borrow_mut() = methods;
+                }
+                Err(err) => {
+                    error!(logger,"Failed to list the entry points: {err}");
+                    list.set_content("Failed to list the entry points.");
+                }
+            }
+        });
+    }
+
+    /// Switch the node editor to the entry point called by the expression. The picker stays
+    /// visible if the expression does not call any of the listed entry points.
+    fn pick(&mut self, expression:&str) {
+        let parser       = &self.project.parser;
+        let entry_points = self.entry_points.borrow().clone();
+        match EntryPoint::from_call_expression(parser,expression,&entry_points) {
+            Ok(entry_point) => {
+                if let Some(node_editor) = self.node_editor.get() {
+                    let controller = node_editor.graph.controller().clone_ref();
+                    let logger     = self.logger.clone_ref();
+                    executor::global::spawn(async move {
+                        if let Err(err) = controller.set_entry_point(entry_point).await {
+                            error!(logger,"Failed to switch the entry point: {err}");
+                        }
+                    });
+                }
+                self.hide();
+            }
+            Err(err) => {
+                warning!(self.logger,"Cannot pick the entry point `{expression}`: {err}");
+                self.text_field.set_content(expression);
+            }
+        }
+    }
+}
+
+impl display::Object for EntryPointPicker {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.display_object
+    }
+}
@@ -1,8 +1,10 @@
 //! This module contains implementation of ViewLayout with a single TextEditor temporarily
-//! occupying half bottom of the screen as the default layout.
+//! occupying half bottom of the screen as the default layout. The screen is split into columns
+//! between the node editors of all opened execution contexts.
 
 use crate::prelude::*;
 
+use crate::view::entry_point_picker::EntryPointPicker;
 use crate::view::temporary_panel::TemporaryPadding;
 use crate::view::temporary_panel::TemporaryPanel;
 use crate::view::text_editor::TextEditor;
@@ -13,9 +15,11 @@ use enso_callback as callback;
 use enso_frp as frp;
 use frp::io::keyboard;
 use ensogl::application::Application;
+use ensogl::control::io::mouse;
 use ensogl::display::shape::text::glyph::font;
 use ensogl::display::traits::*;
 use ensogl::display::world::World;
+use ide_view::graph_editor::ClippingArea;
 use nalgebra::Vector2;
 use nalgebra::zero;
 use std::cell::RefCell;
@@ -23,23 +27,192 @@ use std::rc::Rc;
 
 
 
+// =================
+// === Constants ===
+// =================
+
+/// The minimal zoom of a split's camera, relative to the zoom fitting the screen in the split.
+pub const MIN_ZOOM : f32 = 0.1;
+/// The maximal zoom of a split's camera, relative to the zoom fitting the screen in the split.
+pub const MAX_ZOOM : f32 = 10.0;
+/// The zoom change for each pixel scrolled with the mouse wheel.
+const ZOOM_SPEED : f32 = 0.002;
+
+
+
+// ================
+// === Viewport ===
+// ================
+
+/// The part of the scene occupied by a single split.
+///
+/// The screen is divided into columns of equal width, one for each split. By default, the whole
+/// node editor screen is scaled down to fit into its column.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Viewport {
+    /// The bottom left corner, in the scene coordinates.
+    pub min   : Vector2<f32>,
+    /// The top right corner, in the scene coordinates.
+    pub max   : Vector2<f32>,
+    /// The scale fitting the whole screen into the viewport.
+    pub scale : f32,
+}
+
+impl Viewport {
+    /// The viewport of the split with the given index, when there are `count` splits.
+    pub fn new(screen_size:Vector2<f32>, index:usize, count:usize) -> Self {
+        let count = count.max(1) as f32;
+        let width = screen_size.x / count;
+        let left  = -screen_size.x / 2.0 + index as f32 * width;
+        let min   = Vector2::new(left,-screen_size.y / 2.0);
+        let max   = Vector2::new(left + width,screen_size.y / 2.0);
+        let scale = 1.0 / count;
+        Viewport {min,max,scale}
+    }
+
+    /// The center of the viewport, in the scene coordinates.
+    pub fn center(&self) -> Vector2<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Check whether the scene point is inside the viewport. The right border belongs to the
+    /// viewport of the next split.
+    pub fn contains(&self, point:Vector2<f32>) -> bool {
+        let inside_x = self.min.x <= point.x && point.x < self.max.x;
+        let inside_y = self.min.y <= point.y && point.y <= self.max.y;
+        inside_x && inside_y
+    }
+
+    /// The scale of the node editor seen through the camera.
+    pub fn node_editor_scale(&self, camera:&Camera) -> f32 {
+        self.scale * camera.zoom
+    }
+
+    /// The position of the node editor seen through the camera, in the scene coordinates.
+    pub fn node_editor_position(&self, camera:&Camera) -> Vector2<f32> {
+        self.center() - camera.position * self.node_editor_scale(camera)
+    }
+
+    /// Convert the scene point to the coordinates of the node editor seen through the camera.
+    pub fn to_node_editor(&self, camera:&Camera, point:Vector2<f32>) -> Vector2<f32> {
+        (point - self.node_editor_position(camera)) / self.node_editor_scale(camera)
+    }
+
+    /// The part of the node editor seen through the camera, in the node editor's coordinates.
+    pub fn clipping_area(&self, camera:&Camera) -> ClippingArea {
+        let min = self.to_node_editor(camera,self.min);
+        let max = self.to_node_editor(camera,self.max);
+        ClippingArea::new(min,max)
+    }
+}
+
+
+
+// ==============
+// === Camera ===
+// ==============
+
+/// The camera of a single split, navigated independently from the other splits.
+///
+/// All the node editors are displayed in the same scene, so the camera is realised by moving and
+/// scaling the node editor in its viewport.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Camera {
+    /// The node editor's point displayed in the center of the viewport.
+    pub position : Vector2<f32>,
+    /// The zoom relative to the viewport's scale.
+    pub zoom     : f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        let position = zero();
+        let zoom     = 1.0;
+        Camera {position,zoom}
+    }
+}
+
+impl Camera {
+    /// Move the camera by the given distance in the scene coordinates.
+    pub fn pan_by(&mut self, viewport:&Viewport, distance:Vector2<f32>) {
+        self.position += distance / viewport.node_editor_scale(self);
+    }
+
+    /// Multiply the zoom by the given factor, keeping the given scene point in place.
+    pub fn zoom_by(&mut self, viewport:&Viewport, factor:f32, focus:Vector2<f32>) {
+        let focus_before = viewport.to_node_editor(self,focus);
+        self.zoom        = (self.zoom * factor).max(MIN_ZOOM).min(MAX_ZOOM);
+        let focus_after  = viewport.to_node_editor(self,focus);
+        self.position   += focus_before - focus_after;
+    }
+}
+
+
+
+// =============
+// === Split ===
+// =============
+
+/// A node editor displayed in its own viewport, with its own camera.
+#[derive(Debug)]
+struct Split {
+    node_editor : NodeEditor,
+    camera      : Camera,
+}
+
+impl Split {
+    fn new(node_editor:NodeEditor) -> Self {
+        let camera = default();
+        Split {node_editor,camera}
+    }
+
+    /// Place the node editor in the viewport and hide the elements positioned outside of it. See
+    /// `ClippingArea`.
+    fn update(&self, viewport:&Viewport) {
+        let position = viewport.node_editor_position(&self.camera);
+        let scale    = viewport.node_editor_scale(&self.camera);
+        let area     = viewport.clipping_area(&self.camera);
+        self.node_editor.set_position(Vector3::new(position.x,position.y,0.0));
+        self.node_editor.set_scale(Vector3::new(scale,scale,1.0));
+        self.node_editor.graph.graph_editor().frp.set_clipping_area.emit(Some(area));
+    }
+
+    /// Route the keyboard and mouse input to the node editor only when it is active.
+    fn set_active(&self, active:bool) {
+        self.node_editor.graph.graph_editor().frp.set_active.emit(active);
+    }
+}
+
+
+
 // ==================
 // === ViewLayout ===
 // ==================
 
 shared! { ViewLayout
 
-/// Initial implementation of ViewLayout with a TextEditor and NodeEditor.
+/// Initial implementation of ViewLayout with a TextEditor and NodeEditors.
 #[derive(Debug)]
 pub struct ViewLayoutData {
     network                   : frp::Network,
     mouse_position_sampler    : frp::Sampler<Vector2<f32>>,
     text_editor               : TextEditor,
-    node_editor               : NodeEditor,
+    /// Node editors displayed side by side, each with its own execution context. The first one is
+    /// the main editor, which is never closed and is used by the node searcher.
+    splits                    : Vec<Split>,
+    /// The index of the split under the mouse cursor, which receives the input.
+    focused_split             : usize,
     node_searcher             : NodeSearcher,
+    entry_point_picker        : EntryPointPicker,
     size                      : Vector2<f32>,
     logger                    : Logger,
-    node_searcher_show_action : Option<callback::Handle>
+    node_searcher_show_action : Option<callback::Handle>,
+    /// Actions managing the splits and their entry points.
+    split_actions             : Vec<callback::Handle>,
+    wheel_action              : Option<callback::Handle>,
+    application               : Application,
+    project                   : Rc<model::Project>,
+    visualization_controller  : controller::Visualization,
 }
 
 impl {
@@ -49,6 +222,65 @@ impl {
         self.size = size;
         self.recalculate_layout();
     }
+
+    /// The node editor opened with the layout.
+    pub fn main_node_editor(&self) -> NodeEditor {
+        self.splits[0].node_editor.clone_ref()
+    }
+
+    /// The node editor under the mouse cursor, which receives the input.
+    pub fn focused_node_editor(&self) -> NodeEditor {
+        self.splits[self.focused_split].node_editor.clone_ref()
+    }
+
+    /// All the opened node editors, from left to right.
+    pub fn node_editors(&self) -> Vec<NodeEditor> {
+        self.splits.iter().map(|split| split.node_editor.clone_ref()).collect()
+    }
+
+    /// Close the most recently opened split, dropping its execution context and removing it from
+    /// the project metadata.
+    ///
+    /// The main node editor is never closed. Returns false if there was no split to close.
+    pub fn close_split(&mut self) -> bool {
+        let has_split = self.splits.len() > 1;
+        if has_split {
+            if let Some(split) = self.splits.pop() {
+                split.node_editor.unset_parent();
+                let controller = split.node_editor.graph.controller().clone_ref();
+                executor::global::spawn(async move { controller.remove_from_metadata().await });
+            }
+            self.focused_split = self.focused_split.min(self.splits.len() - 1);
+            self.recalculate_layout();
+        }
+        has_split
+    }
+
+    /// Show the picker of the focused node editor's entry point at the mouse cursor, or hide it if
+    /// it is already visible.
+    pub fn toggle_entry_point_picker(&mut self) {
+        if self.entry_point_picker.is_shown() {
+            self.entry_point_picker.hide();
+        } else {
+            let position    = *self.mouse_position_sampler.value();
+            let node_editor = self.focused_node_editor();
+            self.entry_point_picker.set_position(Vector3::new(position.x,position.y,0.0));
+            self.entry_point_picker.show(node_editor);
+        }
+    }
+
+    /// Focus the split displayed at the given scene point, so only its node editor receives the
+    /// input.
+    pub fn focus_split_at(&mut self, point:Vector2<f32>) {
+        let count = self.splits.len();
+        let index = (0..count).find(|index| self.viewport(*index).contains(point));
+        if let Some(index) = index {
+            if index != self.focused_split {
+                self.focused_split = index;
+                self.update_focus();
+            }
+        }
+    }
 }}
 
 
@@ -58,9 +290,44 @@ impl ViewLayoutData {
 
     fn recalculate_layout(&mut self) {
         self.update_text_editor();
+        self.update_node_editors();
+        self.update_focus();
         self.update_node_searcher();
     }
 
+    fn viewport(&self, index:usize) -> Viewport {
+        Viewport::new(self.size,index,self.splits.len())
+    }
+
+    fn update_node_editors(&mut self) {
+        for (index,split) in self.splits.iter().enumerate() {
+            split.update(&self.viewport(index));
+        }
+    }
+
+    fn update_focus(&self) {
+        for (index,split) in self.splits.iter().enumerate() {
+            split.set_active(index == self.focused_split);
+        }
+    }
+
+    /// Pan the camera of the focused split with the mouse wheel, or zoom it if the control key is
+    /// pressed.
+    fn navigate_focused_split(&mut self, event:&mouse::OnWheel) {
+        let viewport = self.viewport(self.focused_split);
+        let position = *self.mouse_position_sampler.value();
+        if let Some(split) = self.splits.get_mut(self.focused_split) {
+            if event.ctrl_key() {
+                let factor = (-event.delta_y() as f32 * ZOOM_SPEED).exp();
+                split.camera.zoom_by(&viewport,factor,position);
+            } else {
+                let distance = Vector2::new(event.delta_x() as f32,-event.delta_y() as f32);
+                split.camera.pan_by(&viewport,distance);
+            }
+            split.update(&viewport);
+        }
+    }
+
     fn update_text_editor(&mut self) {
         let screen_size = self.size;
         let position    = Vector2::new(-screen_size.x / 2.0,0.0);
@@ -102,25 +369,63 @@ impl ViewLayout {
         let focus_manager = world.text_field_focus_manager();
         let text_editor   = TextEditor::new
             (&logger,scene,text_controller,kb_actions,fonts,focus_manager);
+        let visualization = visualization_controller.clone_ref();
         let node_editor   = NodeEditor::new
-            (&logger,application,graph_controller,project.clone_ref(),visualization_controller);
+            (&logger,application,graph_controller,project.clone_ref(),visualization);
         let node_editor   = node_editor.await?;
         let node_searcher = NodeSearcher::new
-            (scene,&logger,node_editor.clone_ref(),fonts,focus_manager,project);
+            (scene,&logger,node_editor.clone_ref(),fonts,focus_manager,project.clone_ref());
+        let entry_point_picker = EntryPointPicker::new
+            (scene,&logger,fonts,focus_manager,project.clone_ref());
         world.add_child(&node_editor);
         world.add_child(&text_editor.display_object());
         world.add_child(&node_searcher);
+        world.add_child(&entry_point_picker);
         let size  = zero();
         let scene = world.scene();
         let mouse = &scene.mouse.frp;
         frp::new_network! { network def mouse_position_sampler = mouse.position.sampler(); }
         let node_searcher_show_action = None;
-        let data = ViewLayoutData{network,text_editor,node_editor,node_searcher,size,logger,
-            node_searcher_show_action,mouse_position_sampler};
+        let split_actions             = default();
+        let wheel_action              = None;
+        let splits                    = vec![Split::new(node_editor)];
+        let focused_split             = 0;
+        let application               = application.clone_ref();
+        let data = ViewLayoutData{network,text_editor,splits,focused_split,node_searcher,
+            entry_point_picker,size,logger,node_searcher_show_action,split_actions,wheel_action,
+            mouse_position_sampler,application,project,visualization_controller};
         let rc   = Rc::new(RefCell::new(data));
         Ok(Self {rc}.init(world,kb_actions))
     }
 
+    fn init_mouse(self, world:&World) -> Self {
+        let scene   = world.scene();
+        let mouse   = &scene.mouse.frp;
+        let network = self.with_borrowed(|data| data.network.clone_ref());
+        let weak    = self.downgrade();
+        frp::extend! { network
+            eval mouse.position ([weak](position) {
+                if let Some(layout) = weak.upgrade() {
+                    layout.focus_split_at(*position);
+                }
+            });
+        }
+        let weak          = self.downgrade();
+        let wheel_capture = scene.mouse.wheel_capture.clone_ref();
+        let wheel_action  = scene.mouse.mouse_manager.on_wheel.add(move |event:&mouse::OnWheel| {
+            // The wheel events captured by a visualization scroll or zoom its content instead.
+            if wheel_capture.is_captured() {
+                return
+            }
+            event.prevent_default();
+            if let Some(layout) = weak.upgrade() {
+                layout.with_borrowed(|data| data.navigate_focused_split(event));
+            }
+        });
+        self.with_borrowed(|data| data.wheel_action = Some(wheel_action));
+        self
+    }
+
     fn init_keyboard(self, keyboard_actions:&mut keyboard::Actions) -> Self {
         // TODO[ao] add here some useful staff (quitting project for example)
         let layout                    = self.rc.clone_ref();
@@ -135,13 +440,135 @@ impl ViewLayout {
             layout.node_searcher.show();
         });
         self.rc.borrow_mut().node_searcher_show_action = Some(node_searcher_show_action);
+        self.init_split_keyboard(keyboard_actions)
+    }
+
+    fn init_split_keyboard(self, keyboard_actions:&mut keyboard::Actions) -> Self {
+        let weak               = self.downgrade();
+        let keys               = &[keyboard::Key::Control,keyboard::Key::Alt,
+            keyboard::Key::Character("\\".into())];
+        let close_split_action = keyboard_actions.add_action(keys, move || {
+            if let Some(layout) = weak.upgrade() {
+                layout.close_split();
+            }
+        });
+        self.with_borrowed(|data| data.split_actions = vec![close_split_action]);
+        self.init_entry_point_keyboard(keyboard_actions)
+    }
+
+    fn init_entry_point_keyboard(self, keyboard_actions:&mut keyboard::Actions) -> Self {
+        let weak                    = self.downgrade();
+        let key                     = keyboard::Key::Character("e".into());
+        let keys                    = &[keyboard::Key::Control,key];
+        let pick_entry_point_action = keyboard_actions.add_action(keys, move || {
+            if let Some(layout) = weak.upgrade() {
+                layout.toggle_entry_point_picker();
+            }
+        });
+        self.with_borrowed(|data| data.split_actions.push(pick_entry_point_action));
         self
     }
 
+    /// Open a new split displaying the graph of the given controller next to the already opened
+    /// node editors.
+    ///
+    /// Each split has its own execution context, so its computed values and visualizations are
+    /// independent from the other splits.
+    pub async fn open_split(&self, graph_controller:controller::ExecutedGraph)
+    -> FallibleResult<()> {
+        let (logger,application,project,visualization_controller) = self.with_borrowed(|data| {
+            let logger                   = data.logger.clone_ref();
+            let application              = data.application.clone_ref();
+            let project                  = data.project.clone_ref();
+            let visualization_controller = data.visualization_controller.clone_ref();
+            (logger,application,project,visualization_controller)
+        });
+        let node_editor = NodeEditor::new
+            (&logger,&application,graph_controller,project,visualization_controller).await?;
+        application.display.add_child(&node_editor);
+        self.with_borrowed(move |data| {
+            data.splits.push(Split::new(node_editor));
+            data.recalculate_layout();
+        });
+        Ok(())
+    }
+
     fn init(self, world:&World, keyboard_actions:&mut keyboard::Actions) -> Self {
         let screen = world.scene().camera().screen();
         let size   = Vector2::new(screen.width,screen.height);
         self.set_size(size);
-        self.init_keyboard(keyboard_actions)
+        self.init_mouse(world).init_keyboard(keyboard_actions)
+    }
+}
+
+
+
+// ============
+// === Test ===
+// ============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn assert_close(left:Vector2<f32>, right:Vector2<f32>) {
+        assert!((left - right).norm() < 1e-4, "{:?} != {:?}", left, right);
+    }
+
+    #[wasm_bindgen_test]
+    fn splitting_screen_into_viewports() {
+        let screen    = Vector2::new(900.0,600.0);
+        let viewports = (0..3).map(|index| Viewport::new(screen,index,3)).collect_vec();
+        assert_close(viewports[0].min, Vector2::new(-450.0,-300.0));
+        assert_close(viewports[0].max, Vector2::new(-150.0,300.0));
+        assert_close(viewports[2].max, Vector2::new(450.0,300.0));
+        assert_close(viewports[1].center(), Vector2::new(0.0,0.0));
+        assert!(viewports[1].contains(Vector2::new(-150.0,0.0)));
+        assert!(!viewports[0].contains(Vector2::new(-150.0,0.0)));
+        assert!(!viewports[2].contains(Vector2::new(500.0,0.0)));
+
+        // With the default camera, the whole screen of each node editor fits into its viewport, so
+        // the splits do not overlap.
+        let camera = Camera::default();
+        for viewport in &viewports {
+            let area = viewport.clipping_area(&camera);
+            assert_close(area.min, Vector2::new(-450.0,-900.0));
+            assert_close(area.max, Vector2::new(450.0,900.0));
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn single_viewport_covers_screen() {
+        let screen   = Vector2::new(800.0,600.0);
+        let viewport = Viewport::new(screen,0,1);
+        let camera   = Camera::default();
+        assert_close(viewport.node_editor_position(&camera), zero());
+        assert_eq!(viewport.node_editor_scale(&camera), 1.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn navigating_camera() {
+        let screen     = Vector2::new(800.0,600.0);
+        let viewport   = Viewport::new(screen,1,2);
+        let mut camera = Camera::default();
+
+        camera.pan_by(&viewport,Vector2::new(10.0,-20.0));
+        assert_close(camera.position, Vector2::new(20.0,-40.0));
+        let area = viewport.clipping_area(&camera);
+        assert_close(area.min, Vector2::new(-380.0,-640.0));
+        assert_close(area.max, Vector2::new(420.0,560.0));
+
+        let focus        = Vector2::new(250.0,100.0);
+        let focus_before = viewport.to_node_editor(&camera,focus);
+        camera.zoom_by(&viewport,2.0,focus);
+        assert_eq!(camera.zoom, 2.0);
+        assert_close(viewport.to_node_editor(&camera,focus), focus_before);
+
+        camera.zoom_by(&viewport,1000.0,focus);
+        assert_eq!(camera.zoom, MAX_ZOOM);
+        camera.zoom_by(&viewport,0.0,focus);
+        assert_eq!(camera.zoom, MIN_ZOOM);
     }
 }
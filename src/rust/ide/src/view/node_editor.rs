@@ -4,6 +4,8 @@ use crate::prelude::*;
 
 use crate::controller::graph::NodeTrees;
 use crate::model::execution_context::ComputedValueInfo;
+use crate::model::execution_context::ExecutionStatus;
+use crate::model::execution_context::ExpressionError;
 use crate::model::execution_context::ExpressionId;
 use crate::model::execution_context::Visualization;
 use crate::model::execution_context::VisualizationId;
@@ -16,6 +18,7 @@ use ensogl::display;
 use ensogl::display::traits::*;
 use ensogl::application::Application;
 use ide_view::graph_editor;
+use ide_view::graph_editor::component::node;
 use ide_view::graph_editor::component::visualization;
 use ide_view::graph_editor::EdgeTarget;
 use ide_view::graph_editor::GraphEditor;
@@ -143,14 +146,15 @@ impl GraphEditorIntegratedWithController {
 
 #[derive(Debug)]
 struct GraphEditorIntegratedWithControllerModel {
-    logger             : Logger,
-    editor             : GraphEditor,
-    controller         : controller::ExecutedGraph,
-    project            : Rc<model::Project>,
-    node_views         : RefCell<BiMap<ast::Id,graph_editor::NodeId>>,
-    expression_views   : RefCell<HashMap<graph_editor::NodeId,String>>,
-    connection_views   : RefCell<BiMap<controller::graph::Connection,graph_editor::EdgeId>>,
-    visualizations     : SharedHashMap<graph_editor::NodeId,VisualizationId>,
+    logger              : Logger,
+    editor              : GraphEditor,
+    controller          : controller::ExecutedGraph,
+    project             : Rc<model::Project>,
+    node_views          : RefCell<BiMap<ast::Id,graph_editor::NodeId>>,
+    expression_views    : RefCell<HashMap<graph_editor::NodeId,String>>,
+    visualization_views : RefCell<HashMap<graph_editor::NodeId,visualization::Path>>,
+    connection_views    : RefCell<BiMap<controller::graph::Connection,graph_editor::EdgeId>>,
+    visualizations      : SharedHashMap<graph_editor::NodeId,VisualizationId>,
 }
 
 
@@ -193,6 +197,9 @@ impl GraphEditorIntegratedWithController {
             GraphEditorIntegratedWithControllerModel::node_entered_in_ui,&invalidate.trigger);
         let node_exited = Self::ui_action(&model,
             GraphEditorIntegratedWithControllerModel::node_exited_in_ui,&invalidate.trigger);
+        let breadcrumb_selected = Self::ui_action(&model,
+            GraphEditorIntegratedWithControllerModel::breadcrumb_selected_in_ui,
+            &invalidate.trigger);
         let connection_created = Self::ui_action(&model,
             GraphEditorIntegratedWithControllerModel::connection_created_in_ui,&invalidate.trigger);
         let connection_removed = Self::ui_action(&model,
@@ -205,6 +212,13 @@ impl GraphEditorIntegratedWithController {
         let visualization_disabled = Self::ui_action(&model,
             GraphEditorIntegratedWithControllerModel::visualization_disabled_in_ui,
             &invalidate.trigger);
+        let visualization_chosen = Self::ui_action(&model,
+            GraphEditorIntegratedWithControllerModel::visualization_chosen_in_ui,
+            &invalidate.trigger);
+        let undo = Self::ui_action(&model,
+            GraphEditorIntegratedWithControllerModel::undo_in_ui,&invalidate.trigger);
+        let redo = Self::ui_action(&model,
+            GraphEditorIntegratedWithControllerModel::redo_in_ui,&invalidate.trigger);
         frp::extend! {network
             // Notifications from controller
             let handle_notification = FencedAction::fence(&network,
@@ -218,11 +232,15 @@ impl GraphEditorIntegratedWithController {
             _action <- editor_outs.node_removed             .map2(&is_hold,node_removed);
             _action <- editor_outs.node_entered             .map2(&is_hold,node_entered);
             _action <- editor_outs.node_exited              .map2(&is_hold,node_exited);
+            _action <- editor_outs.breadcrumb_selected      .map2(&is_hold,breadcrumb_selected);
             _action <- editor_outs.connection_added         .map2(&is_hold,connection_created);
             _action <- editor_outs.visualization_enabled    .map2(&is_hold,visualization_enabled);
             _action <- editor_outs.visualization_disabled   .map2(&is_hold,visualization_disabled);
+            _action <- editor_outs.visualization_chosen     .map2(&is_hold,visualization_chosen);
             _action <- editor_outs.connection_removed       .map2(&is_hold,connection_removed);
             _action <- editor_outs.node_position_set_batched.map2(&is_hold,node_moved);
+            _action <- editor_outs.undo_requested           .map2(&is_hold,undo);
+            _action <- editor_outs.redo_requested           .map2(&is_hold,redo);
         }
         Self::connect_frp_to_controller_notifications(&model,handle_notification.trigger);
         Self {model,network}
@@ -247,6 +265,9 @@ impl GraphEditorIntegratedWithController {
     /// suitable for connecting to GraphEditor frp network. Returned lambda takes `Parameter` and a
     /// bool, which indicates if this action is currently on hold (e.g. due to performing
     /// invalidation).
+    ///
+    /// All the actions performed during handling of a single UI event (e.g. removing all selected
+    /// nodes) are grouped in one module transaction, so they will be undone together.
     fn ui_action<Action,Parameter>
     ( model      : &Rc<GraphEditorIntegratedWithControllerModel>
     , action     : Action
@@ -256,6 +277,7 @@ impl GraphEditorIntegratedWithController {
             -> FallibleResult<()> + 'static {
         f!([model,invalidate] (parameter,is_hold) {
             if !*is_hold {
+                model.group_edits_until_event_handled();
                 let result = action(&*model,parameter);
                 if let Err(err) = result {
                     error!(model.logger,"Error while performing UI action on controllers: {err}");
@@ -273,18 +295,20 @@ impl GraphEditorIntegratedWithControllerModel {
     , app        : &Application
     , controller : controller::ExecutedGraph
     , project    : Rc<model::Project>) -> Self {
-        let editor           = app.new_view::<GraphEditor>();
-        let node_views       = default();
-        let connection_views = default();
-        let expression_views = default();
-        let visualizations   = default();
+        let editor              = app.new_view::<GraphEditor>();
+        let node_views          = default();
+        let connection_views    = default();
+        let expression_views    = default();
+        let visualization_views = default();
+        let visualizations      = default();
         let this = GraphEditorIntegratedWithControllerModel {editor,controller,node_views,
-            expression_views,connection_views,logger,visualizations,project
+            expression_views,visualization_views,connection_views,logger,visualizations,project
         };
 
         if let Err(err) = this.refresh_graph_view() {
             error!(this.logger,"Error while initializing graph editor: {err}.");
         }
+        this.refresh_breadcrumbs();
         this
     }
 }
@@ -313,6 +337,16 @@ impl GraphEditorIntegratedWithControllerModel {
 // === Updating Graph View ===
 
 impl GraphEditorIntegratedWithControllerModel {
+    /// Display the breadcrumbs of the execution context's stack: the entry point's method followed
+    /// by the methods of all the entered local calls.
+    pub fn refresh_breadcrumbs(&self) {
+        let entry_point = self.controller.entry_point().method;
+        let call_stack  = self.controller.call_stack().into_iter().map(|call| call.definition);
+        let methods     = std::iter::once(entry_point).chain(call_stack);
+        let methods     = methods.map(|method| graph_editor::MethodPointer(Rc::new(method)));
+        self.editor.frp.set_breadcrumbs.emit_event(&methods.collect_vec());
+    }
+
     /// Reload whole displayed content to be up to date with module state.
     pub fn refresh_graph_view(&self) -> FallibleResult<()> {
         info!(self.logger, "Refreshing the graph view.");
@@ -320,9 +354,18 @@ impl GraphEditorIntegratedWithControllerModel {
         let Connections{trees,connections} = self.controller.graph().connections()?;
         self.refresh_node_views(trees)?;
         self.refresh_connection_views(connections)?;
+        self.refresh_warning();
+        self.display_execution_status(&self.controller.execution_status());
         Ok(())
     }
 
+    /// Display the warning about module's malformed metadata, or hide it if the metadata are fine.
+    fn refresh_warning(&self) {
+        let warning = self.controller.graph().module.metadata_warning();
+        let message = warning.map(|warning| warning.to_string());
+        self.editor.frp.set_warning.emit_event(&message);
+    }
+
     fn refresh_node_views
     (&self, mut trees:HashMap<double_representation::node::Id,NodeTrees>) -> FallibleResult<()> {
         debug!(self.logger, "Updating nodes for {self.controller.graph():?}.");
@@ -364,7 +407,7 @@ impl GraphEditorIntegratedWithControllerModel {
         let displayed_id = self.editor.add_node();
         self.refresh_node_view(displayed_id, info, trees);
         // If position wasn't present in metadata, we must initialize it.
-        if info.metadata.and_then(|md| md.position).is_none() {
+        if info.metadata.as_ref().and_then(|md| md.position).is_none() {
             self.editor.frp.inputs.set_node_position.emit_event(&(displayed_id,default_pos));
         }
         self.node_views.borrow_mut().insert(id, displayed_id);
@@ -377,36 +420,34 @@ impl GraphEditorIntegratedWithControllerModel {
     , endpoint : frp::Source<(graph_editor::NodeId,visualization::Data)>
     , node_id  : graph_editor::NodeId
     ) -> impl FnMut(VisualizationUpdateData) -> futures::future::Ready<()> {
-        // TODO [mwu]
-        //  For now only JSON visualizations are supported, so we can just assume JSON data in the
-        //  binary package.
+        // The data is passed as-is, it is up to the visualization to decode it according to its
+        // input format.
         let logger = self.logger.clone_ref();
         move |update| {
-            match Self::deserialize_visualization_data(update) {
-                Ok (data)  => endpoint.emit((node_id,data)),
-                Err(error) =>
-                    // TODO [mwu]
-                    //  We should consider having the visualization also accept error input.
-                    error!(logger, "Failed to deserialize visualization update. {error}"),
-            }
+            debug!(logger, "Received {update.len()} bytes of visualization data for {node_id}.");
+            let data = visualization::Data::from(update.into_bytes());
+            endpoint.emit((node_id,data));
             futures::future::ready(())
         }
     }
 
-    fn deserialize_visualization_data
-    (data:VisualizationUpdateData) -> FallibleResult<visualization::Data> {
-        let binary  = data.as_ref();
-        let as_text = std::str::from_utf8(binary)?;
-        let as_json : serde_json::Value = serde_json::from_str(as_text)?;
-        Ok(visualization::Data::from(as_json))
-    }
-
     fn refresh_node_view
     (&self, id:graph_editor::NodeId, node:&controller::graph::Node, trees:NodeTrees) {
-        let position = node.metadata.and_then(|md| md.position);
+        let position = node.metadata.as_ref().and_then(|md| md.position);
         if let Some(position) = position {
             self.editor.frp.inputs.set_node_position.emit_event(&(id,position.vector));
         }
+        // The remembered visualization must be set before the node's type, so it is not replaced
+        // with the type's default one.
+        let visualization = node.metadata.as_ref().and_then(|md| md.visualization.clone());
+        let path:Option<visualization::Path> =
+            visualization.and_then(|vis| serde_json::from_value(vis).ok());
+        if let Some(path) = path {
+            if Some(&path) != self.visualization_views.borrow().get(&id) {
+                self.editor.frp.inputs.set_visualization.emit_event(&(id,Some(path.clone())));
+                self.visualization_views.borrow_mut().insert(id,path);
+            }
+        }
         let expression = node.info.expression().repr();
         if Some(&expression) != self.expression_views.borrow().get(&id) {
             let code_and_trees = graph_editor::component::node::port::Expression {
@@ -441,6 +482,8 @@ impl GraphEditorIntegratedWithControllerModel {
     /// graph editor view.
     ///
     /// The computed value information includes the expression type and the target method pointer.
+    /// If the expression is a node, its short value preview and the error which occurred during
+    /// computation are also passed.
     fn refresh_computed_info(&self, id:ExpressionId) {
         let info     = self.lookup_computed_info(&id);
         let info     = info.as_ref();
@@ -450,6 +493,57 @@ impl GraphEditorIntegratedWithControllerModel {
             info.method_pointer.clone().map(graph_editor::MethodPointer)
         });
         self.set_method_pointer(id,method_pointer);
+        let displayed_node = self.node_views.borrow().get_by_left(&id).cloned();
+        if let Some(displayed_node) = displayed_node {
+            let short_value = info.and_then(|info| info.short_value.clone().map(node::ShortValue));
+            self.set_node_short_value(displayed_node,short_value);
+            let node_type = info.and_then(|info| info.typename.clone().map(graph_editor::Type));
+            self.set_node_type(displayed_node,node_type);
+            let error = info.and_then(|info| info.error.as_ref()).map(|error| {
+                node::Error {message:self.error_message(id,error)}
+            });
+            self.set_node_error(displayed_node,error);
+        }
+    }
+
+    /// Describe the error which occurred when computing the given node. If the dataflow error was
+    /// raised by another node, that node's expression is given.
+    fn error_message(&self, node_id:ExpressionId, error:&ExpressionError) -> String {
+        let origin = match error {
+            ExpressionError::Dataflow {origin:Some(origin)} => Some(*origin),
+            _                                               => None,
+        };
+        let origin_node = origin.and_then(|origin| {
+            let nodes = self.controller.graph().nodes().ok()?;
+            nodes.into_iter().find(|node| {
+                node.info.expression().iter_recursive().any(|ast| ast.id == Some(origin))
+            })
+        });
+        match origin_node {
+            Some(origin) if origin.info.id() != node_id => {
+                let expression = origin.info.expression().repr();
+                format!("Dataflow error raised by `{}`.", expression)
+            }
+            _ => error.to_string(),
+        }
+    }
+
+    /// Set given short value preview (or lack of such) on the given node.
+    fn set_node_short_value(&self, id:graph_editor::NodeId, value:Option<node::ShortValue>) {
+        let event = (id,value);
+        self.editor.frp.inputs.set_node_short_value.emit_event(&event);
+    }
+
+    /// Set given type (or lack of such) of the given node's value.
+    fn set_node_type(&self, id:graph_editor::NodeId, typename:Option<graph_editor::Type>) {
+        let event = (id,typename);
+        self.editor.frp.inputs.set_node_type.emit_event(&event);
+    }
+
+    /// Set given error (or lack of such) on the given node.
+    fn set_node_error(&self, id:graph_editor::NodeId, error:Option<node::Error>) {
+        let event = (id,error);
+        self.editor.frp.inputs.set_node_error.emit_event(&event);
     }
 
     /// Set given type (or lack of such) on the given sub-expression.
@@ -514,6 +608,7 @@ impl GraphEditorIntegratedWithControllerModel {
     pub fn on_node_entered(&self, _id:double_representation::node::Id) -> FallibleResult<()> {
         self.editor.frp.deselect_all_nodes.emit_event(&());
         self.request_detaching_all_visualizations();
+        self.refresh_breadcrumbs();
         self.refresh_graph_view()
     }
 
@@ -521,17 +616,48 @@ impl GraphEditorIntegratedWithControllerModel {
     pub fn on_node_exited(&self, id:double_representation::node::Id) -> FallibleResult<()> {
         self.editor.frp.deselect_all_nodes.emit_event(&());
         self.request_detaching_all_visualizations();
+        self.refresh_breadcrumbs();
         self.refresh_graph_view()?;
         let id = self.get_displayed_node_id(id)?;
         self.editor.frp.select_node.emit_event(&id);
         Ok(())
     }
 
+    /// Handle notification received from controller about the execution context having switched
+    /// to another entry point.
+    pub fn on_entry_point_changed(&self) -> FallibleResult<()> {
+        self.editor.frp.deselect_all_nodes.emit_event(&());
+        self.request_detaching_all_visualizations();
+        self.refresh_breadcrumbs();
+        self.refresh_graph_view()
+    }
+
     /// Handle notification received from controller about values having been computed.
     pub fn on_values_computed(&self, expressions:&[ExpressionId]) -> FallibleResult<()> {
         self.refresh_computed_infos(&expressions)
     }
 
+    /// Handle notification received from controller about the execution status having changed.
+    pub fn on_execution_status(&self, status:&ExecutionStatus) -> FallibleResult<()> {
+        if let ExecutionStatus::Failed {message} = status {
+            error!(self.logger,"The execution of the graph failed: {message}");
+        } else {
+            info!(self.logger,"The graph is executed again.");
+        }
+        self.display_execution_status(status);
+        Ok(())
+    }
+
+    /// Display the message of the failed execution in the graph editor, or hide it if the last
+    /// execution did not fail.
+    fn display_execution_status(&self, status:&ExecutionStatus) {
+        let message = match status {
+            ExecutionStatus::Failed {message} => Some(format!("Execution failed: {}",message)),
+            ExecutionStatus::Ok               => None,
+        };
+        self.editor.frp.set_execution_error.emit_event(&message);
+    }
+
     /// Request controller to detach all attached visualizations.
     pub fn request_detaching_all_visualizations(&self) {
         let controller = self.controller.clone_ref();
@@ -555,8 +681,10 @@ impl GraphEditorIntegratedWithControllerModel {
         let result = match notification {
             Some(Notification::Graph(Invalidate))         => self.on_invalidated(),
             Some(Notification::ComputedValueInfo(update)) => self.on_values_computed(update),
+            Some(Notification::ExecutionStatus(status))   => self.on_execution_status(status),
             Some(Notification::EnteredNode(id))           => self.on_node_entered(*id),
             Some(Notification::SteppedOutOfNode(id))      => self.on_node_exited(*id),
+            Some(Notification::EntryPointChanged(_))      => self.on_entry_point_changed(),
             other => {
                 warning!(self.logger,"Handling notification {other:?} is not implemented; \
                     performing full invalidation");
@@ -669,6 +797,18 @@ impl GraphEditorIntegratedWithControllerModel {
         Ok(())
     }
 
+    fn visualization_chosen_in_ui
+    (&self, param:&(graph_editor::NodeId,visualization::Path)) -> FallibleResult<()> {
+        let (displayed_id,path) = param;
+        let id                  = self.get_controller_node_id(*displayed_id)?;
+        self.visualization_views.borrow_mut().insert(*displayed_id,path.clone());
+        let path                = serde_json::to_value(path)?;
+        self.controller.graph().module.with_node_metadata(id, |md| {
+            md.visualization = Some(path);
+        });
+        Ok(())
+    }
+
     fn visualization_disabled_in_ui(&self, node_id:&graph_editor::NodeId) -> FallibleResult<()> {
         debug!(self.logger,"Node editor wants to detach visualization on {node_id}.");
         let id             = self.get_controller_visualization_id(*node_id)?;
@@ -696,7 +836,7 @@ impl GraphEditorIntegratedWithControllerModel {
             }
         };
 
-        crate::executor::global::spawn(detach_action);
+        executor::global::spawn(detach_action);
         Ok(())
     }
 
@@ -724,12 +864,51 @@ impl GraphEditorIntegratedWithControllerModel {
         executor::global::spawn(exit_node_action);
         Ok(())
     }
+
+    fn breadcrumb_selected_in_ui(&self, index:&usize) -> FallibleResult<()> {
+        // The first breadcrumb is the entry point, each next one is a local call on the stack.
+        let count = self.controller.call_stack().len().saturating_sub(*index);
+        debug!(self.logger,"Requesting exiting {count} nodes.");
+        let controller        = self.controller.clone_ref();
+        let logger            = self.logger.clone_ref();
+        let exit_nodes_action = async move {
+            let result = controller.exit_nodes(count).await;
+            debug!(logger,"Exiting nodes result: {result:?}.");
+        };
+        executor::global::spawn(exit_nodes_action);
+        Ok(())
+    }
+
+    fn undo_in_ui(&self, _:&()) -> FallibleResult<()> {
+        debug!(self.logger,"Undoing the last module change.");
+        // The only possible failure is having nothing to undo, which is not an error of UI action.
+        if let Err(err) = self.controller.graph().module.undo() {
+            info!(self.logger,"Cannot undo: {err}");
+        }
+        Ok(())
+    }
+
+    fn redo_in_ui(&self, _:&()) -> FallibleResult<()> {
+        debug!(self.logger,"Redoing the last undone module change.");
+        // The only possible failure is having nothing to redo, which is not an error of UI action.
+        if let Err(err) = self.controller.graph().module.redo() {
+            info!(self.logger,"Cannot redo: {err}");
+        }
+        Ok(())
+    }
 }
 
 
 // === Utilities ===
 
 impl GraphEditorIntegratedWithControllerModel {
+    /// Open a module transaction lasting until the currently processed UI event is handled, so all
+    /// the module edits done by a single user's gesture are undone together.
+    fn group_edits_until_event_handled(&self) {
+        let transaction = self.controller.graph().module.open_transaction();
+        executor::global::spawn(async move { drop(transaction) });
+    }
+
     fn get_controller_node_id
     (&self, displayed_id:graph_editor::NodeId) -> Result<ast::Id, MissingMappingFor> {
         let err = MissingMappingFor::DisplayedNode(displayed_id);
@@ -815,15 +994,45 @@ impl NodeEditor {
         graph_editor.project_name.frp.name.emit(project_name);
         for identifier in identifiers {
             let visualization = self.visualization.load_visualization(&identifier).await;
-            let visualization = visualization.map(|visualization| {
-                graph_editor.frp.register_visualization.emit(Some(visualization));
-            });
-            visualization?;
+            match visualization {
+                Ok(visualization) =>
+                    graph_editor.frp.register_visualization.emit(Some(visualization)),
+                Err(error) =>
+                    warning!(self.logger, "Failed to load visualization {identifier}: {error}"),
+            }
         }
+        self.setup_visualization_notification_handler();
         info!(self.logger, "Initialized.");
         Ok(self)
     }
 
+    /// Reload the visualizations changed in the project's directory, so they replace the already
+    /// registered ones.
+    fn setup_visualization_notification_handler(&self) {
+        use controller::visualization::Notification;
+        let stream     = self.visualization.subscribe();
+        let weak       = Rc::downgrade(&self.graph);
+        let controller = self.visualization.clone_ref();
+        let logger     = self.logger.clone_ref();
+        let handler    = process_stream_with_handle(stream,weak,move |notification,graph| {
+            let controller = controller.clone_ref();
+            let logger     = logger.clone_ref();
+            async move {
+                let Notification::Changed(identifier) = notification;
+                info!(logger, "Reloading visualization {identifier}.");
+                match controller.load_visualization(&identifier).await {
+                    Ok(visualization) => {
+                        let endpoint = &graph.graph_editor().frp.register_visualization;
+                        endpoint.emit(Some(visualization))
+                    }
+                    Err(error) =>
+                        warning!(logger, "Failed to reload visualization {identifier}: {error}"),
+                }
+            }
+        });
+        executor::global::spawn(handler);
+    }
+
     /// The path to the module, which graph is currently displayed.
     pub fn displayed_module(&self) -> model::module::Path {
         self.graph.model.controller.graph().module.path.clone_ref()
@@ -67,12 +67,14 @@ impl NodeSearcher {
                 let position      = node_searcher.display_object.position();
                 let position      = position - node_searcher.node_editor.position();
                 let position      = Some(Position{vector:Vector2::new(position.x,position.y)});
-                let metadata      = Some(NodeMetadata{position});
+                let metadata      = Some(NodeMetadata{position,..default()});
                 let id            = None;
                 let location_hint = LocationHint::End;
                 let expression    = expression.to_string();
                 let new_node      = NewNodeInfo { expression,metadata,id,location_hint };
                 node_searcher.node_editor.graph.controller().graph().add_node(new_node);
+                // The node is accepted, so the imports added by searcher should stay.
+                node_searcher.controller.set(None);
                 node_searcher.hide();
             } else {
                 // Keep only one line.
@@ -89,7 +91,8 @@ impl NodeSearcher {
             self.display_object.add_child(&self.text_field.display_object());
             self.text_field.clear_content();
             self.text_field.set_focus();
-            let module     = self.node_editor.displayed_module();
+            let module     = self.node_editor.graph.controller().graph().module;
+            let module     = controller::Module::new_for_model(&self.logger,module,&self.project);
             //TODO[ao]: Now we use some predefined location, until this task will be done:
             // https://github.com/enso-org/ide/issues/653 . This code should be replaced with
             // the proper Searcher view integration anyway.
@@ -118,6 +121,11 @@ impl NodeSearcher {
     pub fn hide(&mut self) {
         if self.is_shown() {
             self.text_field.clear_content();
+            if let Some(controller) = self.controller.get() {
+                if let Err(err) = controller.abandon_node() {
+                    error!(self.logger,"Failed to remove imports of abandoned node: {err}");
+                }
+            }
             self.controller.set(None);
             //FIXME:Use remove_child(&text_field) when replaced by TextField 2.0
             self.display_object.remove_child(&self.text_field.display_object());
@@ -3,6 +3,9 @@
 
 use crate::prelude::*;
 
+use crate::controller::graph::executed::MetadataKey;
+use crate::model::execution_context::EntryPoint;
+use crate::model::execution_context::LocalCall;
 use crate::model::module::Path as ModulePath;
 use crate::view::layout::ViewLayout;
 
@@ -42,6 +45,11 @@ pub const INITIAL_MODULE_NAME:&str = "Main";
 /// This is the definition whose graph will be opened on IDE start.
 pub const MAIN_DEFINITION_NAME:&str = "main";
 
+/// The name under which the execution context of the initially opened graph is stored in the
+/// project metadata. The contexts of the other splits are stored under the splits' identifiers,
+/// which are kept across sessions.
+pub const MAIN_CONTEXT_NAME:&str = "main";
+
 
 
 // ===================
@@ -54,13 +62,15 @@ shared! { ProjectView
     /// GraphEditor.
     #[derive(Debug)]
     pub struct ProjectViewData {
+        logger            : Logger,
         application       : Application,
         layout            : ViewLayout,
         resize_callback   : Option<callback::Handle>,
         model             : Rc<model::Project>,
         keyboard          : Keyboard,
         keyboard_bindings : KeyboardFrpBindings,
-        keyboard_actions  : keyboard::Actions
+        keyboard_actions  : keyboard::Actions,
+        open_split_action : Option<callback::Handle>,
     }
 
     impl {
@@ -76,6 +86,14 @@ pub fn initial_module_path(project:&model::Project) -> FallibleResult<ModulePath
     project.module_path_from_qualified_name(&[INITIAL_MODULE_NAME])
 }
 
+/// Returns the key of the execution context with the given name in the project metadata, which
+/// are stored in the initially opened module.
+pub fn metadata_key(project:&model::Project, name:impl Str) -> FallibleResult<MetadataKey> {
+    let module = initial_module_path(project)?;
+    let name   = name.into();
+    Ok(MetadataKey {module,name})
+}
+
 impl ProjectView {
     /// Create a new ProjectView.
     pub async fn new(logger:impl AnyLogger, model:Rc<model::Project>)
@@ -83,10 +101,23 @@ impl ProjectView {
         let logger            = Logger::sub(logger,"ProjectView");
         let module_path       = initial_module_path(&model)?;
         let file_path         = module_path.file_path().clone();
-        let text_controller   = controller::Text::new(&logger,&*model,file_path).await?;
+        let text_controller   = controller::Text::new(&logger,&*model,file_path);
         let method            = module_path.method_pointer(MAIN_DEFINITION_NAME);
         let graph_controller  = controller::ExecutedGraph::new(&logger,model.clone(),method);
-        let graph_controller  = graph_controller.await?;
+        let main_context_key  = metadata_key(&model,MAIN_CONTEXT_NAME)?;
+        let graph_controller  = async {
+            let graph_controller = graph_controller.await?;
+            if let Err(err) = graph_controller.restore_from_metadata(main_context_key).await {
+                warning!(logger,"Failed to restore the execution context from the previous \
+                    session: {err}");
+            }
+            FallibleResult::Ok(graph_controller)
+        };
+        // The controllers are created in parallel, so their requests to the Language Server may
+        // be sent in batches.
+        let controllers       = futures::future::try_join(text_controller,graph_controller);
+        let controllers       = model.language_server_rpc.batch_each_poll(controllers);
+        let (text_controller,graph_controller) = controllers.await?;
         let application       = Application::new(&web::get_html_element_by_id("root").unwrap());
         Self::setup_components(&application);
         Self::setup_theme(&application);
@@ -100,9 +131,12 @@ impl ProjectView {
         let visualization_controller = model.visualization.clone();
         let layout = ViewLayout::new(&logger,&mut keyboard_actions,&application, text_controller,
             graph_controller,visualization_controller,model.clone_ref(),&mut fonts).await?;
-        let data = ProjectViewData {application,layout,resize_callback,model,keyboard,
-            keyboard_bindings,keyboard_actions};
-        Ok(Self::new_from_data(data).init())
+        let open_split_action = None;
+        let data = ProjectViewData {logger,application,layout,resize_callback,model,keyboard,
+            keyboard_bindings,keyboard_actions,open_split_action};
+        let view = Self::new_from_data(data).init();
+        view.restore_splits().await;
+        Ok(view)
     }
 
     fn init(self) -> Self {
@@ -116,6 +150,26 @@ impl ProjectView {
             }
         );
         self.with_borrowed(move |data| data.resize_callback = Some(resize_callback));
+        self.init_split_keyboard()
+    }
+
+    fn init_split_keyboard(self) -> Self {
+        let weak              = self.downgrade();
+        let keys              = &[keyboard::Key::Control,keyboard::Key::Character("\\".into())];
+        let open_split_action = self.with_borrowed(|data| {
+            data.keyboard_actions.add_action(keys, move || {
+                if let Some(this) = weak.upgrade() {
+                    executor::global::spawn(async move {
+                        if let Err(err) = this.fork_focused_split().await {
+                            this.with_borrowed(|data| {
+                                error!(data.logger,"Failed to open a new split: {err}");
+                            });
+                        }
+                    });
+                }
+            })
+        });
+        self.with_borrowed(move |data| data.open_split_action = Some(open_split_action));
         self
     }
 
@@ -139,6 +193,77 @@ impl ProjectView {
         app.themes.set_enabled(&["dark"]);
     }
 
+    /// Open a new execution context of the given entry point, enter the given local calls, and
+    /// display its graph in a new split, next to the already opened ones.
+    ///
+    /// The entry point may be any method of the project's modules, called with some argument
+    /// expressions, see `model::Project::entry_points`. The execution context is stored in the
+    /// project metadata under a new split identifier, so the split is opened again in the next
+    /// session.
+    pub async fn open_execution_context
+    (&self, entry_point:impl Into<EntryPoint>, call_stack:Vec<LocalCall>) -> FallibleResult<()> {
+        let split_id = Uuid::new_v4().to_string();
+        self.open_split(split_id,entry_point.into(),call_stack).await
+    }
+
+    /// Open a split with a new execution context of the given entry point and call stack. The
+    /// execution context is stored in the project metadata under the given split identifier.
+    async fn open_split
+    (&self, split_id:String, entry_point:EntryPoint, call_stack:Vec<LocalCall>)
+    -> FallibleResult<()> {
+        let (logger,model,layout) = self.with_borrowed(|data| {
+            (data.logger.clone_ref(),data.model.clone_ref(),data.layout.clone_ref())
+        });
+        let graph_controller = controller::ExecutedGraph::new(&logger,model.clone_ref(),entry_point)
+            .await?;
+        for call in call_stack {
+            graph_controller.enter_call(call).await?;
+        }
+        let key = metadata_key(&model,split_id)?;
+        graph_controller.store_in_metadata(key).await;
+        layout.open_split(graph_controller).await
+    }
+
+    /// Open again the splits stored in the project metadata in the previous session, besides the
+    /// main one. The splits which cannot be opened are logged and removed from the metadata.
+    async fn restore_splits(&self) {
+        let (logger,model) = self.with_borrowed(|data| {
+            (data.logger.clone_ref(),data.model.clone_ref())
+        });
+        let module = match initial_module_path(&model) {
+            Ok(path) => model.module(path).await,
+            Err(err) => Err(err),
+        };
+        let module = match module {
+            Ok(module) => module,
+            Err(err)   => {
+                warning!(logger,"Failed to restore the splits: {err}");
+                return
+            }
+        };
+        let contexts = module.project_metadata().execution_contexts;
+        let stored   = contexts.into_iter().filter(|(name,_)| name != MAIN_CONTEXT_NAME);
+        for (name,context) in stored {
+            let result = self.open_split(name.clone(),context.entry_point,context.call_stack);
+            if let Err(err) = result.await {
+                warning!(logger,"Failed to restore the split {name}: {err}");
+                module.with_project_metadata(|md| { md.execution_contexts.remove(&name); });
+            }
+        }
+    }
+
+    /// Open a new split with the entry point and the call stack of the focused split.
+    ///
+    /// The new split has its own execution context, so it may then enter other nodes or switch to
+    /// another entry point, e.g. to compare two call sites of the same method.
+    pub async fn fork_focused_split(&self) -> FallibleResult<()> {
+        let layout      = self.with_borrowed(|data| data.layout.clone_ref());
+        let controller  = layout.focused_node_editor().graph.controller().clone_ref();
+        let entry_point = controller.entry_point();
+        let call_stack  = controller.call_stack();
+        self.open_execution_context(entry_point,call_stack).await
+    }
+
     /// Forgets ProjectView, so it won't get dropped when it goes out of scope.
     pub fn forget(self) {
         std::mem::forget(self)
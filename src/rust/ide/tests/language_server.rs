@@ -294,7 +294,7 @@ async fn setup_project() -> Project {
         (&logger,&pm,name).await.expect("Couldn't get most recent or create new project.");
     let error_msg = "Couldn't open project";
     let pm = Rc::new(pm);
-    ide::IdeInitializer::open_project(&logger,pm,project_metadata).await.expect(error_msg)
+    ide::IdeInitializer::open_project(&logger,pm,project_metadata,None).await.expect(error_msg)
 }
 
 //#[wasm_bindgen_test::wasm_bindgen_test(async)]